@@ -0,0 +1,295 @@
+//! Deterministic synthetic vcf generator shared by benchmarks
+
+/* std use */
+
+/* crate use */
+
+/* project use */
+
+/// Type and number of generated INFO fields, used in rotation
+const INFO_KINDS: &[(&str, &str)] = &[
+    ("Integer", "1"),
+    ("Float", "A"),
+    ("String", "1"),
+    ("Integer", "R"),
+    ("Float", "."),
+    ("Flag", "0"),
+];
+
+/// Type and number of generated FORMAT fields, GT is always the first one
+const FORMAT_KINDS: &[(&str, &str)] = &[
+    ("Integer", "1"),
+    ("Integer", "R"),
+    ("Float", "1"),
+    ("Integer", "G"),
+    ("String", "1"),
+];
+
+const NUCLEOTIDES: &[u8] = b"ACGT";
+
+/// Small splitmix64 random generator, same seed produce same vcf on all platform
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, max: u64) -> u64 {
+        self.next() % max.max(1)
+    }
+
+    fn chance(&mut self, probability: f64) -> bool {
+        ((self.next() >> 11) as f64 / (1u64 << 53) as f64) < probability
+    }
+}
+
+/// Configuration of synthetic vcf
+#[derive(Debug, Clone)]
+pub struct Generator {
+    samples: usize,
+    records: usize,
+    infos: usize,
+    formats: usize,
+    density: f64,
+    multiallelic: f64,
+    chromosomes: usize,
+    seed: u64,
+}
+
+impl Default for Generator {
+    fn default() -> Self {
+        Self {
+            samples: 10,
+            records: 1_000,
+            infos: 6,
+            formats: 5,
+            density: 0.8,
+            multiallelic: 0.1,
+            chromosomes: 3,
+            seed: 42,
+        }
+    }
+}
+
+#[allow(dead_code)]
+impl Generator {
+    /// Create a Generator with default value
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set number of samples
+    pub fn set_samples(mut self, value: usize) -> Self {
+        self.samples = value;
+        self
+    }
+
+    /// Set number of records
+    pub fn set_records(mut self, value: usize) -> Self {
+        self.records = value;
+        self
+    }
+
+    /// Set number of INFO fields declared in header
+    pub fn set_infos(mut self, value: usize) -> Self {
+        self.infos = value;
+        self
+    }
+
+    /// Set number of FORMAT fields declared in header, GT included
+    pub fn set_formats(mut self, value: usize) -> Self {
+        self.formats = value;
+        self
+    }
+
+    /// Set probability of an INFO field or a FORMAT value to be present
+    pub fn set_density(mut self, value: f64) -> Self {
+        self.density = value;
+        self
+    }
+
+    /// Set probability of a record to have more than one alternative allele
+    pub fn set_multiallelic(mut self, value: f64) -> Self {
+        self.multiallelic = value;
+        self
+    }
+
+    /// Set number of chromosomes, records are distributed equally
+    pub fn set_chromosomes(mut self, value: usize) -> Self {
+        self.chromosomes = value;
+        self
+    }
+
+    /// Set seed of random generator
+    pub fn set_seed(mut self, value: u64) -> Self {
+        self.seed = value;
+        self
+    }
+
+    /// Get number of records
+    pub fn records(&self) -> usize {
+        self.records
+    }
+
+    /// Write vcf in `output`
+    pub fn write<W>(&self, output: &mut W) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        let mut random = Random(self.seed);
+
+        writeln!(output, "##fileformat=VCFv4.3")?;
+        writeln!(output, "##FILTER=<ID=q10,Description=\"quality below 10\">")?;
+        for chromosome in 0..self.chromosomes {
+            writeln!(output, "##contig=<ID=chr{}>", chromosome + 1)?;
+        }
+        for index in 0..self.infos {
+            let (ty, number) = INFO_KINDS[index % INFO_KINDS.len()];
+            writeln!(
+                output,
+                "##INFO=<ID=I{},Number={},Type={},Description=\"generated info\">",
+                index, number, ty
+            )?;
+        }
+        if self.formats > 0 {
+            writeln!(
+                output,
+                "##FORMAT=<ID=GT,Number=1,Type=String,Description=\"genotype\">"
+            )?;
+        }
+        for index in 1..self.formats {
+            let (ty, number) = FORMAT_KINDS[(index - 1) % FORMAT_KINDS.len()];
+            writeln!(
+                output,
+                "##FORMAT=<ID=F{},Number={},Type={},Description=\"generated format\">",
+                index, number, ty
+            )?;
+        }
+        write!(output, "#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO")?;
+        if self.formats > 0 && self.samples > 0 {
+            write!(output, "\tFORMAT")?;
+            for sample in 0..self.samples {
+                write!(output, "\tS{}", sample)?;
+            }
+        }
+        writeln!(output)?;
+
+        let by_chromosome = self.records.div_ceil(self.chromosomes.max(1));
+        let mut line = String::new();
+        for record in 0..self.records {
+            line.clear();
+            self.record(&mut random, record, by_chromosome, &mut line);
+            output.write_all(line.as_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    /// Generate vcf in memory
+    pub fn to_vec(&self) -> Vec<u8> {
+        let mut output = Vec::new();
+        self.write(&mut output).unwrap();
+        output
+    }
+
+    fn record(&self, random: &mut Random, index: usize, by_chromosome: usize, line: &mut String) {
+        use std::fmt::Write as _;
+
+        let alts = if random.chance(self.multiallelic) {
+            2 + random.below(2) as usize
+        } else {
+            1
+        };
+        let reference = NUCLEOTIDES[random.below(4) as usize] as char;
+        let alternates = NUCLEOTIDES
+            .iter()
+            .map(|n| *n as char)
+            .filter(|n| *n != reference)
+            .take(alts)
+            .map(String::from)
+            .collect::<Vec<_>>()
+            .join(",");
+
+        write!(
+            line,
+            "chr{}\t{}\t.\t{}\t{}\t{}\t{}\t",
+            index / by_chromosome + 1,
+            (index % by_chromosome) * 100 + 1 + random.below(100) as usize,
+            reference,
+            alternates,
+            random.below(100),
+            if random.chance(0.9) { "PASS" } else { "q10" },
+        )
+        .unwrap();
+
+        let mut infos = Vec::new();
+        for field in 0..self.infos {
+            if !random.chance(self.density) {
+                continue;
+            }
+            let value = match INFO_KINDS[field % INFO_KINDS.len()] {
+                ("Flag", _) => {
+                    infos.push(format!("I{}", field));
+                    continue;
+                }
+                (ty, number) => self.values(random, ty, number, alts),
+            };
+            infos.push(format!("I{}={}", field, value));
+        }
+        if infos.is_empty() {
+            line.push('.');
+        } else {
+            line.push_str(&infos.join(";"));
+        }
+
+        if self.formats > 0 && self.samples > 0 {
+            line.push_str("\tGT");
+            for field in 1..self.formats {
+                write!(line, ":F{}", field).unwrap();
+            }
+
+            for _ in 0..self.samples {
+                let first = random.below(alts as u64 + 1);
+                let second = random.below(alts as u64 + 1);
+                let phase = if random.chance(0.5) { '|' } else { '/' };
+                write!(line, "\t{}{}{}", first, phase, second).unwrap();
+
+                for field in 1..self.formats {
+                    line.push(':');
+                    if random.chance(self.density) {
+                        let (ty, number) = FORMAT_KINDS[(field - 1) % FORMAT_KINDS.len()];
+                        line.push_str(&self.values(random, ty, number, alts));
+                    } else {
+                        line.push('.');
+                    }
+                }
+            }
+        }
+
+        line.push('\n');
+    }
+
+    fn values(&self, random: &mut Random, ty: &str, number: &str, alts: usize) -> String {
+        let count = match number {
+            "A" => alts,
+            "R" => alts + 1,
+            "G" => (alts + 1) * (alts + 2) / 2,
+            "." => 1 + random.below(3) as usize,
+            _ => 1,
+        };
+
+        (0..count)
+            .map(|_| match ty {
+                "Integer" => random.below(1000).to_string(),
+                "Float" => format!("{:.3}", random.below(100_000) as f64 / 1000.0),
+                _ => format!("v{}", random.below(50)),
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
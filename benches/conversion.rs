@@ -0,0 +1,179 @@
+//! Benchmark of Record2Chunk and parquet writer on synthetic vcf
+
+/* std use */
+
+/* crate use */
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use parquet::basic::Compression;
+use parquet::file::properties::WriterVersion;
+
+/* project use */
+use vcf2parquet::options;
+use vcf2parquet::records;
+use vcf2parquet::schema;
+
+mod common;
+
+const RECORDS: usize = 1_000;
+const BATCH_SIZE: usize = 500;
+
+/// Compare vcf layout: number of samples, fields density and multi-allelic ratio
+fn record2chunk(c: &mut Criterion) {
+    let options = options::Options::new().set_info_optional(true);
+
+    let mut group = c.benchmark_group("record2chunk");
+    group.throughput(Throughput::Elements(RECORDS as u64));
+
+    for (name, generator) in [
+        ("sites", common::Generator::new().set_samples(0)),
+        ("samples_10", common::Generator::new().set_samples(10)),
+        ("samples_100", common::Generator::new().set_samples(100)),
+        (
+            "sparse_100",
+            common::Generator::new().set_samples(100).set_density(0.1),
+        ),
+        (
+            "multiallelic_100",
+            common::Generator::new()
+                .set_samples(100)
+                .set_multiallelic(0.5),
+        ),
+        (
+            "info_60",
+            common::Generator::new().set_samples(10).set_infos(60),
+        ),
+    ] {
+        let vcf = generator.set_records(RECORDS).to_vec();
+
+        group.bench_with_input(BenchmarkId::from_parameter(name), &vcf, |b, vcf| {
+            b.iter(|| {
+                vcf2parquet::vcf2batches(&vcf[..], BATCH_SIZE, &options)
+                    .unwrap()
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+/// Compare conversion of all columns with conversion of a few columns, only selected fields are decoded
+fn selection(c: &mut Criterion) {
+    let vcf = common::Generator::new()
+        .set_samples(100)
+        .set_records(RECORDS)
+        .to_vec();
+
+    let mut group = c.benchmark_group("selection");
+    group.throughput(Throughput::Elements(RECORDS as u64));
+
+    for (name, columns) in [
+        ("all", vec![]),
+        ("core", vec!["chromosome", "position", "alternate"]),
+        ("info", vec!["position", "info_I0", "info_I3"]),
+        ("sample", vec!["position", "format_S0_GT", "format_S0_F2"]),
+    ] {
+        let options = options::Options::new()
+            .set_info_optional(true)
+            .set_columns(columns.iter().map(|c| c.to_string()).collect());
+
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                vcf2parquet::vcf2batches(&vcf[..], BATCH_SIZE, &options)
+                    .unwrap()
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+/// Compare reading of records for the same selection, records fully parsed or read lazily and reduced to selected fields before parsing
+fn lazy(c: &mut Criterion) {
+    let vcf = common::Generator::new()
+        .set_samples(100)
+        .set_records(RECORDS)
+        .to_vec();
+
+    let mut group = c.benchmark_group("lazy");
+    group.throughput(Throughput::Elements(RECORDS as u64));
+
+    for (name, columns) in [
+        ("core", vec!["chromosome", "position", "alternate"]),
+        ("info", vec!["position", "info_I0", "info_I3"]),
+        ("sample", vec!["position", "format_S0_GT", "format_S0_F2"]),
+    ] {
+        let options = options::Options::new()
+            .set_info_optional(true)
+            .set_columns(columns.iter().map(|c| c.to_string()).collect());
+
+        let mut reader = noodles::vcf::Reader::new(&vcf[..]);
+        let header: noodles::vcf::Header = reader.read_header().unwrap();
+        let schema = schema::from_header_with_options(&header, &options).unwrap();
+        let selection = records::Selection::new(&header, &schema, &options);
+
+        for (mode, selection) in [("full", None), ("lazy", selection)] {
+            group.bench_function(BenchmarkId::new(mode, name), |b| {
+                b.iter(|| {
+                    let mut reader = noodles::vcf::Reader::new(&vcf[..]);
+                    reader.read_header().unwrap();
+
+                    let mut records = records::Records::new(reader, header.clone());
+                    records.set_selection(selection.clone());
+                    records.collect::<std::io::Result<Vec<_>>>().unwrap()
+                })
+            });
+        }
+    }
+
+    group.finish();
+}
+
+/// Compare parquet writer options on the same vcf
+fn writer(c: &mut Criterion) {
+    let vcf = common::Generator::new()
+        .set_samples(20)
+        .set_records(RECORDS)
+        .to_vec();
+
+    let mut group = c.benchmark_group("writer");
+    group.throughput(Throughput::Bytes(vcf.len() as u64));
+
+    let options = options::Options::new().set_info_optional(true);
+    let dictionary = options.clone().set_dictionary(vec![
+        "chromosome".to_string(),
+        "reference".to_string(),
+        "alternate".to_string(),
+        "filter".to_string(),
+    ]);
+    for (name, compression, options) in [
+        ("uncompressed", Compression::UNCOMPRESSED, options.clone()),
+        ("snappy", Compression::SNAPPY, options.clone()),
+        ("lz4", Compression::LZ4, options.clone()),
+        ("snappy_dictionary", Compression::SNAPPY, dictionary),
+    ] {
+        group.bench_function(name, |b| {
+            b.iter(|| {
+                let mut output = Vec::new();
+                vcf2parquet::vcf2parquet_with_options(
+                    &mut &vcf[..],
+                    &mut output,
+                    BATCH_SIZE,
+                    compression,
+                    &options,
+                    WriterVersion::PARQUET_1_0,
+                )
+                .unwrap();
+                output
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, record2chunk, selection, lazy, writer);
+criterion_main!(benches);
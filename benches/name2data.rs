@@ -0,0 +1,136 @@
+//! Benchmark of record conversion on a wide multi-sample vcf
+
+/* std use */
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/* crate use */
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+
+/* project use */
+use vcf2parquet::name2data;
+use vcf2parquet::options;
+use vcf2parquet::schema;
+
+mod common;
+
+const RECORDS: usize = 200;
+
+/// System allocator that count allocations, to report allocations by record
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl std::alloc::GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        std::alloc::System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+        std::alloc::System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: std::alloc::Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        std::alloc::System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// Print mean number of allocations by record done by add_record
+fn allocations(
+    name: &str,
+    records: &[noodles::vcf::Record],
+    header: &noodles::vcf::Header,
+    schema: &arrow::datatypes::Schema,
+    plan: &std::sync::Arc<name2data::Plan>,
+    options: &options::Options,
+) {
+    let records = records.to_vec();
+    let mut data = name2data::Name2Data::new(RECORDS * 2, schema, plan.clone()).unwrap();
+
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    for record in records {
+        data.add_record(record, header, options).unwrap();
+    }
+    let after = ALLOCATIONS.load(Ordering::Relaxed);
+
+    eprintln!(
+        "add_record/{}: {:.1} allocations by record",
+        name,
+        (after - before) as f64 / RECORDS as f64
+    );
+}
+
+fn add_record(c: &mut Criterion) {
+    let default = options::Options::new().set_info_optional(true);
+    let core = default
+        .clone()
+        .set_variant_id(true)
+        .set_variant_hash(true)
+        .set_classify(true)
+        .set_sv(true);
+
+    let mut group = c.benchmark_group("add_record");
+    group.throughput(Throughput::Elements(RECORDS as u64));
+
+    for (samples, density, options, name) in [
+        (1, 0.8, &default, "density"),
+        (200, 0.8, &default, "density"),
+        (200, 0.2, &default, "density"),
+        (1, 0.8, &core, "core"),
+    ] {
+        let vcf = common::Generator::new()
+            .set_samples(samples)
+            .set_records(RECORDS)
+            .set_density(density)
+            .set_multiallelic(0.25)
+            .to_vec();
+
+        let mut reader = noodles::vcf::Reader::new(&vcf[..]);
+        let header: noodles::vcf::Header = reader.read_header().unwrap();
+        let records = reader
+            .records(&header)
+            .collect::<std::io::Result<Vec<_>>>()
+            .unwrap();
+
+        let schema = schema::from_header_with_options(&header, options).unwrap();
+        let plan = std::sync::Arc::new(name2data::Plan::new(&header, &schema, options));
+        let function = format!("{}_{}", name, density);
+
+        allocations(
+            &format!("{}/{}", function, samples),
+            &records,
+            &header,
+            &schema,
+            &plan,
+            options,
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new(function, samples),
+            &records,
+            |b, records| {
+                b.iter_batched(
+                    || records.clone(),
+                    |records| {
+                        let mut data =
+                            name2data::Name2Data::new(RECORDS * 2, &schema, plan.clone()).unwrap();
+                        for record in records {
+                            data.add_record(record, &header, options).unwrap();
+                        }
+                        data.into_arc()
+                    },
+                    BatchSize::LargeInput,
+                )
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, add_record);
+criterion_main!(benches);
@@ -0,0 +1,68 @@
+//! Write a deterministic synthetic vcf on stdout, same generator as benchmarks
+//!
+//! cargo run --release --example synthetic_vcf -- --samples 1000 --records 100000 > large.vcf
+
+/* std use */
+use std::io::Write as _;
+
+/* crate use */
+use clap::Parser as _;
+
+/* project use */
+#[path = "../benches/common/mod.rs"]
+mod common;
+
+#[derive(clap::Parser, std::fmt::Debug)]
+struct Command {
+    /// Number of samples
+    #[clap(long, default_value_t = 10)]
+    samples: usize,
+
+    /// Number of records
+    #[clap(long, default_value_t = 1_000)]
+    records: usize,
+
+    /// Number of INFO fields
+    #[clap(long, default_value_t = 6)]
+    infos: usize,
+
+    /// Number of FORMAT fields, GT included
+    #[clap(long, default_value_t = 5)]
+    formats: usize,
+
+    /// Probability of an INFO field or a FORMAT value to be present
+    #[clap(long, default_value_t = 0.8)]
+    density: f64,
+
+    /// Probability of a record to have more than one alternative allele
+    #[clap(long, default_value_t = 0.1)]
+    multiallelic: f64,
+
+    /// Number of chromosomes
+    #[clap(long, default_value_t = 3)]
+    chromosomes: usize,
+
+    /// Seed of random generator
+    #[clap(long, default_value_t = 42)]
+    seed: u64,
+}
+
+fn main() -> std::io::Result<()> {
+    let params = Command::parse();
+
+    let stdout = std::io::stdout();
+    let mut output = std::io::BufWriter::new(stdout.lock());
+
+    common::Generator::new()
+        .set_samples(params.samples)
+        .set_records(params.records)
+        .set_infos(params.infos)
+        .set_formats(params.formats)
+        .set_density(params.density)
+        .set_multiallelic(params.multiallelic)
+        .set_chromosomes(params.chromosomes)
+        .set_seed(params.seed)
+        .write(&mut output)?;
+
+    output.flush()
+}
@@ -5,6 +5,7 @@ use vcf2parquet as lib;
 
 /* crate use */
 use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyCapsule};
 
 /* mod decalaration */
 mod error;
@@ -24,6 +25,26 @@ pub enum Compression {
     Zstd,
 }
 
+impl From<Compression> for parquet::basic::Compression {
+    fn from(compression: Compression) -> Self {
+        match compression {
+            Compression::Uncompressed => parquet::basic::Compression::UNCOMPRESSED,
+            Compression::Snappy => parquet::basic::Compression::SNAPPY,
+            Compression::Gzip => {
+                parquet::basic::Compression::GZIP(parquet::basic::GzipLevel::default())
+            }
+            Compression::Lzo => parquet::basic::Compression::LZO,
+            Compression::Brotli => {
+                parquet::basic::Compression::BROTLI(parquet::basic::BrotliLevel::default())
+            }
+            Compression::Lz4 => parquet::basic::Compression::LZ4,
+            Compression::Zstd => {
+                parquet::basic::Compression::ZSTD(parquet::basic::ZstdLevel::default())
+            }
+        }
+    }
+}
+
 #[pyclass]
 #[derive(Debug, Clone, Copy)]
 pub enum ParquetVersion {
@@ -31,56 +52,507 @@ pub enum ParquetVersion {
     V1_0,
 }
 
+impl From<ParquetVersion> for parquet::file::properties::WriterVersion {
+    fn from(version: ParquetVersion) -> Self {
+        match version {
+            ParquetVersion::V2_0 => parquet::file::properties::WriterVersion::PARQUET_2_0,
+            ParquetVersion::V1_0 => parquet::file::properties::WriterVersion::PARQUET_1_0,
+        }
+    }
+}
+
+/// Python file-like object, used as reader or writer
+struct PyFileLike {
+    obj: PyObject,
+    // bytes read but not yet return, a file open in text mode can return more bytes than requested
+    pending: Vec<u8>,
+}
+
+impl PyFileLike {
+    fn new(obj: PyObject) -> Self {
+        Self {
+            obj,
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl std::io::Read for PyFileLike {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.pending.is_empty() {
+            self.pending = Python::with_gil(|py| -> std::io::Result<Vec<u8>> {
+                let data = self
+                    .obj
+                    .call_method1(py, "read", (buf.len(),))
+                    .map_err(py2io)?;
+
+                // file open in text mode return str, read(n) return n characters
+                match data.extract::<&[u8]>(py) {
+                    Ok(bytes) => Ok(bytes.to_vec()),
+                    Err(_) => Ok(data.extract::<String>(py).map_err(py2io)?.into_bytes()),
+                }
+            })?;
+        }
+
+        let length = self.pending.len().min(buf.len());
+        buf[..length].copy_from_slice(&self.pending[..length]);
+        self.pending.drain(..length);
+
+        Ok(length)
+    }
+}
+
+impl std::io::Write for PyFileLike {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        Python::with_gil(|py| {
+            self.obj
+                .call_method1(py, "write", (PyBytes::new(py, buf),))
+                .map_err(py2io)?;
+
+            Ok(buf.len())
+        })
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Python::with_gil(|py| {
+            if self.obj.as_ref(py).hasattr("flush").map_err(py2io)? {
+                self.obj.call_method0(py, "flush").map_err(py2io)?;
+            }
+
+            Ok(())
+        })
+    }
+}
+
+fn py2io(error: PyErr) -> std::io::Error {
+    std::io::Error::other(error.to_string())
+}
+
+/// Open a path or a file-like object as a decompressed buffered reader
+fn reader(input: &PyAny, read_buffer: usize) -> PyResult<Box<dyn std::io::BufRead + Send>> {
+    let raw: Box<dyn std::io::Read + Send> = if let Ok(path) = input.extract::<std::path::PathBuf>()
+    {
+        Box::new(std::fs::File::open(path)?)
+    } else if input.hasattr("read")? {
+        Box::new(PyFileLike::new(input.into()))
+    } else {
+        return Err(pyo3::exceptions::PyTypeError::new_err(
+            "input must be a path or a file-like object with read method",
+        ));
+    };
+
+    let (file, _) = niffler::send::get_reader(raw).map_err(PyVcf2ParquetErr::Niffle)?;
+
+    Ok(Box::new(std::io::BufReader::with_capacity(
+        read_buffer,
+        file,
+    )))
+}
+
+/// Open a path or a file-like object as writer
+fn writer(output: &PyAny) -> PyResult<Box<dyn std::io::Write + Send>> {
+    if let Ok(path) = output.extract::<std::path::PathBuf>() {
+        Ok(Box::new(std::fs::File::create(path)?))
+    } else if output.hasattr("write")? {
+        Ok(Box::new(PyFileLike::new(output.into())))
+    } else {
+        Err(pyo3::exceptions::PyTypeError::new_err(
+            "output must be a path or a file-like object with write method",
+        ))
+    }
+}
+
+/// Conversion options, each keyword argument match a command line option
+#[pyclass]
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    inner: lib::options::Options,
+    compact_integer: bool,
+    sample_qc: Option<std::path::PathBuf>,
+}
+
+#[pymethods]
+impl Options {
+    #[new]
+    #[pyo3(signature = (info_optional=false,dictionary=None,type_hints=None,info_float64=false,compact_integer=false,schema_override=None,undeclared=None,lenient=false,columns=None,samples=None,regions=None,info_name=None,format_name=None,sanitize_names=false,lowercase_names=false,memory_budget=None,variant_id=false,variant_hash=false,normalize=false,reference=None,classify=false,sv=false,context=0,check_reference=false,fill_reference=false,genotype_stats=false,sample_qc=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        info_optional: bool,
+        dictionary: Option<Vec<String>>,
+        type_hints: Option<Vec<String>>,
+        info_float64: bool,
+        compact_integer: bool,
+        schema_override: Option<std::path::PathBuf>,
+        undeclared: Option<&str>,
+        lenient: bool,
+        columns: Option<Vec<String>>,
+        samples: Option<Vec<String>>,
+        regions: Option<Vec<String>>,
+        info_name: Option<String>,
+        format_name: Option<String>,
+        sanitize_names: bool,
+        lowercase_names: bool,
+        memory_budget: Option<usize>,
+        variant_id: bool,
+        variant_hash: bool,
+        normalize: bool,
+        reference: Option<std::path::PathBuf>,
+        classify: bool,
+        sv: bool,
+        context: usize,
+        check_reference: bool,
+        fill_reference: bool,
+        genotype_stats: bool,
+        sample_qc: Option<std::path::PathBuf>,
+    ) -> PyResult<Self> {
+        let type_hints = type_hints
+            .unwrap_or_default()
+            .iter()
+            .map(|t| t.parse())
+            .collect::<Result<Vec<lib::options::TypeHint>, _>>()
+            .map_err(PyVcf2ParquetErr::from)?;
+
+        let overrides = schema_override
+            .map(lib::overrides::Overrides::from_path)
+            .transpose()
+            .map_err(PyVcf2ParquetErr::from)?
+            .unwrap_or_default();
+
+        let undeclared = undeclared
+            .map(|u| u.parse())
+            .transpose()
+            .map_err(PyVcf2ParquetErr::from)?
+            .unwrap_or_default();
+
+        let mut naming = lib::options::Naming::new()
+            .set_sanitize(sanitize_names)
+            .set_lowercase(lowercase_names);
+        if let Some(template) = info_name {
+            naming = naming.set_info_template(template);
+        }
+        if let Some(template) = format_name {
+            naming = naming.set_format_template(template);
+        }
+
+        let reference = reference
+            .map(lib::reference::Reference::from_path)
+            .transpose()
+            .map_err(PyVcf2ParquetErr::from)?
+            .map(std::sync::Arc::new);
+
+        let inner = lib::options::Options::new()
+            .set_info_optional(info_optional)
+            .set_dictionary(dictionary.unwrap_or_default())
+            .set_type_hints(type_hints)
+            .set_info_float64(info_float64)
+            .set_overrides(overrides)
+            .set_undeclared(undeclared)
+            .set_lenient(lenient)
+            .set_naming(naming)
+            .set_memory_budget(memory_budget.map(|m| m.saturating_mul(1 << 20)))
+            .set_variant_id(variant_id)
+            .set_variant_hash(variant_hash)
+            .set_normalize(normalize)
+            .set_reference(reference)
+            .set_classify(classify)
+            .set_sv(sv)
+            .set_context(context)
+            .set_check_reference(check_reference)
+            .set_fill_reference(fill_reference)
+            .set_genotype_stats(genotype_stats)
+            .set_sample_qc(sample_qc.is_some());
+
+        Ok(Self {
+            inner: selection(inner, columns, samples, regions)?,
+            compact_integer,
+            sample_qc,
+        })
+    }
+}
+
+impl Options {
+    /// Get library options, if integer compaction is set `input` is read a first time to choose integer types
+    fn build(&self, input: &PyAny, read_buffer: usize) -> PyResult<lib::options::Options> {
+        if !self.compact_integer {
+            return Ok(self.inner.clone());
+        }
+
+        if input.extract::<std::path::PathBuf>().is_err() {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "compact_integer read input twice, input must be a path",
+            ));
+        }
+
+        Ok(
+            lib::compact_integer(reader(input, read_buffer)?, &self.inner)
+                .map_err(PyVcf2ParquetErr::from)?,
+        )
+    }
+
+    /// Write per sample quality control summary if a path is set
+    fn write_sample_qc(&self, stats: &lib::stats::Stats) -> PyResult<()> {
+        if let (Some(path), Some(qc)) = (&self.sample_qc, stats.sample_qc()) {
+            qc.write(std::fs::File::create(path)?)
+                .map_err(PyVcf2ParquetErr::from)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Get options, `info_optional` keyword argument is keep for compatibility
+fn options(options: Option<Options>, info_optional: bool) -> Options {
+    let mut options = options.unwrap_or_default();
+    if info_optional {
+        options.inner = options.inner.set_info_optional(true);
+    }
+
+    options
+}
+
+/// Add column, sample and region selection to options, selection not set keep value of options
+fn selection(
+    mut options: lib::options::Options,
+    columns: Option<Vec<String>>,
+    samples: Option<Vec<String>>,
+    regions: Option<Vec<String>>,
+) -> PyResult<lib::options::Options> {
+    if let Some(regions) = regions {
+        let regions = regions
+            .iter()
+            .map(|r| r.parse())
+            .collect::<Result<Vec<lib::options::Region>, _>>()
+            .map_err(PyVcf2ParquetErr::from)?;
+        options = options.set_regions(regions);
+    }
+    if let Some(columns) = columns {
+        options = options.set_columns(columns);
+    }
+    if let Some(samples) = samples {
+        options = options.set_samples(samples);
+    }
+
+    Ok(options)
+}
+
 #[pyfunction]
-#[pyo3(signature = (input,output,read_buffer=8192,batch_size=100_000,compression=Compression::Snappy,info_optional=false,parquet_version=ParquetVersion::V2_0))]
+#[pyo3(signature = (input,output,read_buffer=8192,batch_size=100_000,compression=Compression::Snappy,info_optional=false,parquet_version=ParquetVersion::V2_0,options=None))]
+#[allow(clippy::too_many_arguments)]
 fn convert_vcf(
-    input: std::path::PathBuf,
-    output: std::path::PathBuf,
+    input: &PyAny,
+    output: &PyAny,
     read_buffer: usize,
     batch_size: usize,
     compression: Compression,
     info_optional: bool,
     parquet_version: ParquetVersion,
+    options: Option<Options>,
 ) -> PyResult<()> {
-    let mut reader = std::fs::File::open(input)
-        .map(Box::new)
-        .map(|x| niffler::get_reader(x))?
-        .map(|(file, _)| std::io::BufReader::with_capacity(read_buffer, file))
-        .map_err(PyVcf2ParquetErr::Niffle)?;
-
-    let mut output = std::fs::File::create(output)?;
-
-    let compression = match compression {
-        Compression::Uncompressed => parquet::basic::Compression::UNCOMPRESSED,
-        Compression::Snappy => parquet::basic::Compression::SNAPPY,
-        Compression::Gzip => {
-            parquet::basic::Compression::GZIP(parquet::basic::GzipLevel::default())
-        }
-        Compression::Lzo => parquet::basic::Compression::LZO,
-        Compression::Brotli => {
-            parquet::basic::Compression::BROTLI(parquet::basic::BrotliLevel::default())
-        }
-        Compression::Lz4 => parquet::basic::Compression::LZ4,
-        Compression::Zstd => {
-            parquet::basic::Compression::ZSTD(parquet::basic::ZstdLevel::default())
-        }
-    };
+    let options = self::options(options, info_optional);
+    let conversion = options.build(input, read_buffer)?;
 
-    let parquet_version = match parquet_version {
-        ParquetVersion::V2_0 => parquet::file::properties::WriterVersion::PARQUET_2_0,
-        ParquetVersion::V1_0 => parquet::file::properties::WriterVersion::PARQUET_1_0,
-    };
+    let mut reader = reader(input, read_buffer)?;
+    let mut output = writer(output)?;
 
-    lib::vcf2parquet(
+    let stats = lib::vcf2parquet_with_options(
         &mut reader,
         &mut output,
         batch_size,
-        compression,
-        info_optional,
-        parquet_version,
+        compression.into(),
+        &conversion,
+        parquet_version.into(),
     )
-    .map_err(PyVcf2ParquetErr::from)
-    .map_err(PyErr::from)
+    .map_err(PyVcf2ParquetErr::from)?;
+
+    options.write_sample_qc(&stats)
+}
+
+#[pyfunction]
+#[pyo3(signature = (input,output_format,read_buffer=8192,batch_size=100_000,compression=Compression::Snappy,info_optional=false,parquet_version=ParquetVersion::V2_0,options=None))]
+#[allow(clippy::too_many_arguments)]
+fn split_vcf(
+    input: &PyAny,
+    output_format: &str,
+    read_buffer: usize,
+    batch_size: usize,
+    compression: Compression,
+    info_optional: bool,
+    parquet_version: ParquetVersion,
+    options: Option<Options>,
+) -> PyResult<()> {
+    let options = self::options(options, info_optional);
+    let conversion = options.build(input, read_buffer)?;
+
+    let mut reader = reader(input, read_buffer)?;
+
+    let stats = lib::vcf2multiparquet_with_options(
+        &mut reader,
+        output_format,
+        batch_size,
+        compression.into(),
+        &conversion,
+        parquet_version.into(),
+    )
+    .map_err(PyVcf2ParquetErr::from)?;
+
+    options.write_sample_qc(&stats)
+}
+
+fn schema_capsule(py: Python<'_>, schema: &arrow::datatypes::Schema) -> PyResult<PyObject> {
+    let ffi = arrow::ffi::FFI_ArrowSchema::try_from(schema)
+        .map_err(lib::error::Error::from)
+        .map_err(PyVcf2ParquetErr::from)?;
+
+    Ok(PyCapsule::new(py, ffi, Some(c"arrow_schema".into()))?.into())
+}
+
+/// Arrow record batch, export with arrow PyCapsule interface
+#[pyclass]
+pub struct RecordBatch {
+    inner: arrow::record_batch::RecordBatch,
+}
+
+#[pymethods]
+impl RecordBatch {
+    /// Number of records in batch
+    fn __len__(&self) -> usize {
+        self.inner.num_rows()
+    }
+
+    fn __arrow_c_schema__(&self, py: Python<'_>) -> PyResult<PyObject> {
+        schema_capsule(py, &self.inner.schema())
+    }
+
+    #[pyo3(signature = (requested_schema=None))]
+    fn __arrow_c_array__(
+        &self,
+        py: Python<'_>,
+        requested_schema: Option<PyObject>,
+    ) -> PyResult<(PyObject, PyObject)> {
+        let _ = requested_schema;
+
+        let data = arrow::array::StructArray::from(self.inner.clone());
+        let (array, schema) = arrow::ffi::to_ffi(&arrow::array::Array::to_data(&data))
+            .map_err(lib::error::Error::from)
+            .map_err(PyVcf2ParquetErr::from)?;
+
+        Ok((
+            PyCapsule::new(py, schema, Some(c"arrow_schema".into()))?.into(),
+            PyCapsule::new(py, array, Some(c"arrow_array".into()))?.into(),
+        ))
+    }
+}
+
+/// Iterator on arrow record batch read from a vcf, export with arrow PyCapsule interface
+#[pyclass]
+pub struct BatchReader {
+    schema: arrow::datatypes::SchemaRef,
+    inner: Option<Box<dyn arrow::record_batch::RecordBatchReader + Send>>,
+}
+
+#[pymethods]
+impl BatchReader {
+    #[new]
+    #[pyo3(signature = (input,read_buffer=8192,batch_size=100_000,info_optional=false,columns=None,samples=None,regions=None,options=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        input: &PyAny,
+        read_buffer: usize,
+        batch_size: usize,
+        info_optional: bool,
+        columns: Option<Vec<String>>,
+        samples: Option<Vec<String>>,
+        regions: Option<Vec<String>>,
+        options: Option<Options>,
+    ) -> PyResult<Self> {
+        let options = self::options(options, info_optional).build(input, read_buffer)?;
+        let options = selection(options, columns, samples, regions)?;
+        let reader = reader(input, read_buffer)?;
+
+        let batches =
+            lib::vcf2batches(reader, batch_size, &options).map_err(PyVcf2ParquetErr::from)?;
+
+        Ok(Self {
+            schema: arrow::record_batch::RecordBatchReader::schema(&batches),
+            inner: Some(Box::new(batches)),
+        })
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self) -> PyResult<Option<RecordBatch>> {
+        match self.inner.as_mut().and_then(|i| i.next()) {
+            Some(Ok(inner)) => Ok(Some(RecordBatch { inner })),
+            Some(Err(e)) => Err(PyVcf2ParquetErr::from(lib::error::Error::from(e)).into()),
+            None => Ok(None),
+        }
+    }
+
+    fn __arrow_c_schema__(&self, py: Python<'_>) -> PyResult<PyObject> {
+        schema_capsule(py, &self.schema)
+    }
+
+    /// Export remaining batches as an arrow stream, reader is consumed
+    #[pyo3(signature = (requested_schema=None))]
+    fn __arrow_c_stream__(
+        &mut self,
+        py: Python<'_>,
+        requested_schema: Option<PyObject>,
+    ) -> PyResult<PyObject> {
+        let _ = requested_schema;
+
+        let inner = self.inner.take().ok_or_else(|| {
+            PyVcf2ParquetErr::Other("BatchReader is already consumed".to_string())
+        })?;
+        let stream = arrow::ffi_stream::FFI_ArrowArrayStream::new(inner);
+
+        Ok(PyCapsule::new(py, stream, Some(c"arrow_array_stream".into()))?.into())
+    }
+}
+
+/// Read a vcf in a pyarrow Table, or a polars DataFrame if `polars` is true, without parquet serialization
+#[pyfunction]
+#[pyo3(signature = (input,read_buffer=8192,batch_size=100_000,info_optional=false,columns=None,samples=None,regions=None,polars=false,options=None))]
+#[allow(clippy::too_many_arguments)]
+fn read_vcf(
+    py: Python<'_>,
+    input: &PyAny,
+    read_buffer: usize,
+    batch_size: usize,
+    info_optional: bool,
+    columns: Option<Vec<String>>,
+    samples: Option<Vec<String>>,
+    regions: Option<Vec<String>>,
+    polars: bool,
+    options: Option<Options>,
+) -> PyResult<PyObject> {
+    let reader = BatchReader::new(
+        input,
+        read_buffer,
+        batch_size,
+        info_optional,
+        columns,
+        samples,
+        regions,
+        options,
+    )?;
+
+    let table = py
+        .import("pyarrow")?
+        .getattr("RecordBatchReader")?
+        .call_method1("from_stream", (Py::new(py, reader)?,))?
+        .call_method0("read_all")?;
+
+    if polars {
+        Ok(py
+            .import("polars")?
+            .call_method1("from_arrow", (table,))?
+            .into())
+    } else {
+        Ok(table.into())
+    }
 }
 
 /// A Python module implemented in Rust. The name of this function must match
@@ -89,6 +561,12 @@ fn convert_vcf(
 #[pymodule]
 fn pyvcf2parquet(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(convert_vcf, m)?)?;
+    m.add_function(wrap_pyfunction!(split_vcf, m)?)?;
+    m.add_function(wrap_pyfunction!(read_vcf, m)?)?;
     m.add_class::<Compression>()?;
+    m.add_class::<ParquetVersion>()?;
+    m.add_class::<Options>()?;
+    m.add_class::<RecordBatch>()?;
+    m.add_class::<BatchReader>()?;
     Ok(())
 }
@@ -1,6 +1,10 @@
 import polars as pl
+import pyarrow as pa
 from pyvcf2parquet import *
+import glob
+import io
 import os
+import pytest
 
 
 def test_vcf2parquet():
@@ -10,3 +14,134 @@ def test_vcf2parquet():
     assert df.shape == (3, 10)
 
     os.remove("tests/test.parquet")
+
+
+def test_parquet_version():
+    convert_vcf(
+        "tests/test.vcf",
+        "tests/test.parquet",
+        compression=Compression.Zstd,
+        parquet_version=ParquetVersion.V1_0,
+    )
+    df = pl.read_parquet("tests/test.parquet")
+
+    assert df.shape == (3, 10)
+
+    os.remove("tests/test.parquet")
+
+
+def test_split():
+    split_vcf("tests/test.vcf", "tests/test_{}.parquet", batch_size=1)
+    paths = sorted(glob.glob("tests/test_*.parquet"))
+
+    assert len(paths) == 3
+    assert pl.concat([pl.read_parquet(p) for p in paths]).shape == (3, 10)
+
+    for path in paths:
+        os.remove(path)
+
+
+def test_file_like():
+    with open("tests/test.vcf", "rb") as input:
+        output = io.BytesIO()
+        convert_vcf(input, output)
+
+    output.seek(0)
+    df = pl.read_parquet(output)
+
+    assert df.shape == (3, 10)
+
+
+def test_batch_reader():
+    batches = [pa.record_batch(b) for b in BatchReader("tests/test.vcf", batch_size=2)]
+
+    assert [b.num_rows for b in batches] == [2, 1]
+
+    table = pa.RecordBatchReader.from_stream(BatchReader("tests/test.vcf")).read_all()
+
+    assert table.num_rows == 3
+    assert table.num_columns == 10
+    assert pl.from_arrow(table).shape == (3, 10)
+
+
+def test_read_vcf():
+    table = read_vcf("tests/test.vcf")
+
+    assert isinstance(table, pa.Table)
+    assert table.shape == (3, 10)
+
+    df = read_vcf("tests/test.vcf", columns=["chromosome", "position"], polars=True)
+
+    assert isinstance(df, pl.DataFrame)
+    assert df.columns == ["chromosome", "position"]
+
+
+def test_options():
+    options = Options(
+        columns=["chromosome", "position", "variant_id"],
+        dictionary=["chromosome"],
+        variant_id=True,
+        lenient=True,
+        memory_budget=64,
+    )
+    table = read_vcf("tests/test.vcf", options=options)
+
+    assert table.column_names == ["chromosome", "position", "variant_id"]
+    assert pa.types.is_dictionary(table.schema.field("chromosome").type)
+    assert table.num_rows == 3
+
+    # selection keyword arguments override selection of options
+    table = read_vcf("tests/test.vcf", columns=["position"], options=options)
+    assert table.column_names == ["position"]
+
+    convert_vcf("tests/test.vcf", "tests/test.parquet", options=Options(info_name="i_{key}"))
+    df = pl.read_parquet("tests/test.parquet")
+
+    assert df.shape == (3, 10)
+    assert all(not c.startswith("info_") for c in df.columns)
+
+    os.remove("tests/test.parquet")
+
+    with pytest.raises(Exception):
+        Options(undeclared="ignore")
+
+    with pytest.raises(Exception):
+        Options(type_hints=["INFO/DP"])
+
+
+def test_compact_integer():
+    vcf = (
+        "##fileformat=VCFv4.3\n"
+        '##INFO=<ID=DP,Number=1,Type=Integer,Description="depth">\n'
+        "#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\n"
+        + "".join(f"1\t{p}\t.\tA\tC\t.\t.\tDP=10\n" for p in range(1, 21))
+        + "1\t21\t.\tA\tC\t.\t.\tDP=1000\n"
+    )
+    with open("tests/compact.vcf", "w") as output:
+        output.write(vcf)
+
+    table = read_vcf("tests/compact.vcf", options=Options(compact_integer=True))
+
+    assert table.schema.field("info_DP").type == pa.int16()
+    assert table.column("info_DP")[20].as_py() == 1000
+
+    # a file-like object can't be read twice
+    with pytest.raises(ValueError):
+        read_vcf(io.StringIO(vcf), options=Options(compact_integer=True))
+
+    os.remove("tests/compact.vcf")
+
+
+def test_text_file_like():
+    # multibyte characters, read(n) of a text file return n characters
+    vcf = (
+        "##fileformat=VCFv4.3\n"
+        '##INFO=<ID=GN,Number=1,Type=String,Description="gène">\n'
+        "#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\n"
+        + "".join(f"1\t{p}\t.\tA\tC\t.\t.\tGN=élément_{p}\n" for p in range(1, 101))
+    )
+
+    table = read_vcf(io.StringIO(vcf), read_buffer=16)
+
+    assert table.num_rows == 100
+    assert table.column("info_GN")[99].as_py() == "élément_100"
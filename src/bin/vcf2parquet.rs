@@ -1,59 +1,232 @@
 //! vcf2parquet bin
 
 /* std use */
+use std::io::Write as _;
 
 /* crate use */
+use arrow::record_batch::RecordBatchReader as _;
 use clap::Parser as _;
 
 /* project use */
 use vcf2parquet::cli;
 use vcf2parquet::error;
+use vcf2parquet::options;
+use vcf2parquet::stats;
+#[cfg(feature = "object_store")]
+use vcf2parquet::storage;
 
 /* mod section */
 
 fn main() -> error::Result<()> {
     let params = cli::Command::parse();
 
+    stderrlog::new()
+        .module(module_path!())
+        .quiet(params.quiet())
+        .verbosity(params.verbosity())
+        .init()
+        .map_err(|e| error::Error::Log(e.to_string()))?;
+
     match params.subcommand() {
-        cli::SubCommand::Convert(subparams) => convert(&params, subparams),
-        cli::SubCommand::Split(subparams) => split(&params, subparams),
+        cli::SubCommand::Convert(subparams) => write_stats(&params, convert(&params, subparams)?),
+        cli::SubCommand::Split(subparams) => write_stats(&params, split(&params, subparams)?),
+        cli::SubCommand::Validate(subparams) => validate(&params, subparams),
+        cli::SubCommand::Inspect(subparams) => inspect(&params, subparams),
     }
 }
 
-fn convert(params: &cli::Command, subparams: &cli::Convert) -> error::Result<()> {
-    let mut reader = std::fs::File::open(params.input())
-        .map(Box::new)
-        .map(|x| niffler::get_reader(x))?
-        .map(|(file, _)| std::io::BufReader::with_capacity(params.read_buffer(), file))?;
+fn write_stats(params: &cli::Command, stats: stats::Stats) -> error::Result<()> {
+    if let Some(path) = params.stats_json() {
+        std::fs::write(path, stats.to_json())?;
+    }
+
+    if let (Some(path), Some(qc)) = (params.sample_qc(), stats.sample_qc()) {
+        qc.write(std::fs::File::create(path)?)?;
+    }
+
+    Ok(())
+}
 
-    let mut output = std::fs::File::create(subparams.output())?;
+fn convert(params: &cli::Command, subparams: &cli::Convert) -> error::Result<stats::Stats> {
+    let (mut reader, progress) = reader(params)?;
+    let options = options(params)?.set_progress(Some(progress));
 
-    vcf2parquet::vcf2parquet(
+    #[cfg(feature = "object_store")]
+    if let Some(url) = subparams.output().to_str().filter(|p| storage::is_url(p)) {
+        let (store, path) = storage::parse(url)?;
+
+        return storage::vcf2parquet(
+            &mut reader,
+            store,
+            &path,
+            params.batch_size(),
+            params.compression(),
+            &options,
+            params.parquet_version(),
+        );
+    }
+
+    let mut output = writer(subparams.output())?;
+
+    vcf2parquet::vcf2parquet_with_options(
         &mut reader,
         &mut output,
         params.batch_size(),
         params.compression(),
-        params.info_optional(),
+        &options,
         params.parquet_version(),
-    )?;
-
-    Ok(())
+    )
 }
 
-fn split(params: &cli::Command, subparams: &cli::Split) -> error::Result<()> {
-    let mut reader = std::fs::File::open(params.input())
-        .map(Box::new)
-        .map(|x| niffler::get_reader(x))?
-        .map(|(file, _)| std::io::BufReader::with_capacity(params.read_buffer(), file))?;
+fn split(params: &cli::Command, subparams: &cli::Split) -> error::Result<stats::Stats> {
+    let (mut reader, progress) = reader(params)?;
+    let options = options(params)?.set_progress(Some(progress));
+
+    #[cfg(feature = "object_store")]
+    if storage::is_url(subparams.format()) {
+        let (store, _) = storage::parse(subparams.format())?;
+
+        return storage::vcf2multiparquet(
+            &mut reader,
+            store,
+            subparams.format(),
+            params.batch_size(),
+            params.compression(),
+            &options,
+            params.parquet_version(),
+        );
+    }
 
-    vcf2parquet::vcf2multiparquet(
+    vcf2parquet::vcf2multiparquet_with_options(
         &mut reader,
         subparams.format(),
         params.batch_size(),
         params.compression(),
-        params.info_optional(),
+        &options,
         params.parquet_version(),
+    )
+}
+
+fn validate(params: &cli::Command, subparams: &cli::Validate) -> error::Result<()> {
+    let (reader, progress) = reader(params)?;
+    let options = options(params)?.set_progress(Some(progress));
+
+    let report = vcf2parquet::validate::validate(
+        reader,
+        std::fs::File::open(subparams.parquet())?,
+        params.batch_size(),
+        &options,
+        subparams.spot_check(),
     )?;
 
-    Ok(())
+    println!(
+        "{} rows expected, {} rows found",
+        report.expected().rows(),
+        report.found().rows()
+    );
+    for discrepancy in report.discrepancies() {
+        println!("{}", discrepancy);
+    }
+
+    if report.is_valid() {
+        Ok(())
+    } else {
+        Err(error::Error::Validation(report.discrepancies().len()))
+    }
+}
+
+fn inspect(params: &cli::Command, subparams: &cli::Inspect) -> error::Result<()> {
+    let mut output = std::io::stdout().lock();
+
+    if let Some(format) = subparams.export() {
+        let schema = if is_parquet(params.input()) {
+            parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(
+                std::fs::File::open(params.input())?,
+            )?
+            .schema()
+            .clone()
+        } else {
+            let (reader, _) = reader(params)?;
+            vcf2parquet::vcf2batches(reader, 1, &options(params)?)?.schema()
+        };
+
+        writeln!(
+            output,
+            "{}",
+            vcf2parquet::export::schema(&schema, format, subparams.table())?
+        )?;
+
+        return Ok(());
+    }
+
+    if is_parquet(params.input()) {
+        let description = vcf2parquet::inspect::parquet(std::fs::File::open(params.input())?)?;
+        vcf2parquet::inspect::write_parquet(&mut output, &description)
+    } else {
+        let (reader, _) = reader(params)?;
+        let columns = vcf2parquet::inspect::vcf(reader, &options(params)?)?;
+        vcf2parquet::inspect::write_columns(&mut output, &columns)
+    }
+}
+
+/// Build conversion options, if integer compaction is asked input is read a first time to choose integer types
+fn options(params: &cli::Command) -> error::Result<options::Options> {
+    let options = params.options()?;
+    if !params.compact_integer() {
+        return Ok(options);
+    }
+
+    if params.input() == std::path::Path::new("-") {
+        return Err(error::Error::CompactStdin);
+    }
+
+    let (reader, _) = reader(params)?;
+    vcf2parquet::compact_integer(reader, &options)
+}
+
+/// Return true if path is a local file starting with parquet magic number
+fn is_parquet(path: &std::path::Path) -> bool {
+    let mut magic = [0; 4];
+
+    std::fs::File::open(path)
+        .and_then(|mut f| std::io::Read::read_exact(&mut f, &mut magic))
+        .is_ok()
+        && &magic == b"PAR1"
+}
+
+/// Open input path, `-` is stdin, compression is detected, bytes read are count to report progress
+fn reader(
+    params: &cli::Command,
+) -> error::Result<(std::io::BufReader<Box<dyn std::io::Read>>, stats::Progress)> {
+    let (raw, progress): (Box<dyn std::io::Read>, _) = match params.input().to_str() {
+        Some("-") => (Box::new(std::io::stdin()), stats::Progress::new(None)),
+        #[cfg(feature = "object_store")]
+        Some(url) if storage::is_url(url) => {
+            let (store, path) = storage::parse(url)?;
+            let object = storage::ObjectReader::new(store, &path)?;
+            let progress = stats::Progress::new(Some(object.size()));
+            (Box::new(object), progress)
+        }
+        _ => {
+            let file = std::fs::File::open(params.input())?;
+            let progress = stats::Progress::new(file.metadata().ok().map(|m| m.len()));
+            (Box::new(file), progress)
+        }
+    };
+
+    let (file, _) = niffler::get_reader(Box::new(progress.reader(raw)))?;
+
+    Ok((
+        std::io::BufReader::with_capacity(params.read_buffer(), file),
+        progress,
+    ))
+}
+
+/// Open output path, `-` is stdout
+fn writer(path: &std::path::Path) -> error::Result<Box<dyn std::io::Write + Send>> {
+    if path == std::path::Path::new("-") {
+        Ok(Box::new(std::io::BufWriter::new(std::io::stdout())))
+    } else {
+        Ok(Box::new(std::fs::File::create(path)?))
+    }
 }
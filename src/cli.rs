@@ -6,6 +6,10 @@
 use parquet::file::properties::WriterVersion;
 
 /* project use */
+use crate::error;
+use crate::options;
+use crate::overrides;
+use crate::reference;
 
 /// Parquet version available for user
 #[derive(Debug, clap::ValueEnum, Clone, Copy)]
@@ -50,7 +54,7 @@ pub enum Compression {
     about = "Convert a vcf in parquet"
 )]
 pub struct Command {
-    /// Input path
+    /// Input path or object storage url (s3://, gs://, az://), - read stdin
     #[clap(short = 'i', long = "input")]
     input: std::path::PathBuf,
 
@@ -58,6 +62,10 @@ pub struct Command {
     #[clap(short = 'b', long = "batch-size")]
     batch_size: Option<usize>,
 
+    /// Memory budget of column builders in MiB, batch size is reduce to fit and row group is write when budget is exceed (default no limit)
+    #[clap(short = 'm', long = "memory-budget")]
+    memory_budget: Option<usize>,
+
     /// Compression method (default snappy)
     #[clap(value_enum, short = 'c', long = "compression")]
     compression: Option<Compression>,
@@ -74,6 +82,114 @@ pub struct Command {
     #[clap(long = "parquet-version")]
     parquet_version: Option<ParquetVersion>,
 
+    /// Columns stored as dictionary of string, e.g. chromosome,info_GENE
+    #[clap(short = 'D', long = "dictionary", value_delimiter = ',')]
+    dictionary: Vec<String>,
+
+    /// Columns keep in output, INFO and FORMAT fields of other columns are not decoded, e.g. position,info_DP
+    #[clap(long = "columns", value_delimiter = ',')]
+    columns: Vec<String>,
+
+    /// Samples keep in output, FORMAT fields of other samples are not decoded
+    #[clap(long = "samples", value_delimiter = ',')]
+    samples: Vec<String>,
+
+    /// Add a variant_id column chromosome-position-reference-alternate
+    #[clap(long = "variant-id")]
+    variant_id: bool,
+
+    /// Add a variant_hash column, 64 bits hash of variant_id
+    #[clap(long = "variant-hash")]
+    variant_hash: bool,
+
+    /// Trim alleles before computation of variant_id and variant_hash
+    #[clap(long = "normalize")]
+    normalize: bool,
+
+    /// Fasta reference used to left align indels, imply --normalize
+    #[clap(long = "reference")]
+    reference: Option<std::path::PathBuf>,
+
+    /// Add variant_type, length_change, transition, sv_type and sv_length columns
+    #[clap(long = "classify")]
+    classify: bool,
+
+    /// Parse symbolic alleles and breakends in sv_type, sv_length, sv_end, mate_chromosome, mate_position, orientation and CIPOS/CIEND columns
+    #[clap(long = "sv")]
+    sv: bool,
+
+    /// Add a context column with N reference bases on each side of REF, e.g. 1 for trinucleotide context, require --reference
+    #[clap(long = "context")]
+    context: Option<usize>,
+
+    /// Add a reference_match column, false if REF differ from reference, require --reference
+    #[clap(long = "check-reference")]
+    check_reference: bool,
+
+    /// Replace REF made only of N by reference bases, require --reference
+    #[clap(long = "fill-reference")]
+    fill_reference: bool,
+
+    /// Add ac, an, af, n_het, n_hom_alt, n_missing, call_rate and hwe_pvalue columns computed from genotypes of kept samples
+    #[clap(long = "genotype-stats")]
+    genotype_stats: bool,
+
+    /// Arrow type of numeric fields, e.g. INFO/DP=int16,FORMAT/PL=int16,INFO/AF=float64
+    #[clap(short = 'T', long = "type-hint", value_delimiter = ',')]
+    type_hints: Vec<options::TypeHint>,
+
+    /// Store INFO float fields in 64 bits
+    #[clap(long = "info-float64")]
+    info_float64: bool,
+
+    /// Choose narrowest integer type of INFO and FORMAT integer fields, input is read twice (not available with stdin)
+    #[clap(long = "compact-integer")]
+    compact_integer: bool,
+
+    /// Toml or json file that override INFO and FORMAT header definition
+    #[clap(long = "schema-override")]
+    schema_override: Option<std::path::PathBuf>,
+
+    /// Policy for INFO and FORMAT keys not declared in header: error, warn or extra (default warn)
+    #[clap(long = "undeclared")]
+    undeclared: Option<options::Undeclared>,
+
+    /// Value that can't be convert are replace by null and unparsable records are skip, instead of stop conversion
+    #[clap(long = "lenient")]
+    lenient: bool,
+
+    /// Template of INFO column names, {key} is replace by key (default info_{key})
+    #[clap(long = "info-name")]
+    info_name: Option<String>,
+
+    /// Template of FORMAT column names, {sample} and {key} are replace by sample name and key (default format_{sample}_{key})
+    #[clap(long = "format-name")]
+    format_name: Option<String>,
+
+    /// Replace characters other than A-Z, a-z, 0-9 and _ in column names by _
+    #[clap(long = "sanitize-names")]
+    sanitize_names: bool,
+
+    /// Write column names in lowercase
+    #[clap(long = "lowercase-names")]
+    lowercase_names: bool,
+
+    /// Write conversion statistics in a json file
+    #[clap(long = "stats-json")]
+    stats_json: Option<std::path::PathBuf>,
+
+    /// Write a parquet with one row by sample: called, missing, het and hom-alt genotypes, Ti/Tv, mean DP and GQ and het rate by chromosome
+    #[clap(long = "sample-qc")]
+    sample_qc: Option<std::path::PathBuf>,
+
+    /// Silence all output, by default warnings, progress and summary of conversion are reported
+    #[clap(short = 'q', long = "quiet")]
+    quiet: bool,
+
+    /// Verbose mode (-v, -vv, -vvv, etc)
+    #[clap(short = 'v', long = "verbosity", action = clap::ArgAction::Count)]
+    verbosity: u8,
+
     #[clap(subcommand)]
     subcommand: SubCommand,
 }
@@ -86,12 +202,18 @@ pub enum SubCommand {
 
     /// Convert a vcf in multiple parquet file each file contains `batch_size` record
     Split(Split),
+
+    /// Compare a vcf and the parquet generated from it, use same options as conversion
+    Validate(Validate),
+
+    /// Print columns produce from a vcf, or row groups, columns size and metadata of a parquet
+    Inspect(Inspect),
 }
 
 /// Convert a vcf in a parquet
 #[derive(clap::Parser, std::fmt::Debug, Clone)]
 pub struct Convert {
-    /// Output path
+    /// Output path or object storage url (s3://, gs://, az://), - write on stdout
     #[clap(short = 'o', long = "output")]
     output: std::path::PathBuf,
 }
@@ -99,11 +221,35 @@ pub struct Convert {
 /// Convert a vcf in multiple parquet file each file contains `batch_size` record
 #[derive(clap::Parser, std::fmt::Debug, Clone)]
 pub struct Split {
-    /// Output format string, first {} are replace by number
+    /// Output format string, path or object storage url, first {} are replace by number
     #[clap(short = 'f', long = "output-format")]
     format: String,
 }
 
+/// Compare a vcf and the parquet generated from it, use same options as conversion
+#[derive(clap::Parser, std::fmt::Debug, Clone)]
+pub struct Validate {
+    /// Parquet path
+    #[clap(short = 'p', long = "parquet")]
+    parquet: std::path::PathBuf,
+
+    /// Compare INFO and FORMAT values of one row every N rows, 0 disable it (default 1000)
+    #[clap(short = 's', long = "spot-check")]
+    spot_check: Option<usize>,
+}
+
+/// Print columns produce from a vcf, or row groups, columns size and metadata of a parquet
+#[derive(clap::Parser, std::fmt::Debug, Clone)]
+pub struct Inspect {
+    /// Only print schema in another language, records aren't read [possible values: arrow-json, parquet, duckdb, hive, bigquery]
+    #[clap(short = 'e', long = "export")]
+    export: Option<crate::export::Format>,
+
+    /// Table name used in CREATE TABLE (default vcf)
+    #[clap(short = 't', long = "table")]
+    table: Option<String>,
+}
+
 impl Command {
     /// Get input
     pub fn input(&self) -> &std::path::PathBuf {
@@ -115,6 +261,11 @@ impl Command {
         self.batch_size.unwrap_or(100_000)
     }
 
+    /// Get memory budget in bytes
+    pub fn memory_budget(&self) -> Option<usize> {
+        self.memory_budget.map(|m| m.saturating_mul(1 << 20))
+    }
+
     /// Get compression set by user or default value
     pub fn compression(&self) -> parquet::basic::Compression {
         match self.compression {
@@ -154,6 +305,175 @@ impl Command {
         self.info_optional
     }
 
+    /// Get columns stored as dictionary
+    pub fn dictionary(&self) -> &[String] {
+        &self.dictionary
+    }
+
+    /// Get columns keep in output
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Get samples keep in output
+    pub fn samples(&self) -> &[String] {
+        &self.samples
+    }
+
+    /// Get variant_id
+    pub fn variant_id(&self) -> bool {
+        self.variant_id
+    }
+
+    /// Get variant_hash
+    pub fn variant_hash(&self) -> bool {
+        self.variant_hash
+    }
+
+    /// Get normalize
+    pub fn normalize(&self) -> bool {
+        self.normalize
+    }
+
+    /// Get reference path
+    pub fn reference(&self) -> Option<&std::path::PathBuf> {
+        self.reference.as_ref()
+    }
+
+    /// Get classify
+    pub fn classify(&self) -> bool {
+        self.classify
+    }
+
+    /// Get sv
+    pub fn sv(&self) -> bool {
+        self.sv
+    }
+
+    /// Get number of bases of sequence context
+    pub fn context(&self) -> usize {
+        self.context.unwrap_or(0)
+    }
+
+    /// Get check reference
+    pub fn check_reference(&self) -> bool {
+        self.check_reference
+    }
+
+    /// Get fill reference
+    pub fn fill_reference(&self) -> bool {
+        self.fill_reference
+    }
+
+    /// Get genotype statistics
+    pub fn genotype_stats(&self) -> bool {
+        self.genotype_stats
+    }
+
+    /// Get type hints
+    pub fn type_hints(&self) -> &[options::TypeHint] {
+        &self.type_hints
+    }
+
+    /// Get info float64
+    pub fn info_float64(&self) -> bool {
+        self.info_float64
+    }
+
+    /// Get compact integer
+    pub fn compact_integer(&self) -> bool {
+        self.compact_integer
+    }
+
+    /// Get schema override path
+    pub fn schema_override(&self) -> Option<&std::path::PathBuf> {
+        self.schema_override.as_ref()
+    }
+
+    /// Get undeclared key policy
+    pub fn undeclared(&self) -> options::Undeclared {
+        self.undeclared.unwrap_or_default()
+    }
+
+    /// Get lenient
+    pub fn lenient(&self) -> bool {
+        self.lenient
+    }
+
+    /// Get column naming policy
+    pub fn naming(&self) -> options::Naming {
+        let mut naming = options::Naming::new()
+            .set_sanitize(self.sanitize_names)
+            .set_lowercase(self.lowercase_names);
+
+        if let Some(template) = &self.info_name {
+            naming = naming.set_info_template(template.clone());
+        }
+        if let Some(template) = &self.format_name {
+            naming = naming.set_format_template(template.clone());
+        }
+
+        naming
+    }
+
+    /// Get statistics json path
+    pub fn stats_json(&self) -> Option<&std::path::PathBuf> {
+        self.stats_json.as_ref()
+    }
+
+    /// Get sample quality control parquet path
+    pub fn sample_qc(&self) -> Option<&std::path::PathBuf> {
+        self.sample_qc.as_ref()
+    }
+
+    /// Get quiet
+    pub fn quiet(&self) -> bool {
+        self.quiet
+    }
+
+    /// Get verbosity level, warning and information (progress, summary) are reported by default
+    pub fn verbosity(&self) -> usize {
+        self.verbosity as usize + 2
+    }
+
+    /// Get conversion options, schema override file and reference index are read
+    pub fn options(&self) -> error::Result<options::Options> {
+        let overrides = match self.schema_override() {
+            Some(path) => overrides::Overrides::from_path(path)?,
+            None => overrides::Overrides::default(),
+        };
+
+        let reference = self
+            .reference()
+            .map(reference::Reference::from_path)
+            .transpose()?
+            .map(std::sync::Arc::new);
+
+        Ok(options::Options::new()
+            .set_info_optional(self.info_optional())
+            .set_dictionary(self.dictionary().to_vec())
+            .set_columns(self.columns().to_vec())
+            .set_samples(self.samples().to_vec())
+            .set_variant_id(self.variant_id())
+            .set_variant_hash(self.variant_hash())
+            .set_normalize(self.normalize())
+            .set_reference(reference)
+            .set_classify(self.classify())
+            .set_sv(self.sv())
+            .set_context(self.context())
+            .set_check_reference(self.check_reference())
+            .set_fill_reference(self.fill_reference())
+            .set_genotype_stats(self.genotype_stats())
+            .set_sample_qc(self.sample_qc().is_some())
+            .set_type_hints(self.type_hints().to_vec())
+            .set_info_float64(self.info_float64())
+            .set_overrides(overrides)
+            .set_undeclared(self.undeclared())
+            .set_lenient(self.lenient())
+            .set_naming(self.naming())
+            .set_memory_budget(self.memory_budget()))
+    }
+
     /// Get subcommand
     pub fn subcommand(&self) -> &SubCommand {
         &self.subcommand
@@ -174,6 +494,30 @@ impl Split {
     }
 }
 
+impl Validate {
+    /// Get parquet path
+    pub fn parquet(&self) -> &std::path::PathBuf {
+        &self.parquet
+    }
+
+    /// Get spot check interval
+    pub fn spot_check(&self) -> usize {
+        self.spot_check.unwrap_or(1000)
+    }
+}
+
+impl Inspect {
+    /// Get export format
+    pub fn export(&self) -> Option<crate::export::Format> {
+        self.export
+    }
+
+    /// Get table name
+    pub fn table(&self) -> &str {
+        self.table.as_deref().unwrap_or("vcf")
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -184,6 +528,7 @@ mod tests {
         let mut params = Command {
             input: std::path::Path::new("test/input.vcf").to_path_buf(),
             batch_size: None,
+            memory_budget: None,
             compression: Some(Compression::Snappy),
             read_buffer: None,
             subcommand: SubCommand::Convert(Convert {
@@ -191,6 +536,33 @@ mod tests {
             }),
             info_optional: false,
             parquet_version: None,
+            dictionary: vec![],
+            columns: vec![],
+            samples: vec![],
+            variant_id: false,
+            variant_hash: false,
+            normalize: false,
+            reference: None,
+            classify: false,
+            sv: false,
+            context: None,
+            check_reference: false,
+            fill_reference: false,
+            genotype_stats: false,
+            type_hints: vec![],
+            info_float64: false,
+            compact_integer: false,
+            schema_override: None,
+            undeclared: None,
+            lenient: false,
+            info_name: None,
+            format_name: None,
+            sanitize_names: false,
+            lowercase_names: false,
+            stats_json: None,
+            sample_qc: None,
+            quiet: false,
+            verbosity: 0,
         };
 
         assert_eq!(
@@ -212,6 +584,7 @@ mod tests {
         params = Command {
             input: std::path::Path::new("test/input.vcf").to_path_buf(),
             batch_size: Some(100),
+            memory_budget: Some(2),
             compression: Some(Compression::Snappy),
             read_buffer: Some(8194),
             subcommand: SubCommand::Split(Split {
@@ -219,11 +592,72 @@ mod tests {
             }),
             info_optional: false,
             parquet_version: Some(ParquetVersion::V1),
+            dictionary: vec!["chromosome".to_string()],
+            columns: vec!["chromosome".to_string()],
+            samples: vec!["sample_1".to_string()],
+            variant_id: true,
+            variant_hash: true,
+            normalize: true,
+            reference: None,
+            classify: true,
+            sv: true,
+            context: Some(1),
+            check_reference: true,
+            fill_reference: true,
+            genotype_stats: true,
+            type_hints: vec!["INFO/DP=int16".parse().unwrap()],
+            info_float64: true,
+            compact_integer: true,
+            schema_override: None,
+            undeclared: Some(options::Undeclared::Extra),
+            lenient: true,
+            info_name: None,
+            format_name: None,
+            sanitize_names: false,
+            lowercase_names: false,
+            stats_json: None,
+            sample_qc: None,
+            quiet: true,
+            verbosity: 2,
         };
 
         assert_eq!(params.batch_size(), 100);
+        assert_eq!(params.memory_budget(), Some(2 * 1024 * 1024));
+        assert_eq!(
+            params.options().unwrap().memory_budget(),
+            Some(2 * 1024 * 1024)
+        );
         assert_eq!(params.read_buffer(), 8194);
         assert_eq!(params.parquet_version(), WriterVersion::PARQUET_1_0);
+        assert_eq!(params.dictionary(), &["chromosome".to_string()]);
+        assert_eq!(
+            params.options().unwrap().dictionary(),
+            &["chromosome".to_string()]
+        );
+        assert_eq!(
+            params.options().unwrap().columns(),
+            &["chromosome".to_string()]
+        );
+        assert_eq!(params.samples(), &["sample_1".to_string()]);
+        assert!(params.variant_id() && params.variant_hash() && params.normalize());
+        assert!(params.reference().is_none());
+        assert!(params.options().unwrap().normalize());
+        assert!(params.options().unwrap().classify());
+        assert!(params.options().unwrap().sv());
+        assert_eq!(params.context(), 1);
+        assert!(params.check_reference() && params.fill_reference());
+        assert!(params.options().unwrap().genotype_stats());
+        assert!(params.schema_override().is_none());
+        assert_eq!(params.type_hints().len(), 1);
+        assert!(params.info_float64());
+        assert!(params.compact_integer());
+        assert_eq!(
+            params.options().unwrap().undeclared(),
+            options::Undeclared::Extra
+        );
+        assert!(params.options().unwrap().lenient());
+        assert!(params.quiet());
+        assert_eq!(params.verbosity(), 4);
 
         match params.subcommand.clone() {
             SubCommand::Split(s) => assert_eq!(s.format(), "test_{}.parquet"),
@@ -236,6 +670,7 @@ mod tests {
         let mut params = Command {
             input: std::path::Path::new("test/input.vcf").to_path_buf(),
             batch_size: None,
+            memory_budget: None,
             compression: None,
             read_buffer: None,
             subcommand: SubCommand::Convert(Convert {
@@ -243,6 +678,33 @@ mod tests {
             }),
             info_optional: false,
             parquet_version: None,
+            dictionary: vec![],
+            columns: vec![],
+            samples: vec![],
+            variant_id: false,
+            variant_hash: false,
+            normalize: false,
+            reference: None,
+            classify: false,
+            sv: false,
+            context: None,
+            check_reference: false,
+            fill_reference: false,
+            genotype_stats: false,
+            type_hints: vec![],
+            info_float64: false,
+            compact_integer: false,
+            schema_override: None,
+            undeclared: None,
+            lenient: false,
+            info_name: None,
+            format_name: None,
+            sanitize_names: false,
+            lowercase_names: false,
+            stats_json: None,
+            sample_qc: None,
+            quiet: false,
+            verbosity: 0,
         };
 
         assert_eq!(params.compression(), parquet::basic::Compression::SNAPPY);
@@ -251,6 +713,7 @@ mod tests {
         params = Command {
             input: std::path::Path::new("test/input.vcf").to_path_buf(),
             batch_size: None,
+            memory_budget: None,
             compression: Some(Compression::Uncompressed),
             read_buffer: None,
             subcommand: SubCommand::Convert(Convert {
@@ -258,6 +721,33 @@ mod tests {
             }),
             info_optional: false,
             parquet_version: None,
+            dictionary: vec![],
+            columns: vec![],
+            samples: vec![],
+            variant_id: false,
+            variant_hash: false,
+            normalize: false,
+            reference: None,
+            classify: false,
+            sv: false,
+            context: None,
+            check_reference: false,
+            fill_reference: false,
+            genotype_stats: false,
+            type_hints: vec![],
+            info_float64: false,
+            compact_integer: false,
+            schema_override: None,
+            undeclared: None,
+            lenient: false,
+            info_name: None,
+            format_name: None,
+            sanitize_names: false,
+            lowercase_names: false,
+            stats_json: None,
+            sample_qc: None,
+            quiet: false,
+            verbosity: 0,
         };
 
         assert_eq!(
@@ -268,6 +758,7 @@ mod tests {
         params = Command {
             input: std::path::Path::new("test/input.vcf").to_path_buf(),
             batch_size: None,
+            memory_budget: None,
             compression: Some(Compression::Snappy),
             read_buffer: None,
             subcommand: SubCommand::Convert(Convert {
@@ -275,6 +766,33 @@ mod tests {
             }),
             info_optional: false,
             parquet_version: None,
+            dictionary: vec![],
+            columns: vec![],
+            samples: vec![],
+            variant_id: false,
+            variant_hash: false,
+            normalize: false,
+            reference: None,
+            classify: false,
+            sv: false,
+            context: None,
+            check_reference: false,
+            fill_reference: false,
+            genotype_stats: false,
+            type_hints: vec![],
+            info_float64: false,
+            compact_integer: false,
+            schema_override: None,
+            undeclared: None,
+            lenient: false,
+            info_name: None,
+            format_name: None,
+            sanitize_names: false,
+            lowercase_names: false,
+            stats_json: None,
+            sample_qc: None,
+            quiet: false,
+            verbosity: 0,
         };
 
         assert_eq!(params.compression(), parquet::basic::Compression::SNAPPY);
@@ -282,6 +800,7 @@ mod tests {
         params = Command {
             input: std::path::Path::new("test/input.vcf").to_path_buf(),
             batch_size: None,
+            memory_budget: None,
             compression: Some(Compression::Gzip),
             read_buffer: None,
             subcommand: SubCommand::Convert(Convert {
@@ -289,6 +808,33 @@ mod tests {
             }),
             info_optional: false,
             parquet_version: None,
+            dictionary: vec![],
+            columns: vec![],
+            samples: vec![],
+            variant_id: false,
+            variant_hash: false,
+            normalize: false,
+            reference: None,
+            classify: false,
+            sv: false,
+            context: None,
+            check_reference: false,
+            fill_reference: false,
+            genotype_stats: false,
+            type_hints: vec![],
+            info_float64: false,
+            compact_integer: false,
+            schema_override: None,
+            undeclared: None,
+            lenient: false,
+            info_name: None,
+            format_name: None,
+            sanitize_names: false,
+            lowercase_names: false,
+            stats_json: None,
+            sample_qc: None,
+            quiet: false,
+            verbosity: 0,
         };
 
         assert_eq!(
@@ -299,6 +845,7 @@ mod tests {
         params = Command {
             input: std::path::Path::new("test/input.vcf").to_path_buf(),
             batch_size: None,
+            memory_budget: None,
             compression: Some(Compression::Lzo),
             read_buffer: None,
             subcommand: SubCommand::Convert(Convert {
@@ -306,6 +853,33 @@ mod tests {
             }),
             info_optional: false,
             parquet_version: None,
+            dictionary: vec![],
+            columns: vec![],
+            samples: vec![],
+            variant_id: false,
+            variant_hash: false,
+            normalize: false,
+            reference: None,
+            classify: false,
+            sv: false,
+            context: None,
+            check_reference: false,
+            fill_reference: false,
+            genotype_stats: false,
+            type_hints: vec![],
+            info_float64: false,
+            compact_integer: false,
+            schema_override: None,
+            undeclared: None,
+            lenient: false,
+            info_name: None,
+            format_name: None,
+            sanitize_names: false,
+            lowercase_names: false,
+            stats_json: None,
+            sample_qc: None,
+            quiet: false,
+            verbosity: 0,
         };
 
         assert_eq!(params.compression(), parquet::basic::Compression::LZO);
@@ -313,6 +887,7 @@ mod tests {
         params = Command {
             input: std::path::Path::new("test/input.vcf").to_path_buf(),
             batch_size: None,
+            memory_budget: None,
             compression: Some(Compression::Brotli),
             read_buffer: None,
             subcommand: SubCommand::Convert(Convert {
@@ -320,6 +895,33 @@ mod tests {
             }),
             info_optional: false,
             parquet_version: None,
+            dictionary: vec![],
+            columns: vec![],
+            samples: vec![],
+            variant_id: false,
+            variant_hash: false,
+            normalize: false,
+            reference: None,
+            classify: false,
+            sv: false,
+            context: None,
+            check_reference: false,
+            fill_reference: false,
+            genotype_stats: false,
+            type_hints: vec![],
+            info_float64: false,
+            compact_integer: false,
+            schema_override: None,
+            undeclared: None,
+            lenient: false,
+            info_name: None,
+            format_name: None,
+            sanitize_names: false,
+            lowercase_names: false,
+            stats_json: None,
+            sample_qc: None,
+            quiet: false,
+            verbosity: 0,
         };
 
         assert_eq!(
@@ -330,6 +932,7 @@ mod tests {
         params = Command {
             input: std::path::Path::new("test/input.vcf").to_path_buf(),
             batch_size: None,
+            memory_budget: None,
             compression: Some(Compression::Lz4),
             read_buffer: None,
             subcommand: SubCommand::Convert(Convert {
@@ -337,6 +940,33 @@ mod tests {
             }),
             info_optional: false,
             parquet_version: None,
+            dictionary: vec![],
+            columns: vec![],
+            samples: vec![],
+            variant_id: false,
+            variant_hash: false,
+            normalize: false,
+            reference: None,
+            classify: false,
+            sv: false,
+            context: None,
+            check_reference: false,
+            fill_reference: false,
+            genotype_stats: false,
+            type_hints: vec![],
+            info_float64: false,
+            compact_integer: false,
+            schema_override: None,
+            undeclared: None,
+            lenient: false,
+            info_name: None,
+            format_name: None,
+            sanitize_names: false,
+            lowercase_names: false,
+            stats_json: None,
+            sample_qc: None,
+            quiet: false,
+            verbosity: 0,
         };
 
         assert_eq!(params.compression(), parquet::basic::Compression::LZ4);
@@ -0,0 +1,213 @@
+//! Aggregate genotype statistics of a variant across samples
+
+/* std use */
+
+/* crate use */
+
+/* project use */
+
+/// Genotype counts of an alternate allele across samples
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Counts {
+    samples: usize,
+    allele_count: usize,
+    allele_number: usize,
+    het: usize,
+    hom_alt: usize,
+    missing: usize,
+    diploid: [usize; 3],
+}
+
+impl Counts {
+    /// Create a new Counts
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add genotype of a sample, `alleles` are allele index of genotype, None for missing allele or genotype, `allele` is index of alternate allele
+    pub fn add<I>(&mut self, alleles: Option<I>, allele: usize)
+    where
+        I: Iterator<Item = Option<usize>>,
+    {
+        self.samples += 1;
+
+        let Some(alleles) = alleles else {
+            self.missing += 1;
+            return;
+        };
+
+        let (mut called, mut copies, mut missing) = (0, 0, false);
+        for position in alleles {
+            match position {
+                Some(a) => {
+                    called += 1;
+                    copies += usize::from(a == allele);
+                }
+                None => missing = true,
+            }
+        }
+
+        self.allele_number += called;
+        self.allele_count += copies;
+
+        if missing || called == 0 {
+            self.missing += 1;
+        } else if copies == called {
+            self.hom_alt += 1;
+        } else if copies != 0 {
+            self.het += 1;
+        }
+
+        if !missing && called == 2 {
+            self.diploid[copies] += 1;
+        }
+    }
+
+    /// Get number of copies of alternate allele in called genotypes
+    pub fn allele_count(&self) -> usize {
+        self.allele_count
+    }
+
+    /// Get number of called alleles
+    pub fn allele_number(&self) -> usize {
+        self.allele_number
+    }
+
+    /// Get frequency of alternate allele, None if no allele is called
+    pub fn allele_frequency(&self) -> Option<f64> {
+        (self.allele_number != 0).then(|| self.allele_count as f64 / self.allele_number as f64)
+    }
+
+    /// Get number of samples that carry alternate allele and another allele
+    pub fn het(&self) -> usize {
+        self.het
+    }
+
+    /// Get number of samples that carry only alternate allele
+    pub fn hom_alt(&self) -> usize {
+        self.hom_alt
+    }
+
+    /// Get number of samples without genotype or with a missing allele
+    pub fn missing(&self) -> usize {
+        self.missing
+    }
+
+    /// Get fraction of samples with a complete genotype, None if there isn't sample
+    pub fn call_rate(&self) -> Option<f64> {
+        (self.samples != 0).then(|| (self.samples - self.missing) as f64 / self.samples as f64)
+    }
+
+    /// Get p-value of Hardy-Weinberg equilibrium exact test, on diploid genotypes, other alleles are count as reference
+    pub fn hwe(&self) -> Option<f64> {
+        hwe(self.diploid[1], self.diploid[0], self.diploid[2])
+    }
+}
+
+/// Hardy-Weinberg equilibrium exact test of Wigginton et al. 2005, None if there isn't genotype
+pub fn hwe(het: usize, hom_1: usize, hom_2: usize) -> Option<f64> {
+    let genotypes = het + hom_1 + hom_2;
+    if genotypes == 0 {
+        return None;
+    }
+
+    let rare = 2 * hom_1.min(hom_2) + het;
+    let mut probabilities = vec![0.0; rare + 1];
+
+    // start from most likely number of heterozygous, with same parity than rare allele count
+    let mut middle = rare * (2 * genotypes - rare) / (2 * genotypes);
+    if middle % 2 != rare % 2 {
+        middle += 1;
+    }
+    probabilities[middle] = 1.0;
+
+    let (mut hets, mut hom_rare) = (middle, (rare - middle) / 2);
+    let mut hom_common = genotypes - hets - hom_rare;
+    while hets >= 2 {
+        probabilities[hets - 2] = probabilities[hets] * (hets * (hets - 1)) as f64
+            / (4 * (hom_rare + 1) * (hom_common + 1)) as f64;
+        hets -= 2;
+        hom_rare += 1;
+        hom_common += 1;
+    }
+
+    let (mut hets, mut hom_rare) = (middle, (rare - middle) / 2);
+    let mut hom_common = genotypes - hets - hom_rare;
+    while hets + 2 <= rare {
+        probabilities[hets + 2] = probabilities[hets] * (4 * hom_rare * hom_common) as f64
+            / ((hets + 2) * (hets + 1)) as f64;
+        hets += 2;
+        hom_rare -= 1;
+        hom_common -= 1;
+    }
+
+    let total = probabilities.iter().sum::<f64>();
+    let observed = probabilities[het];
+    let pvalue = probabilities
+        .iter()
+        .filter(|p| **p <= observed * (1.0 + 1e-8))
+        .sum::<f64>()
+        / total;
+
+    Some(pvalue.min(1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gt(
+        alleles: &[Option<usize>],
+    ) -> Option<std::iter::Copied<std::slice::Iter<'_, Option<usize>>>> {
+        Some(alleles.iter().copied())
+    }
+
+    #[test]
+    fn counts() {
+        let mut counts = Counts::new();
+
+        counts.add(gt(&[Some(0), Some(1)]), 1);
+        counts.add(gt(&[Some(1), Some(1)]), 1);
+        counts.add(gt(&[Some(1), Some(2)]), 1);
+        counts.add(gt(&[Some(0), Some(0)]), 1);
+        counts.add(gt(&[None, Some(1)]), 1);
+        counts.add(gt(&[Some(1)]), 1);
+        counts.add(None::<std::iter::Empty<Option<usize>>>, 1);
+
+        assert_eq!(counts.allele_count(), 6);
+        assert_eq!(counts.allele_number(), 10);
+        assert_eq!(counts.allele_frequency(), Some(0.6));
+        assert_eq!(counts.het(), 2);
+        assert_eq!(counts.hom_alt(), 2);
+        assert_eq!(counts.missing(), 2);
+        assert_eq!(counts.call_rate(), Some(5.0 / 7.0));
+
+        // second alternate allele
+        let mut counts = Counts::new();
+        counts.add(gt(&[Some(1), Some(2)]), 2);
+        counts.add(gt(&[Some(2), Some(2)]), 2);
+        assert_eq!(counts.allele_count(), 3);
+        assert_eq!(counts.het(), 1);
+        assert_eq!(counts.hom_alt(), 1);
+
+        let empty = Counts::new();
+        assert_eq!(empty.allele_frequency(), None);
+        assert_eq!(empty.call_rate(), None);
+        assert_eq!(empty.hwe(), None);
+    }
+
+    #[test]
+    fn hardy_weinberg() {
+        // population at equilibrium
+        assert!((hwe(50, 25, 25).unwrap() - 1.0).abs() < 1e-6);
+        assert_eq!(hwe(0, 10, 0), Some(1.0));
+
+        // no heterozygous
+        assert!(hwe(0, 50, 50).unwrap() < 1e-20);
+
+        // values of direct computation of genotype probabilities
+        assert!((hwe(57, 14, 29).unwrap() - 0.150680).abs() < 1e-6);
+        assert!((hwe(3, 10, 1).unwrap() - 0.348148).abs() < 1e-6);
+        assert_eq!(hwe(3, 10, 1), hwe(3, 1, 10));
+    }
+}
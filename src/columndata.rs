@@ -8,111 +8,231 @@ use arrow::array::ArrayBuilder;
 /* project use */
 
 /// Stores arrow array builders for each column datatype
-#[derive(Debug)]
 pub enum ColumnData {
     /// Boolean column
     Bool(arrow::array::BooleanBuilder),
+    /// Int8 column
+    Int8(arrow::array::Int8Builder),
+    /// Int16 column
+    Int16(arrow::array::Int16Builder),
     /// Int32 column
     Int(arrow::array::Int32Builder),
+    /// Int64 column
+    Int64(arrow::array::Int64Builder),
+    /// Float16 column
+    Float16(arrow::array::Float16Builder),
     /// Float32 column
     Float(arrow::array::Float32Builder),
+    /// Float64 column
+    Float64(arrow::array::Float64Builder),
     /// String column
     String(arrow::array::StringBuilder),
+    /// Dictionary of string column
+    DictString(arrow::array::StringDictionaryBuilder<arrow::datatypes::Int32Type>),
 
+    /// List of int8 column
+    ListInt8(arrow::array::ListBuilder<arrow::array::Int8Builder>),
+    /// List of int16 column
+    ListInt16(arrow::array::ListBuilder<arrow::array::Int16Builder>),
     /// List of int32 column
     ListInt(arrow::array::ListBuilder<arrow::array::Int32Builder>),
+    /// List of float16 column
+    ListFloat16(arrow::array::ListBuilder<arrow::array::Float16Builder>),
     /// List of float32 column
     ListFloat(arrow::array::ListBuilder<arrow::array::Float32Builder>),
+    /// List of float64 column
+    ListFloat64(arrow::array::ListBuilder<arrow::array::Float64Builder>),
     /// List of string column
     ListString(arrow::array::ListBuilder<arrow::array::StringBuilder>),
+    /// List of dictionary of string column
+    ListDictString(
+        arrow::array::ListBuilder<
+            arrow::array::StringDictionaryBuilder<arrow::datatypes::Int32Type>,
+        >,
+    ),
+
+    /// Map of string column
+    Map(arrow::array::MapBuilder<arrow::array::StringBuilder, arrow::array::StringBuilder>),
+}
+
+impl std::fmt::Debug for ColumnData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // arrow dictionary builders don't implement Debug
+        let name = match self {
+            ColumnData::Bool(_) => "Bool",
+            ColumnData::Int8(_) => "Int8",
+            ColumnData::Int16(_) => "Int16",
+            ColumnData::Int(_) => "Int",
+            ColumnData::Int64(_) => "Int64",
+            ColumnData::Float16(_) => "Float16",
+            ColumnData::Float(_) => "Float",
+            ColumnData::Float64(_) => "Float64",
+            ColumnData::String(_) => "String",
+            ColumnData::DictString(_) => "DictString",
+
+            ColumnData::ListInt8(_) => "ListInt8",
+            ColumnData::ListInt16(_) => "ListInt16",
+            ColumnData::ListInt(_) => "ListInt",
+            ColumnData::ListFloat16(_) => "ListFloat16",
+            ColumnData::ListFloat(_) => "ListFloat",
+            ColumnData::ListFloat64(_) => "ListFloat64",
+            ColumnData::ListString(_) => "ListString",
+            ColumnData::ListDictString(_) => "ListDictString",
+
+            ColumnData::Map(_) => "Map",
+        };
+
+        f.debug_struct(name).field("len", &self.len()).finish()
+    }
 }
 
 impl ColumnData {
-    /// Creates a new ColumnData based on arrow type, length and field name
+    /// Creates a new ColumnData based on arrow type, length and field name, return an error if arrow type isn't supported
     pub fn new(
         arrow_type: &arrow::datatypes::DataType,
         length: usize,
         field_name: &str,
         nullable: bool,
-    ) -> Self {
-        match arrow_type {
+    ) -> arrow::error::Result<Self> {
+        Ok(match arrow_type {
             arrow::datatypes::DataType::Boolean => {
                 ColumnData::Bool(arrow::array::BooleanBuilder::with_capacity(length))
             }
+            arrow::datatypes::DataType::Int8 => {
+                ColumnData::Int8(arrow::array::Int8Builder::with_capacity(length))
+            }
+            arrow::datatypes::DataType::Int16 => {
+                ColumnData::Int16(arrow::array::Int16Builder::with_capacity(length))
+            }
             arrow::datatypes::DataType::Int32 => {
                 ColumnData::Int(arrow::array::Int32Builder::with_capacity(length))
             }
+            arrow::datatypes::DataType::Int64 => {
+                ColumnData::Int64(arrow::array::Int64Builder::with_capacity(length))
+            }
+            arrow::datatypes::DataType::Float16 => {
+                ColumnData::Float16(arrow::array::Float16Builder::with_capacity(length))
+            }
             arrow::datatypes::DataType::Float32 => {
                 ColumnData::Float(arrow::array::Float32Builder::with_capacity(length))
             }
+            arrow::datatypes::DataType::Float64 => {
+                ColumnData::Float64(arrow::array::Float64Builder::with_capacity(length))
+            }
             arrow::datatypes::DataType::Utf8 => ColumnData::String(
                 arrow::array::StringBuilder::with_capacity(length, length * 10),
             ),
+            arrow::datatypes::DataType::Dictionary(_, _) => ColumnData::DictString(
+                arrow::array::StringDictionaryBuilder::with_capacity(length, length, length * 10),
+            ),
             arrow::datatypes::DataType::List(field) => match field.data_type() {
-                arrow::datatypes::DataType::Int32 => ColumnData::ListInt(
-                    arrow::array::ListBuilder::with_capacity(
-                        arrow::array::Int32Builder::new(),
-                        length,
-                    )
-                    .with_field(arrow::datatypes::Field::new(
-                        field_name,
-                        arrow::datatypes::DataType::Int32,
-                        nullable,
-                    )),
-                ),
-                arrow::datatypes::DataType::Float32 => ColumnData::ListFloat(
+                arrow::datatypes::DataType::Int8 => {
+                    ColumnData::ListInt8(primitive_list(length, field_name, nullable))
+                }
+                arrow::datatypes::DataType::Int16 => {
+                    ColumnData::ListInt16(primitive_list(length, field_name, nullable))
+                }
+                arrow::datatypes::DataType::Int32 => {
+                    ColumnData::ListInt(primitive_list(length, field_name, nullable))
+                }
+                arrow::datatypes::DataType::Float16 => {
+                    ColumnData::ListFloat16(primitive_list(length, field_name, nullable))
+                }
+                arrow::datatypes::DataType::Float32 => {
+                    ColumnData::ListFloat(primitive_list(length, field_name, nullable))
+                }
+                arrow::datatypes::DataType::Float64 => {
+                    ColumnData::ListFloat64(primitive_list(length, field_name, nullable))
+                }
+                arrow::datatypes::DataType::Utf8 => ColumnData::ListString(
                     arrow::array::ListBuilder::with_capacity(
-                        arrow::array::Float32Builder::new(),
+                        arrow::array::StringBuilder::new(),
                         length,
                     )
                     .with_field(arrow::datatypes::Field::new(
                         field_name,
-                        arrow::datatypes::DataType::Float32,
+                        arrow::datatypes::DataType::Utf8,
                         nullable,
                     )),
                 ),
-                arrow::datatypes::DataType::Utf8 => ColumnData::ListString(
+                arrow::datatypes::DataType::Dictionary(_, _) => ColumnData::ListDictString(
                     arrow::array::ListBuilder::with_capacity(
-                        arrow::array::StringBuilder::new(),
+                        arrow::array::StringDictionaryBuilder::new(),
                         length,
                     )
                     .with_field(arrow::datatypes::Field::new(
                         field_name,
-                        arrow::datatypes::DataType::Utf8,
+                        field.data_type().clone(),
                         nullable,
                     )),
                 ),
-                _ => todo!(),
+                dt => return Err(unsupported(field_name, dt)),
             },
-            dt => unreachable!("Unsupported arrow type, please check Schema: {:?}", dt),
-        }
+            arrow::datatypes::DataType::Map(_, _) => {
+                ColumnData::Map(arrow::array::MapBuilder::with_capacity(
+                    None,
+                    arrow::array::StringBuilder::new(),
+                    arrow::array::StringBuilder::new(),
+                    length,
+                ))
+            }
+            dt => return Err(unsupported(field_name, dt)),
+        })
     }
 
     /// Add a Null value in array
-    pub fn push_null(&mut self) {
+    pub fn push_null(&mut self) -> arrow::error::Result<()> {
         match self {
             ColumnData::Bool(a) => a.append_null(),
+            ColumnData::Int8(a) => a.append_null(),
+            ColumnData::Int16(a) => a.append_null(),
             ColumnData::Int(a) => a.append_null(),
+            ColumnData::Int64(a) => a.append_null(),
+            ColumnData::Float16(a) => a.append_null(),
             ColumnData::Float(a) => a.append_null(),
+            ColumnData::Float64(a) => a.append_null(),
             ColumnData::String(a) => a.append_null(),
+            ColumnData::DictString(a) => a.append_null(),
 
+            ColumnData::ListInt8(a) => a.append_null(),
+            ColumnData::ListInt16(a) => a.append_null(),
             ColumnData::ListInt(a) => a.append_null(),
+            ColumnData::ListFloat16(a) => a.append_null(),
             ColumnData::ListFloat(a) => a.append_null(),
+            ColumnData::ListFloat64(a) => a.append_null(),
             ColumnData::ListString(a) => a.append_null(),
+            ColumnData::ListDictString(a) => a.append_null(),
+
+            ColumnData::Map(a) => return a.append(false),
         }
+
+        Ok(())
     }
 
     /// Get the length of internal array
     pub fn len(&self) -> usize {
         match self {
             ColumnData::Bool(a) => a.len(),
+            ColumnData::Int8(a) => a.len(),
+            ColumnData::Int16(a) => a.len(),
             ColumnData::Int(a) => a.len(),
+            ColumnData::Int64(a) => a.len(),
+            ColumnData::Float16(a) => a.len(),
             ColumnData::Float(a) => a.len(),
+            ColumnData::Float64(a) => a.len(),
             ColumnData::String(a) => a.len(),
+            ColumnData::DictString(a) => a.len(),
 
+            ColumnData::ListInt8(a) => a.len(),
+            ColumnData::ListInt16(a) => a.len(),
             ColumnData::ListInt(a) => a.len(),
+            ColumnData::ListFloat16(a) => a.len(),
             ColumnData::ListFloat(a) => a.len(),
+            ColumnData::ListFloat64(a) => a.len(),
             ColumnData::ListString(a) => a.len(),
+            ColumnData::ListDictString(a) => a.len(),
+
+            ColumnData::Map(a) => a.len(),
         }
     }
 
@@ -121,76 +241,167 @@ impl ColumnData {
         self.len() == 0
     }
 
-    /// Add a boolean value in array, if it's not a boolean array failled
-    pub fn push_bool(&mut self, value: bool) {
+    /// Estimate memory used by builder in bytes, allocated capacity for primitive values and written bytes for others
+    pub fn memory(&self) -> usize {
+        match self {
+            ColumnData::Bool(a) => a.capacity() / 8,
+            ColumnData::Int8(a) => primitive_memory(a),
+            ColumnData::Int16(a) => primitive_memory(a),
+            ColumnData::Int(a) => primitive_memory(a),
+            ColumnData::Int64(a) => primitive_memory(a),
+            ColumnData::Float16(a) => primitive_memory(a),
+            ColumnData::Float(a) => primitive_memory(a),
+            ColumnData::Float64(a) => primitive_memory(a),
+            ColumnData::String(a) => string_memory(a),
+            // values of dictionary are deduplicate, only keys are count
+            ColumnData::DictString(a) => a.len() * 4,
+
+            ColumnData::ListInt8(a) => {
+                a.offsets_slice().len() * 4 + primitive_memory(a.values_ref())
+            }
+            ColumnData::ListInt16(a) => {
+                a.offsets_slice().len() * 4 + primitive_memory(a.values_ref())
+            }
+            ColumnData::ListInt(a) => {
+                a.offsets_slice().len() * 4 + primitive_memory(a.values_ref())
+            }
+            ColumnData::ListFloat16(a) => {
+                a.offsets_slice().len() * 4 + primitive_memory(a.values_ref())
+            }
+            ColumnData::ListFloat(a) => {
+                a.offsets_slice().len() * 4 + primitive_memory(a.values_ref())
+            }
+            ColumnData::ListFloat64(a) => {
+                a.offsets_slice().len() * 4 + primitive_memory(a.values_ref())
+            }
+            ColumnData::ListString(a) => {
+                a.offsets_slice().len() * 4 + string_memory(a.values_ref())
+            }
+            ColumnData::ListDictString(a) => a.offsets_slice().len() * 4 + a.values_ref().len() * 4,
+
+            // keys and values builder can't be read without mutable access, count an offset and an entry by row
+            ColumnData::Map(a) => a.len() * (4 + 2 * STRING_ESTIMATE),
+        }
+    }
+
+    /// Add a boolean value in array, if it's not a boolean array return an error
+    pub fn push_bool(&mut self, value: bool) -> arrow::error::Result<()> {
         match self {
             ColumnData::Bool(a) => a.append_value(value),
-            _ => todo!(),
+            _ => return Err(self.mismatch("boolean")),
         }
+
+        Ok(())
     }
 
-    /// Add a i32 value in array, if it's not a integer array failled
-    pub fn push_i32(&mut self, value: Option<i32>) {
+    /// Add a i32 value in array, if it's not a integer array or value didn't fit in array type return an error
+    pub fn push_i32(&mut self, value: Option<i32>) -> arrow::error::Result<()> {
         match self {
+            ColumnData::Int8(a) => a.append_option(value.map(narrow).transpose()?),
+            ColumnData::Int16(a) => a.append_option(value.map(narrow).transpose()?),
             ColumnData::Int(a) => a.append_option(value),
-            _ => todo!(),
+            _ => return Err(self.mismatch("integer")),
         }
+
+        Ok(())
     }
 
-    /// Add a f32 value in array, if it's not a float array failled
-    pub fn push_f32(&mut self, value: Option<f32>) {
+    /// Add a i64 value in array, if it's not a 64 bits integer array return an error
+    pub fn push_i64(&mut self, value: i64) -> arrow::error::Result<()> {
         match self {
+            ColumnData::Int64(a) => a.append_value(value),
+            _ => return Err(self.mismatch("64 bits integer")),
+        }
+
+        Ok(())
+    }
+
+    /// Add a f32 value in array, if it's not a float array return an error
+    pub fn push_f32(&mut self, value: Option<f32>) -> arrow::error::Result<()> {
+        match self {
+            ColumnData::Float16(a) => a.append_option(value.map(half::f16::from_f32)),
             ColumnData::Float(a) => a.append_option(value),
-            _ => todo!(),
+            ColumnData::Float64(a) => a.append_option(value.map(widen)),
+            _ => return Err(self.mismatch("float")),
         }
+
+        Ok(())
     }
 
-    /// Add a string value in array, if it's not a string array failled
-    pub fn push_string(&mut self, value: String) {
+    /// Add a f64 value in array, if it's not a 64 bits float array return an error
+    pub fn push_f64(&mut self, value: Option<f64>) -> arrow::error::Result<()> {
         match self {
-            ColumnData::String(a) => a.append_option(Some(value)),
-            _ => todo!(),
+            ColumnData::Float64(a) => a.append_option(value),
+            _ => return Err(self.mismatch("64 bits float")),
         }
+
+        Ok(())
     }
 
-    /// Add a vector of integer value in array, if it's not a vector of integer array failled
-    pub fn push_veci32(&mut self, value: Vec<Option<i32>>) -> arrow::error::Result<()> {
+    /// Add a string value in array, if it's not a string array return an error
+    pub fn push_string(&mut self, value: impl AsRef<str>) -> arrow::error::Result<()> {
         match self {
-            ColumnData::ListInt(a) => {
-                a.values().append_values(
-                    &value
-                        .iter()
-                        .map(|v| v.unwrap_or_default())
-                        .collect::<Vec<i32>>(),
-                    &value.iter().map(|v| v.is_some()).collect::<Vec<bool>>(),
-                );
-                a.append(true);
-                Ok(())
-            }
-            _ => todo!(),
+            ColumnData::String(a) => a.append_value(value),
+            ColumnData::DictString(a) => a.append_value(value),
+            _ => return Err(self.mismatch("string")),
         }
+
+        Ok(())
     }
 
-    /// Add a vector of float value in array, if it's not a vector of float array failled
-    pub fn push_vecf32(&mut self, value: Vec<Option<f32>>) -> arrow::error::Result<()> {
+    /// Add a vector of integer value in array, if it's not a vector of integer array return an error
+    pub fn push_veci32<I>(&mut self, value: I) -> arrow::error::Result<()>
+    where
+        I: IntoIterator<Item = Option<i32>>,
+        I::IntoIter: Clone,
+    {
+        let value = value.into_iter();
         match self {
-            ColumnData::ListFloat(a) => {
-                a.values().append_values(
-                    &value
-                        .iter()
-                        .map(|v| v.unwrap_or_default())
-                        .collect::<Vec<f32>>(),
-                    &value.iter().map(|v| v.is_some()).collect::<Vec<bool>>(),
-                );
-                a.append(true);
-                Ok(())
+            ColumnData::ListInt8(a) => push_narrow_list(a, value)?,
+            ColumnData::ListInt16(a) => push_narrow_list(a, value)?,
+            ColumnData::ListInt(a) => push_primitive_list(a, value),
+            _ => return Err(self.mismatch("list of integer")),
+        }
+        Ok(())
+    }
+
+    /// Add a vector of float value in array, if it's not a vector of float array return an error
+    pub fn push_vecf32(
+        &mut self,
+        value: impl IntoIterator<Item = Option<f32>>,
+    ) -> arrow::error::Result<()> {
+        let value = value.into_iter();
+        match self {
+            ColumnData::ListFloat16(a) => {
+                push_primitive_list(a, value.map(|v| v.map(half::f16::from_f32)))
             }
-            _ => todo!(),
+            ColumnData::ListFloat(a) => push_primitive_list(a, value),
+            ColumnData::ListFloat64(a) => push_primitive_list(a, value.map(|v| v.map(widen))),
+            _ => return Err(self.mismatch("list of float")),
         }
+        Ok(())
     }
 
-    /// Add a vector of string value in array, if it's not a vector of string array failled
-    pub fn push_vecstring(&mut self, value: Vec<Option<String>>) -> arrow::error::Result<()> {
+    /// Add a vector of 64 bits float value in array, if it's not a vector of 64 bits float array return an error
+    pub fn push_vecf64(
+        &mut self,
+        value: impl IntoIterator<Item = Option<f64>>,
+    ) -> arrow::error::Result<()> {
+        match self {
+            ColumnData::ListFloat64(a) => push_primitive_list(a, value.into_iter()),
+            _ => return Err(self.mismatch("list of 64 bits float")),
+        }
+        Ok(())
+    }
+
+    /// Add a vector of string value in array, if it's not a vector of string array return an error
+    pub fn push_vecstring<S>(
+        &mut self,
+        value: impl IntoIterator<Item = Option<S>>,
+    ) -> arrow::error::Result<()>
+    where
+        S: AsRef<str>,
+    {
         match self {
             ColumnData::ListString(a) => {
                 for v in value {
@@ -199,7 +410,28 @@ impl ColumnData {
                 a.append(true);
                 Ok(())
             }
-            _ => todo!(),
+            ColumnData::ListDictString(a) => {
+                for v in value {
+                    a.values().append_option(v);
+                }
+                a.append(true);
+                Ok(())
+            }
+            _ => Err(self.mismatch("list of string")),
+        }
+    }
+
+    /// Add a map of string in array, if it's not a map array return an error
+    pub fn push_map(&mut self, value: Vec<(String, Option<String>)>) -> arrow::error::Result<()> {
+        match self {
+            ColumnData::Map(a) => {
+                for (k, v) in value {
+                    a.keys().append_value(k);
+                    a.values().append_option(v);
+                }
+                a.append(true)
+            }
+            _ => Err(self.mismatch("map")),
         }
     }
 
@@ -209,12 +441,222 @@ impl ColumnData {
 
         match self {
             ColumnData::Bool(mut a) => arrow::array::Array::slice(&a.finish(), 0, length),
+            ColumnData::Int8(mut a) => arrow::array::Array::slice(&a.finish(), 0, length),
+            ColumnData::Int16(mut a) => arrow::array::Array::slice(&a.finish(), 0, length),
             ColumnData::Int(mut a) => arrow::array::Array::slice(&a.finish(), 0, length),
+            ColumnData::Int64(mut a) => arrow::array::Array::slice(&a.finish(), 0, length),
+            ColumnData::Float16(mut a) => arrow::array::Array::slice(&a.finish(), 0, length),
             ColumnData::Float(mut a) => arrow::array::Array::slice(&a.finish(), 0, length),
+            ColumnData::Float64(mut a) => arrow::array::Array::slice(&a.finish(), 0, length),
             ColumnData::String(mut a) => arrow::array::Array::slice(&a.finish(), 0, length),
+            ColumnData::DictString(mut a) => arrow::array::Array::slice(&a.finish(), 0, length),
+            ColumnData::ListInt8(mut a) => arrow::array::Array::slice(&a.finish(), 0, length),
+            ColumnData::ListInt16(mut a) => arrow::array::Array::slice(&a.finish(), 0, length),
             ColumnData::ListInt(mut a) => arrow::array::Array::slice(&a.finish(), 0, length),
+            ColumnData::ListFloat16(mut a) => arrow::array::Array::slice(&a.finish(), 0, length),
             ColumnData::ListFloat(mut a) => arrow::array::Array::slice(&a.finish(), 0, length),
+            ColumnData::ListFloat64(mut a) => arrow::array::Array::slice(&a.finish(), 0, length),
             ColumnData::ListString(mut a) => arrow::array::Array::slice(&a.finish(), 0, length),
+            ColumnData::ListDictString(mut a) => arrow::array::Array::slice(&a.finish(), 0, length),
+            ColumnData::Map(mut a) => arrow::array::Array::slice(&a.finish(), 0, length),
         }
     }
+
+    fn mismatch(&self, expected: &str) -> arrow::error::ArrowError {
+        arrow::error::ArrowError::InvalidArgumentError(format!(
+            "Try to push a {} value in a {:?} column",
+            expected, self
+        ))
+    }
+}
+
+fn unsupported(
+    field_name: &str,
+    arrow_type: &arrow::datatypes::DataType,
+) -> arrow::error::ArrowError {
+    arrow::error::ArrowError::NotYetImplemented(format!(
+        "Column {} have an unsupported arrow type {:?}",
+        field_name, arrow_type
+    ))
+}
+
+fn primitive_list<T>(
+    length: usize,
+    field_name: &str,
+    nullable: bool,
+) -> arrow::array::ListBuilder<arrow::array::PrimitiveBuilder<T>>
+where
+    T: arrow::datatypes::ArrowPrimitiveType,
+{
+    arrow::array::ListBuilder::with_capacity(arrow::array::PrimitiveBuilder::<T>::new(), length)
+        .with_field(arrow::datatypes::Field::new(
+            field_name,
+            T::DATA_TYPE,
+            nullable,
+        ))
+}
+
+/// Average number of bytes of a string value, used when real size can't be read
+pub(crate) const STRING_ESTIMATE: usize = 14;
+
+fn primitive_memory<T>(builder: &arrow::array::PrimitiveBuilder<T>) -> usize
+where
+    T: arrow::datatypes::ArrowPrimitiveType,
+{
+    builder.capacity() * std::mem::size_of::<T::Native>()
+}
+
+fn string_memory(builder: &arrow::array::StringBuilder) -> usize {
+    builder.values_slice().len() + builder.offsets_slice().len() * 4
+}
+
+fn push_primitive_list<T>(
+    builder: &mut arrow::array::ListBuilder<arrow::array::PrimitiveBuilder<T>>,
+    value: impl Iterator<Item = Option<T::Native>>,
+) where
+    T: arrow::datatypes::ArrowPrimitiveType,
+{
+    for v in value {
+        builder.values().append_option(v);
+    }
+    builder.append(true);
+}
+
+/// Values are check before any insertion, a list isn't partially add if a value doesn't fit
+fn push_narrow_list<T>(
+    builder: &mut arrow::array::ListBuilder<arrow::array::PrimitiveBuilder<T>>,
+    value: impl Iterator<Item = Option<i32>> + Clone,
+) -> arrow::error::Result<()>
+where
+    T: arrow::datatypes::ArrowPrimitiveType,
+    T::Native: TryFrom<i32>,
+{
+    for v in value.clone().flatten() {
+        narrow::<T::Native>(v)?;
+    }
+
+    push_primitive_list(
+        builder,
+        value.map(|v| v.and_then(|v| T::Native::try_from(v).ok())),
+    );
+    Ok(())
+}
+
+fn narrow<T>(value: i32) -> arrow::error::Result<T>
+where
+    T: TryFrom<i32>,
+{
+    T::try_from(value).map_err(|_| {
+        arrow::error::ArrowError::CastError(format!(
+            "Value {} doesn't fit in {}",
+            value,
+            std::any::type_name::<T>()
+        ))
+    })
+}
+
+fn widen(value: f32) -> f64 {
+    // noodles parse float in f32, shortest representation of f32 is the value write in vcf
+    value.to_string().parse().unwrap_or(value as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn narrow_integer() {
+        let mut column =
+            ColumnData::new(&arrow::datatypes::DataType::Int8, 2, "int8", true).unwrap();
+
+        column.push_i32(Some(-128)).unwrap();
+        column.push_i32(None).unwrap();
+        assert!(column.push_i32(Some(128)).is_err());
+
+        let array = column.into_arc();
+        let array = array
+            .as_any()
+            .downcast_ref::<arrow::array::Int8Array>()
+            .unwrap();
+        assert_eq!(array.value(0), -128);
+        assert!(arrow::array::Array::is_null(array, 1));
+    }
+
+    #[test]
+    fn narrow_list() {
+        let mut column =
+            ColumnData::new(
+                &arrow::datatypes::DataType::List(std::sync::Arc::new(
+                    arrow::datatypes::Field::new("int16", arrow::datatypes::DataType::Int16, true),
+                )),
+                1,
+                "int16",
+                true,
+            )
+            .unwrap();
+
+        column.push_veci32(vec![Some(1), None, Some(-300)]).unwrap();
+        assert!(column.push_veci32(vec![Some(40_000)]).is_err());
+    }
+
+    #[test]
+    fn float_width() {
+        let mut half =
+            ColumnData::new(&arrow::datatypes::DataType::Float16, 1, "half", true).unwrap();
+        half.push_f32(Some(0.5)).unwrap();
+        let array = half.into_arc();
+        let array = array
+            .as_any()
+            .downcast_ref::<arrow::array::Float16Array>()
+            .unwrap();
+        assert_eq!(array.value(0), half::f16::from_f32(0.5));
+
+        let mut double =
+            ColumnData::new(&arrow::datatypes::DataType::Float64, 1, "double", true).unwrap();
+        double.push_f32(Some(0.1)).unwrap();
+        let array = double.into_arc();
+        let array = array
+            .as_any()
+            .downcast_ref::<arrow::array::Float64Array>()
+            .unwrap();
+        assert_eq!(array.value(0), 0.1);
+    }
+
+    #[test]
+    fn map() {
+        let mut column = ColumnData::new(&crate::schema::extra_type(), 2, "extra", true).unwrap();
+
+        column
+            .push_map(vec![
+                ("A".to_string(), Some("1".to_string())),
+                ("B".to_string(), None),
+            ])
+            .unwrap();
+        column.push_null().unwrap();
+
+        let array = column.into_arc();
+        assert_eq!(array.data_type(), &crate::schema::extra_type());
+
+        let array = array
+            .as_any()
+            .downcast_ref::<arrow::array::MapArray>()
+            .unwrap();
+        assert_eq!(array.value_length(0), 2);
+        assert!(arrow::array::Array::is_null(array, 1));
+    }
+
+    #[test]
+    fn mismatch() {
+        let mut column =
+            ColumnData::new(&arrow::datatypes::DataType::Int32, 1, "int", true).unwrap();
+
+        assert!(column.push_bool(true).is_err());
+        assert!(column.push_f32(Some(1.0)).is_err());
+        assert!(column.push_string("a").is_err());
+        assert!(column.push_vecstring(Vec::<Option<String>>::new()).is_err());
+        assert!(column.push_map(vec![]).is_err());
+        assert!(column.is_empty());
+
+        assert!(ColumnData::new(&arrow::datatypes::DataType::Date32, 1, "date", true).is_err());
+    }
 }
@@ -14,9 +14,89 @@ pub enum Error {
     #[error("Conversion of arrow type in noodles type isn't supported.")]
     NoConversion,
 
+    /// Column can't be store as dictionary
+    #[error("Column {0} isn't a string column of schema, it can't be store as dictionary.")]
+    DictionaryColumn(String),
+
+    /// Type hint can't be parsed
+    #[error("Type hint {0} can't be parsed, expected format is INFO/KEY=type or FORMAT/KEY=type, type is one of int8, int16, int32, float16, float32, float64.")]
+    TypeHintParse(String),
+
+    /// Type hint not match vcf type
+    #[error("Type hint for field {0} doesn't match type declared in vcf header.")]
+    TypeHint(String),
+
+    /// Integer compaction need to read input twice
+    #[error("Integer compaction read input twice, it isn't available when input is stdin.")]
+    CompactStdin,
+
+    /// Schema override error
+    #[error("Schema override error: {0}")]
+    Override(String),
+
+    /// Undeclared policy can't be parsed
+    #[error("Undeclared key policy {0} can't be parsed, expected value is error, warn or extra.")]
+    UndeclaredParse(String),
+
+    /// Key present in record but not declared in header
+    #[error("Key {0} found in record {1} isn't declared in vcf header.")]
+    UndeclaredKey(String, String),
+
+    /// Logger can't be initialized
+    #[error("Logger initialization failled: {0}")]
+    Log(String),
+
+    /// Value of a field can't be convert in arrow type
+    #[error("Value of field {0} in record {1} can't be convert: {2}")]
+    InvalidValue(String, String, String),
+
+    /// Record can't be parsed
+    #[error("Record {0} can't be parsed: {1}")]
+    InvalidRecord(usize, std::io::Error),
+
+    /// Region can't be parsed
+    #[error("Region {0} can't be parsed, expected format is chr, chr:start- or chr:start-end.")]
+    RegionParse(String),
+
+    /// Selected column isn't in schema
+    #[error("Column {0} isn't in schema, it can't be selected.")]
+    SelectColumn(String),
+
+    /// Selected sample isn't in vcf header
+    #[error("Sample {0} isn't in vcf header, it can't be selected.")]
+    SelectSample(String),
+
+    /// Object storage url can't be parsed
+    #[error("Url {0} can't be parsed: {1}")]
+    Url(String, String),
+
+    /// Parquet content doesn't match vcf
+    #[error("{0} discrepancies found between vcf and parquet.")]
+    Validation(usize),
+
+    /// Column naming template doesn't contain required placeholders
+    #[error("Column name template {0} is invalid, INFO template must contain {{key}} and FORMAT template {{sample}} and {{key}}.")]
+    NamingTemplate(String),
+
+    /// Two fields produce the same column name, case is ignored
+    #[error("Column names {0} and {1} produce by {2} and {3} are equal ignoring case, change naming policy (e.g. --format-name \"format_{{sample}}__{{key}}\") or rename field with schema override.")]
+    ColumnCollision(String, String, String, String),
+
+    /// Export format can't be parsed
+    #[error("Export format {0} can't be parsed, expected value is arrow-json, parquet, duckdb, hive or bigquery.")]
+    ExportFormatParse(String),
+
+    /// Arrow type of column can't be export
+    #[error("Column {0} with type {1} can't be export.")]
+    ExportType(String, String),
+
+    /// Reference fasta can't be read
+    #[error("Reference error: {0}")]
+    Reference(String),
+
     /// Arrow error
     #[error(transparent)]
-    Arrow(#[from] arrow::error::ArrowError),
+    Arrow(arrow::error::ArrowError),
 
     /// Parquet error
     #[error(transparent)]
@@ -30,10 +110,34 @@ pub enum Error {
     #[error(transparent)]
     NoodlesHeader(#[from] noodles::vcf::header::ParseError),
 
+    /// Object store error
+    #[cfg(feature = "object_store")]
+    #[error(transparent)]
+    ObjectStore(#[from] object_store::Error),
+
     /// Niffler error
     #[error(transparent)]
     Niffler(#[from] niffler::Error),
 }
 
+impl From<arrow::error::ArrowError> for Error {
+    fn from(error: arrow::error::ArrowError) -> Self {
+        // Error raise in Record2Chunk are wrap in arrow error, unwrap it
+        match error {
+            arrow::error::ArrowError::ExternalError(e) => match e.downcast::<Error>() {
+                Ok(e) => *e,
+                Err(e) => Error::Arrow(arrow::error::ArrowError::ExternalError(e)),
+            },
+            e => Error::Arrow(e),
+        }
+    }
+}
+
+impl From<Error> for arrow::error::ArrowError {
+    fn from(error: Error) -> Self {
+        arrow::error::ArrowError::ExternalError(Box::new(error))
+    }
+}
+
 /// Result type
 pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,344 @@
+//! Export arrow schema in other schema language, to create tables before loading parquet
+
+/* std use */
+
+/* crate use */
+
+/* project use */
+use crate::error;
+
+/// Schema language available for export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Arrow JSON schema, as used by arrow integration tests
+    ArrowJson,
+    /// Parquet message type
+    Parquet,
+    /// DuckDB `CREATE TABLE`
+    Duckdb,
+    /// Hive `CREATE EXTERNAL TABLE` stored as parquet
+    Hive,
+    /// BigQuery `CREATE TABLE`
+    Bigquery,
+}
+
+impl std::str::FromStr for Format {
+    type Err = error::Error;
+
+    fn from_str(s: &str) -> error::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "arrow-json" => Ok(Format::ArrowJson),
+            "parquet" => Ok(Format::Parquet),
+            "duckdb" => Ok(Format::Duckdb),
+            "hive" => Ok(Format::Hive),
+            "bigquery" => Ok(Format::Bigquery),
+            _ => Err(error::Error::ExportFormatParse(s.to_string())),
+        }
+    }
+}
+
+/// Write `schema` in `format`, `table` is name of table in DDL
+pub fn schema(
+    schema: &arrow::datatypes::Schema,
+    format: Format,
+    table: &str,
+) -> error::Result<String> {
+    match format {
+        Format::ArrowJson => arrow_json(schema),
+        Format::Parquet => parquet_message(schema),
+        Format::Duckdb | Format::Hive | Format::Bigquery => ddl(schema, format, table),
+    }
+}
+
+fn arrow_json(schema: &arrow::datatypes::Schema) -> error::Result<String> {
+    let mut dictionary_id = 0;
+    let fields = schema
+        .fields()
+        .iter()
+        .map(|f| json_field(f, &mut dictionary_id))
+        .collect::<error::Result<Vec<_>>>()?;
+
+    Ok(serde_json::to_string_pretty(&serde_json::json!({ "fields": fields })).unwrap_or_default())
+}
+
+fn json_field(
+    field: &arrow::datatypes::Field,
+    dictionary_id: &mut i64,
+) -> error::Result<serde_json::Value> {
+    let mut value = serde_json::json!({
+        "name": field.name(),
+        "nullable": field.is_nullable(),
+    });
+
+    let data_type = match field.data_type() {
+        arrow::datatypes::DataType::Dictionary(key, value_type) => {
+            value["dictionary"] = serde_json::json!({
+                "id": *dictionary_id,
+                "indexType": json_type(field.name(), key)?,
+                "isOrdered": false,
+            });
+            *dictionary_id += 1;
+
+            value_type.as_ref()
+        }
+        other => other,
+    };
+
+    value["type"] = json_type(field.name(), data_type)?;
+    value["children"] = match data_type {
+        arrow::datatypes::DataType::List(inner) | arrow::datatypes::DataType::Map(inner, _) => {
+            serde_json::Value::Array(vec![json_field(inner, dictionary_id)?])
+        }
+        arrow::datatypes::DataType::Struct(fields) => serde_json::Value::Array(
+            fields
+                .iter()
+                .map(|f| json_field(f, dictionary_id))
+                .collect::<error::Result<Vec<_>>>()?,
+        ),
+        _ => serde_json::Value::Array(vec![]),
+    };
+
+    Ok(value)
+}
+
+fn json_type(
+    name: &str,
+    data_type: &arrow::datatypes::DataType,
+) -> error::Result<serde_json::Value> {
+    let int = |width: u8| serde_json::json!({"name": "int", "bitWidth": width, "isSigned": true});
+    let float =
+        |precision: &str| serde_json::json!({"name": "floatingpoint", "precision": precision});
+
+    Ok(match data_type {
+        arrow::datatypes::DataType::Boolean => serde_json::json!({"name": "bool"}),
+        arrow::datatypes::DataType::Int8 => int(8),
+        arrow::datatypes::DataType::Int16 => int(16),
+        arrow::datatypes::DataType::Int32 => int(32),
+        arrow::datatypes::DataType::Int64 => int(64),
+        arrow::datatypes::DataType::Float16 => float("HALF"),
+        arrow::datatypes::DataType::Float32 => float("SINGLE"),
+        arrow::datatypes::DataType::Float64 => float("DOUBLE"),
+        arrow::datatypes::DataType::Utf8 => serde_json::json!({"name": "utf8"}),
+        arrow::datatypes::DataType::List(_) => serde_json::json!({"name": "list"}),
+        arrow::datatypes::DataType::Struct(_) => serde_json::json!({"name": "struct"}),
+        arrow::datatypes::DataType::Map(_, sorted) => {
+            serde_json::json!({"name": "map", "keysSorted": sorted})
+        }
+        other => {
+            return Err(error::Error::ExportType(
+                name.to_string(),
+                other.to_string(),
+            ))
+        }
+    })
+}
+
+fn parquet_message(schema: &arrow::datatypes::Schema) -> error::Result<String> {
+    let descriptor = parquet::arrow::arrow_to_parquet_schema(schema)?;
+
+    let mut output = Vec::new();
+    parquet::schema::printer::print_schema(&mut output, descriptor.root_schema());
+
+    Ok(String::from_utf8_lossy(&output).to_string())
+}
+
+fn ddl(schema: &arrow::datatypes::Schema, format: Format, table: &str) -> error::Result<String> {
+    let quote = |name: &str| match format {
+        Format::Duckdb => format!("\"{}\"", name.replace('"', "\"\"")),
+        _ => format!("`{}`", name.replace('`', "``")),
+    };
+
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|f| {
+            let mut column = format!(
+                "    {} {}",
+                quote(f.name()),
+                sql_type(f.name(), f.data_type(), format)?
+            );
+
+            let repeated = matches!(f.data_type(), arrow::datatypes::DataType::List(_));
+            if !f.is_nullable()
+                && format != Format::Hive
+                && !(repeated && format == Format::Bigquery)
+            {
+                column.push_str(" NOT NULL");
+            }
+
+            Ok(column)
+        })
+        .collect::<error::Result<Vec<_>>>()?;
+
+    let (create, end) = match format {
+        Format::Hive => ("CREATE EXTERNAL TABLE", "\nSTORED AS PARQUET;"),
+        _ => ("CREATE TABLE", ";"),
+    };
+
+    Ok(format!(
+        "{} {} (\n{}\n){}",
+        create,
+        quote(table),
+        columns.join(",\n"),
+        end
+    ))
+}
+
+fn sql_type(
+    name: &str,
+    data_type: &arrow::datatypes::DataType,
+    format: Format,
+) -> error::Result<String> {
+    let error = || error::Error::ExportType(name.to_string(), data_type.to_string());
+
+    Ok(match (format, data_type) {
+        (_, arrow::datatypes::DataType::Dictionary(_, value)) => sql_type(name, value, format)?,
+        (_, arrow::datatypes::DataType::List(inner)) => {
+            let inner = sql_type(name, inner.data_type(), format)?;
+            match format {
+                Format::Duckdb => format!("{}[]", inner),
+                _ => format!("ARRAY<{}>", inner),
+            }
+        }
+
+        (Format::Duckdb, arrow::datatypes::DataType::Boolean) => "BOOLEAN".to_string(),
+        (Format::Duckdb, arrow::datatypes::DataType::Int8) => "TINYINT".to_string(),
+        (Format::Duckdb, arrow::datatypes::DataType::Int16) => "SMALLINT".to_string(),
+        (Format::Duckdb, arrow::datatypes::DataType::Int32) => "INTEGER".to_string(),
+        (Format::Duckdb, arrow::datatypes::DataType::Int64) => "BIGINT".to_string(),
+        (Format::Duckdb, arrow::datatypes::DataType::Float16)
+        | (Format::Duckdb, arrow::datatypes::DataType::Float32) => "FLOAT".to_string(),
+        (Format::Duckdb, arrow::datatypes::DataType::Float64) => "DOUBLE".to_string(),
+        (Format::Duckdb, arrow::datatypes::DataType::Utf8) => "VARCHAR".to_string(),
+        (Format::Duckdb, arrow::datatypes::DataType::Map(_, _)) => {
+            "MAP(VARCHAR, VARCHAR)".to_string()
+        }
+
+        (Format::Hive, arrow::datatypes::DataType::Boolean) => "BOOLEAN".to_string(),
+        (Format::Hive, arrow::datatypes::DataType::Int8) => "TINYINT".to_string(),
+        (Format::Hive, arrow::datatypes::DataType::Int16) => "SMALLINT".to_string(),
+        (Format::Hive, arrow::datatypes::DataType::Int32) => "INT".to_string(),
+        (Format::Hive, arrow::datatypes::DataType::Int64) => "BIGINT".to_string(),
+        (Format::Hive, arrow::datatypes::DataType::Float16)
+        | (Format::Hive, arrow::datatypes::DataType::Float32) => "FLOAT".to_string(),
+        (Format::Hive, arrow::datatypes::DataType::Float64) => "DOUBLE".to_string(),
+        (Format::Hive, arrow::datatypes::DataType::Utf8) => "STRING".to_string(),
+        (Format::Hive, arrow::datatypes::DataType::Map(_, _)) => "MAP<STRING, STRING>".to_string(),
+
+        (Format::Bigquery, arrow::datatypes::DataType::Boolean) => "BOOL".to_string(),
+        (
+            Format::Bigquery,
+            arrow::datatypes::DataType::Int8
+            | arrow::datatypes::DataType::Int16
+            | arrow::datatypes::DataType::Int32
+            | arrow::datatypes::DataType::Int64,
+        ) => "INT64".to_string(),
+        (
+            Format::Bigquery,
+            arrow::datatypes::DataType::Float16
+            | arrow::datatypes::DataType::Float32
+            | arrow::datatypes::DataType::Float64,
+        ) => "FLOAT64".to_string(),
+        (Format::Bigquery, arrow::datatypes::DataType::Utf8) => "STRING".to_string(),
+        (Format::Bigquery, arrow::datatypes::DataType::Map(_, _)) => {
+            "ARRAY<STRUCT<key STRING, value STRING>>".to_string()
+        }
+
+        _ => return Err(error()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+
+    fn test_schema() -> arrow::datatypes::Schema {
+        arrow::datatypes::Schema::new(vec![
+            arrow::datatypes::Field::new("chromosome", arrow::datatypes::DataType::Utf8, false),
+            arrow::datatypes::Field::new(
+                "filter",
+                arrow::datatypes::DataType::List(Arc::new(arrow::datatypes::Field::new(
+                    "filter",
+                    arrow::datatypes::DataType::Utf8,
+                    false,
+                ))),
+                false,
+            ),
+            arrow::datatypes::Field::new("info_DP", arrow::datatypes::DataType::Int16, true),
+            arrow::datatypes::Field::new(
+                "info_CSQ",
+                arrow::datatypes::DataType::Dictionary(
+                    Box::new(arrow::datatypes::DataType::Int32),
+                    Box::new(arrow::datatypes::DataType::Utf8),
+                ),
+                true,
+            ),
+        ])
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!("Arrow-Json".parse::<Format>().unwrap(), Format::ArrowJson);
+        assert_eq!("duckdb".parse::<Format>().unwrap(), Format::Duckdb);
+        assert!("sqlite".parse::<Format>().is_err());
+    }
+
+    #[test]
+    fn json() {
+        let value: serde_json::Value =
+            serde_json::from_str(&schema(&test_schema(), Format::ArrowJson, "vcf").unwrap())
+                .unwrap();
+
+        assert_eq!(value["fields"][0]["type"]["name"], "utf8");
+        assert_eq!(value["fields"][1]["type"]["name"], "list");
+        assert_eq!(value["fields"][1]["children"][0]["name"], "filter");
+        assert_eq!(value["fields"][2]["type"]["bitWidth"], 16);
+        assert_eq!(value["fields"][3]["type"]["name"], "utf8");
+        assert_eq!(value["fields"][3]["dictionary"]["id"], 0);
+    }
+
+    #[test]
+    fn message() {
+        let message = schema(&test_schema(), Format::Parquet, "vcf").unwrap();
+
+        assert!(message.starts_with("message arrow_schema {\n"));
+        assert!(message.contains("REQUIRED BYTE_ARRAY chromosome (STRING);"));
+        assert!(message.contains("OPTIONAL INT32 info_DP (INTEGER(16,true));"));
+    }
+
+    #[test]
+    fn sql() {
+        assert_eq!(
+            schema(&test_schema(), Format::Duckdb, "vcf").unwrap(),
+            "CREATE TABLE \"vcf\" (
+    \"chromosome\" VARCHAR NOT NULL,
+    \"filter\" VARCHAR[] NOT NULL,
+    \"info_DP\" SMALLINT,
+    \"info_CSQ\" VARCHAR
+);"
+        );
+
+        assert_eq!(
+            schema(&test_schema(), Format::Hive, "vcf").unwrap(),
+            "CREATE EXTERNAL TABLE `vcf` (
+    `chromosome` STRING,
+    `filter` ARRAY<STRING>,
+    `info_DP` SMALLINT,
+    `info_CSQ` STRING
+)
+STORED AS PARQUET;"
+        );
+
+        assert_eq!(
+            schema(&test_schema(), Format::Bigquery, "vcf").unwrap(),
+            "CREATE TABLE `vcf` (
+    `chromosome` STRING NOT NULL,
+    `filter` ARRAY<STRING>,
+    `info_DP` INT64,
+    `info_CSQ` STRING
+);"
+        );
+    }
+}
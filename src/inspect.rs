@@ -0,0 +1,405 @@
+//! Describe schema produce from a vcf and content of a parquet
+
+/* std use */
+
+/* crate use */
+use arrow::record_batch::RecordBatchReader as _;
+use parquet::file::reader::FileReader as _;
+
+/* project use */
+use crate::error;
+use crate::options;
+use crate::schema;
+
+/// Description of a column produce from a vcf
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    name: String,
+    data_type: arrow::datatypes::DataType,
+    nullable: bool,
+    source: String,
+}
+
+impl Column {
+    /// Get name of column
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get arrow type of column
+    pub fn data_type(&self) -> &arrow::datatypes::DataType {
+        &self.data_type
+    }
+
+    /// Return true if column is nullable
+    pub fn nullable(&self) -> bool {
+        self.nullable
+    }
+
+    /// Get vcf field or header definition at origin of column
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+/// Read header of `input` vcf and describe columns of arrow schema, `options` are options used for conversion
+pub fn vcf<R>(input: R, options: &options::Options) -> error::Result<Vec<Column>>
+where
+    R: std::io::BufRead,
+{
+    let chunk_iterator = crate::vcf2batches(input, 1, options)?;
+    let header = chunk_iterator.header();
+
+    let mut sources = std::collections::HashMap::new();
+    for (column, field) in schema::required_column()
+        .iter()
+        .zip(["CHROM", "POS", "ID", "REF", "ALT", "QUAL", "FILTER"])
+    {
+        sources.insert(column.name().to_string(), field.to_string());
+    }
+    for column in schema::computed_column(options) {
+        sources.insert(
+            column.name().to_string(),
+            schema::computed_source(column.name()).to_string(),
+        );
+    }
+
+    sources.insert(
+        schema::info_extra_column(options),
+        "INFO fields not declared in header".to_string(),
+    );
+    for (name, value) in header.infos() {
+        sources.insert(
+            schema::info_column(name.as_ref(), options),
+            format!(
+                "INFO=<ID={},Number={},Type={},Description=\"{}\">",
+                name,
+                value.number(),
+                value.ty(),
+                value.description()
+            ),
+        );
+    }
+
+    for sample in header.sample_names() {
+        sources.insert(
+            schema::format_extra_column(sample, options),
+            format!("FORMAT fields of {} not declared in header", sample),
+        );
+        for (name, value) in header.formats() {
+            sources.insert(
+                schema::format_column(sample, name.as_ref(), options),
+                format!(
+                    "FORMAT=<ID={},Number={},Type={},Description=\"{}\"> of {}",
+                    name,
+                    value.number(),
+                    value.ty(),
+                    value.description(),
+                    sample
+                ),
+            );
+        }
+    }
+
+    Ok(chunk_iterator
+        .schema()
+        .fields()
+        .iter()
+        .map(|f| Column {
+            name: f.name().to_string(),
+            data_type: f.data_type().clone(),
+            nullable: f.is_nullable(),
+            source: sources.remove(f.name()).unwrap_or_default(),
+        })
+        .collect())
+}
+
+/// Write description of vcf columns in a tabular format
+pub fn write_columns<W>(output: &mut W, columns: &[Column]) -> error::Result<()>
+where
+    W: std::io::Write,
+{
+    writeln!(output, "name\ttype\tnullable\tsource")?;
+    for column in columns {
+        writeln!(
+            output,
+            "{}\t{}\t{}\t{}",
+            column.name,
+            type_name(&column.data_type),
+            column.nullable,
+            column.source
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Size of a parquet column, sum over all row groups
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnSize {
+    name: String,
+    compression: String,
+    compressed: i64,
+    uncompressed: i64,
+}
+
+impl ColumnSize {
+    /// Get path of column
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get compression of column
+    pub fn compression(&self) -> &str {
+        &self.compression
+    }
+
+    /// Get compressed size in bytes
+    pub fn compressed(&self) -> i64 {
+        self.compressed
+    }
+
+    /// Get uncompressed size in bytes
+    pub fn uncompressed(&self) -> i64 {
+        self.uncompressed
+    }
+
+    /// Get compression ratio, uncompressed size divide by compressed size
+    pub fn ratio(&self) -> f64 {
+        ratio(self.uncompressed, self.compressed)
+    }
+}
+
+/// Description of a parquet file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parquet {
+    rows: i64,
+    row_groups: Vec<(i64, i64, i64)>,
+    columns: Vec<ColumnSize>,
+    metadata: Vec<(String, String)>,
+}
+
+impl Parquet {
+    /// Get number of rows
+    pub fn rows(&self) -> i64 {
+        self.rows
+    }
+
+    /// Get number of rows, compressed and uncompressed size of each row group
+    pub fn row_groups(&self) -> &[(i64, i64, i64)] {
+        &self.row_groups
+    }
+
+    /// Get size of each column
+    pub fn columns(&self) -> &[ColumnSize] {
+        &self.columns
+    }
+
+    /// Get key value metadata embedded in file, arrow schema isn't include
+    pub fn metadata(&self) -> &[(String, String)] {
+        &self.metadata
+    }
+}
+
+/// Read footer of `input` parquet and describe row groups, columns and metadata
+pub fn parquet<P>(input: P) -> error::Result<Parquet>
+where
+    P: parquet::file::reader::ChunkReader + 'static,
+{
+    let reader = parquet::file::serialized_reader::SerializedFileReader::new(input)?;
+    let metadata = reader.metadata();
+
+    let mut columns: Vec<ColumnSize> = Vec::new();
+    let mut row_groups = Vec::new();
+    for row_group in metadata.row_groups() {
+        row_groups.push((
+            row_group.num_rows(),
+            row_group.compressed_size(),
+            row_group.total_byte_size(),
+        ));
+
+        for (index, column) in row_group.columns().iter().enumerate() {
+            match columns.get_mut(index) {
+                Some(size) => {
+                    size.compressed += column.compressed_size();
+                    size.uncompressed += column.uncompressed_size();
+                }
+                None => columns.push(ColumnSize {
+                    name: column.column_path().string(),
+                    compression: column.compression().to_string(),
+                    compressed: column.compressed_size(),
+                    uncompressed: column.uncompressed_size(),
+                }),
+            }
+        }
+    }
+
+    let metadata = metadata
+        .file_metadata()
+        .key_value_metadata()
+        .map(|kvs| {
+            kvs.iter()
+                .filter(|kv| kv.key != parquet::arrow::ARROW_SCHEMA_META_KEY)
+                .map(|kv| (kv.key.clone(), kv.value.clone().unwrap_or_default()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(Parquet {
+        rows: reader.metadata().file_metadata().num_rows(),
+        row_groups,
+        columns,
+        metadata,
+    })
+}
+
+/// Write description of parquet in a tabular format
+pub fn write_parquet<W>(output: &mut W, parquet: &Parquet) -> error::Result<()>
+where
+    W: std::io::Write,
+{
+    writeln!(
+        output,
+        "{} rows in {} row groups",
+        parquet.rows,
+        parquet.row_groups.len()
+    )?;
+
+    writeln!(output, "\nrow_group\trows\tcompressed\tuncompressed\tratio")?;
+    for (index, (rows, compressed, uncompressed)) in parquet.row_groups.iter().enumerate() {
+        writeln!(
+            output,
+            "{}\t{}\t{}\t{}\t{:.2}",
+            index,
+            rows,
+            compressed,
+            uncompressed,
+            ratio(*uncompressed, *compressed)
+        )?;
+    }
+
+    writeln!(
+        output,
+        "\ncolumn\tcompression\tcompressed\tuncompressed\tratio"
+    )?;
+    for column in parquet.columns.iter() {
+        writeln!(
+            output,
+            "{}\t{}\t{}\t{}\t{:.2}",
+            column.name,
+            column.compression,
+            column.compressed,
+            column.uncompressed,
+            column.ratio()
+        )?;
+    }
+
+    writeln!(output, "\nkey\tvalue")?;
+    for (key, value) in parquet.metadata.iter() {
+        writeln!(output, "{}\t{}", key, value)?;
+    }
+
+    Ok(())
+}
+
+/// Short representation of arrow type, inner field of list is reduce to its type
+fn type_name(data_type: &arrow::datatypes::DataType) -> String {
+    match data_type {
+        arrow::datatypes::DataType::List(inner) => {
+            format!("List<{}>", type_name(inner.data_type()))
+        }
+        arrow::datatypes::DataType::Dictionary(key, value) => {
+            format!("Dictionary<{}, {}>", type_name(key), type_name(value))
+        }
+        arrow::datatypes::DataType::Map(_, _) => "Map<Utf8, Utf8>".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn ratio(uncompressed: i64, compressed: i64) -> f64 {
+    if compressed == 0 {
+        0.0
+    } else {
+        uncompressed as f64 / compressed as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static VCF_FILE: &[u8] = b"##fileformat=VCFv4.3
+##INFO=<ID=XD,Number=1,Type=Integer,Description=\"depth\">
+##FORMAT=<ID=GT,Number=1,Type=String,Description=\"Genotype\">
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tsample
+1\t925952\t.\tG\tA\t.\t.\tXD=1\tGT\t0/1
+1\t925953\t.\tG\tA,C\t.\t.\tXD=2\tGT\t1/2
+2\t925954\t.\tG\tA\t.\t.\tXD=3\tGT\t1/1
+";
+
+    #[test]
+    fn columns() {
+        let columns = vcf(VCF_FILE, &options::Options::new()).unwrap();
+
+        assert_eq!(columns.len(), 9);
+        assert_eq!(columns[0].name(), "chromosome");
+        assert_eq!(columns[0].source(), "CHROM");
+        assert!(!columns[0].nullable());
+        assert_eq!(columns[7].name(), "info_XD");
+        assert_eq!(columns[7].data_type(), &arrow::datatypes::DataType::Int32);
+        assert_eq!(
+            columns[7].source(),
+            "INFO=<ID=XD,Number=1,Type=Integer,Description=\"depth\">"
+        );
+        assert_eq!(columns[8].name(), "format_sample_GT");
+        assert!(columns[8].nullable());
+        assert_eq!(
+            columns[8].source(),
+            "FORMAT=<ID=GT,Number=1,Type=String,Description=\"Genotype\"> of sample"
+        );
+
+        let mut output = Vec::new();
+        write_columns(&mut output, &columns[1..3]).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "name\ttype\tnullable\tsource\nposition\tInt32\tfalse\tPOS\nidentifier\tList<Utf8>\tfalse\tID\n"
+        );
+    }
+
+    #[test]
+    fn parquet_file() {
+        let mut output = tempfile::tempfile().unwrap();
+        crate::vcf2parquet_with_options(
+            &mut &VCF_FILE[..],
+            &mut output,
+            2,
+            parquet::basic::Compression::SNAPPY,
+            &options::Options::new(),
+            parquet::file::properties::WriterVersion::PARQUET_2_0,
+        )
+        .unwrap();
+
+        let description = parquet(output).unwrap();
+
+        assert_eq!(description.rows(), 4);
+        assert_eq!(
+            description
+                .row_groups()
+                .iter()
+                .map(|r| r.0)
+                .collect::<Vec<_>>(),
+            vec![4]
+        );
+        assert_eq!(description.columns().len(), 9);
+        assert_eq!(description.columns()[0].name(), "chromosome");
+        assert_eq!(description.columns()[0].compression(), "SNAPPY");
+        assert!(description.columns()[0].ratio() > 0.0);
+        assert!(description.metadata().is_empty());
+
+        let mut output = Vec::new();
+        write_parquet(&mut output, &description).unwrap();
+        assert!(String::from_utf8(output)
+            .unwrap()
+            .starts_with("4 rows in 1 row groups\n"));
+    }
+}
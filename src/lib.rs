@@ -4,16 +4,61 @@
 /* std use */
 
 /* crate use */
+use arrow::record_batch::RecordBatchReader as _;
 use parquet::file::properties::WriterVersion;
 /* project use */
 
 /* mod section */
 pub mod cli;
+pub mod cohort;
 pub mod columndata;
 pub mod error;
+pub mod export;
+pub mod inspect;
 pub mod name2data;
+pub mod options;
+pub mod overrides;
 pub mod record2chunk;
+pub mod records;
+pub mod reference;
+pub mod sampleqc;
 pub mod schema;
+pub mod stats;
+#[cfg(feature = "object_store")]
+pub mod storage;
+pub mod validate;
+pub mod variant;
+
+/// Read `input` vcf and return an iterator on arrow record batch, each batch contains `batch_size` records
+pub fn vcf2batches<R>(
+    input: R,
+    batch_size: usize,
+    options: &options::Options,
+) -> error::Result<record2chunk::Record2Chunk<records::Records<R>>>
+where
+    R: std::io::BufRead,
+{
+    // VCF section
+    let mut reader = noodles::vcf::Reader::new(input);
+
+    let mut vcf_header: noodles::vcf::Header = reader.read_header()?;
+    options.overrides().apply(&mut vcf_header)?;
+    let mut records = records::Records::new(reader, vcf_header.clone());
+
+    // Arrow section
+    let schema = schema::from_header_with_options(&vcf_header, options)?;
+    let batch_size = budget_batch_size(&schema, batch_size, options);
+    records.set_selection(records::Selection::new(&vcf_header, &schema, options));
+    records.set_float64(records::float64_infos(&vcf_header, &schema, options));
+
+    Ok(record2chunk::Record2Chunk::new(
+        records,
+        batch_size,
+        vcf_header,
+        std::sync::Arc::new(schema),
+        options.clone(),
+    ))
+}
 
 /// Read `input` vcf and write parquet in `output`
 pub fn vcf2parquet<R, W>(
@@ -28,42 +73,57 @@ where
     R: std::io::BufRead,
     W: std::io::Write + std::marker::Send,
 {
-    // VCF section
-    let mut reader = noodles::vcf::Reader::new(input);
-
-    let vcf_header: noodles::vcf::Header = reader.read_header()?;
-
-    // Parquet section
-    let schema = schema::from_header(&vcf_header, info_optional)?;
-    let schema_ptr = std::sync::Arc::new(schema);
-
-    let mut iterator = reader.records(&vcf_header);
-    let chunk_iterator = record2chunk::Record2Chunk::new(
-        &mut iterator,
+    vcf2parquet_with_options(
+        input,
+        output,
         batch_size,
-        vcf_header.clone(),
-        schema_ptr.clone(),
-    );
+        compression,
+        &options::Options::new().set_info_optional(info_optional),
+        parquet_version,
+    )?;
 
-    let options = parquet::file::properties::WriterProperties::builder()
-        .set_compression(compression)
-        .set_statistics_enabled(parquet::file::properties::EnabledStatistics::Page)
-        .set_writer_version(parquet_version)
-        .set_write_batch_size(batch_size)
-        .build();
+    Ok(())
+}
 
-    let row_groups = arrow::array::RecordBatchIterator::new(chunk_iterator, schema_ptr.clone());
+/// Read `input` vcf and write parquet in `output` according to `options`, return statistics of conversion
+pub fn vcf2parquet_with_options<R, W>(
+    input: &mut R,
+    output: &mut W,
+    batch_size: usize,
+    compression: parquet::basic::Compression,
+    options: &options::Options,
+    parquet_version: WriterVersion,
+) -> error::Result<stats::Stats>
+where
+    R: std::io::BufRead,
+    W: std::io::Write + std::marker::Send,
+{
+    let mut chunk_iterator = vcf2batches(input, batch_size, options)?;
+    let schema_ptr = chunk_iterator.schema();
+    let memory_budget = options.memory_budget();
+
+    // Parquet section
+    let options = writer_properties(batch_size, compression, parquet_version);
 
     let mut writer =
         parquet::arrow::ArrowWriter::try_new(output, schema_ptr.clone(), Some(options))?;
 
-    for result in row_groups {
+    for result in chunk_iterator.by_ref() {
         let group = result?;
         writer.write(&group)?;
+
+        if over_budget(memory_budget, writer.in_progress_size()) {
+            writer.flush()?;
+        }
     }
     let _ = writer.close()?;
 
-    Ok(())
+    Ok(chunk_iterator.stats().clone())
+}
+
+/// True if writer buffer exceed memory budget, current row group must be flush
+pub(crate) fn over_budget(memory_budget: Option<usize>, in_progress_size: usize) -> bool {
+    memory_budget.is_some_and(|budget| in_progress_size >= budget)
 }
 
 /// Read `input` vcf and write each row group in a parquet file match with template
@@ -78,33 +138,37 @@ pub fn vcf2multiparquet<R>(
 where
     R: std::io::BufRead,
 {
-    // VCF section
-    let mut reader = noodles::vcf::Reader::new(input);
-
-    let vcf_header: noodles::vcf::Header = reader.read_header()?;
-
-    // Parquet section
-    let schema = schema::from_header(&vcf_header, info_optional)?;
-    let schema_ptr = std::sync::Arc::new(schema);
-
-    let mut iterator = reader.records(&vcf_header);
-    let chunk_iterator = record2chunk::Record2Chunk::new(
-        &mut iterator,
+    vcf2multiparquet_with_options(
+        input,
+        template,
         batch_size,
-        vcf_header.clone(),
-        schema_ptr.clone(),
-    );
+        compression,
+        &options::Options::new().set_info_optional(info_optional),
+        parquet_version,
+    )?;
 
-    let options = parquet::file::properties::WriterProperties::builder()
-        .set_compression(compression)
-        .set_statistics_enabled(parquet::file::properties::EnabledStatistics::Page)
-        .set_writer_version(parquet_version)
-        .set_write_batch_size(batch_size)
-        .build();
+    Ok(())
+}
+
+/// Read `input` vcf and write each row group in a parquet file match with template according to `options`, return statistics of conversion
+pub fn vcf2multiparquet_with_options<R>(
+    input: &mut R,
+    template: &str,
+    batch_size: usize,
+    compression: parquet::basic::Compression,
+    options: &options::Options,
+    parquet_version: WriterVersion,
+) -> error::Result<stats::Stats>
+where
+    R: std::io::BufRead,
+{
+    let mut chunk_iterator = vcf2batches(input, batch_size, options)?;
+    let schema_ptr = chunk_iterator.schema();
 
-    let row_groups = arrow::array::RecordBatchIterator::new(chunk_iterator, schema_ptr.clone());
+    // Parquet section
+    let options = writer_properties(batch_size, compression, parquet_version);
 
-    for (index, result) in row_groups.enumerate() {
+    for (index, result) in chunk_iterator.by_ref().enumerate() {
         let group = result?;
         let output = std::fs::File::create(template.replace("{}", &index.to_string()))?;
         let mut writer = parquet::arrow::ArrowWriter::try_new(
@@ -117,7 +181,71 @@ where
         writer.close()?;
     }
 
-    Ok(())
+    Ok(chunk_iterator.stats().clone())
+}
+
+pub(crate) fn writer_properties(
+    batch_size: usize,
+    compression: parquet::basic::Compression,
+    parquet_version: WriterVersion,
+) -> parquet::file::properties::WriterProperties {
+    parquet::file::properties::WriterProperties::builder()
+        .set_compression(compression)
+        .set_statistics_enabled(parquet::file::properties::EnabledStatistics::Page)
+        .set_writer_version(parquet_version)
+        .set_write_batch_size(batch_size)
+        .build()
+}
+
+/// Reduce `batch_size` so column builders of a batch fit in memory budget, according to estimate size of a row
+fn budget_batch_size(
+    schema: &arrow::datatypes::Schema,
+    batch_size: usize,
+    options: &options::Options,
+) -> usize {
+    let Some(budget) = options.memory_budget() else {
+        return batch_size;
+    };
+
+    let row = schema::row_bytes(schema);
+    let size = (budget / row).clamp(1, batch_size.max(1));
+    if size < batch_size {
+        log::info!(
+            "Batch size reduce to {} records to fit memory budget of {} bytes, a row use about {} bytes",
+            size,
+            budget,
+            row
+        );
+    }
+
+    size
+}
+
+/// Read all records of `input` vcf to choose narrowest integer type of integer fields, return `options` with these type hints
+///
+/// Type hints are used by conversion so `input` must be read a second time to convert it.
+pub fn compact_integer<R>(input: R, options: &options::Options) -> error::Result<options::Options>
+where
+    R: std::io::BufRead,
+{
+    let mut reader = noodles::vcf::Reader::new(input);
+
+    let mut vcf_header: noodles::vcf::Header = reader.read_header()?;
+    options.overrides().apply(&mut vcf_header)?;
+
+    let mut range = schema::IntegerRange::new();
+    for (index, result) in records::Records::new(reader, vcf_header.clone()).enumerate() {
+        match result {
+            Ok(record) => range.add_record(&vcf_header, &record),
+            Err(e) if !options.lenient() => return Err(error::Error::InvalidRecord(index + 1, e)),
+            Err(_) => (),
+        }
+    }
+
+    let mut hints = range.hints(&vcf_header, options);
+    hints.extend_from_slice(options.type_hints());
+
+    Ok(options.clone().set_type_hints(hints))
 }
 
 #[cfg(test)]
@@ -256,6 +384,353 @@ mod tests {
         assert_eq!(output, *PARQUET_FILE);
     }
 
+    #[test]
+    fn convert_dictionary() {
+        let mut input = std::io::BufReader::new(VCF_FILE);
+        let mut output = tempfile::tempfile().unwrap();
+
+        vcf2parquet_with_options(
+            &mut input,
+            &mut output,
+            1,
+            parquet::basic::Compression::UNCOMPRESSED,
+            &options::Options::new()
+                .set_dictionary(vec!["chromosome".to_string(), "filter".to_string()]),
+            WriterVersion::PARQUET_2_0,
+        )
+        .unwrap();
+
+        let reader =
+            parquet::arrow::arrow_reader::ParquetRecordBatchReader::try_new(output, 1).unwrap();
+        let batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+
+        assert_eq!(batches.len(), 1);
+        let chromosome = batches[0]
+            .column_by_name("chromosome")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow::array::DictionaryArray<arrow::datatypes::Int32Type>>()
+            .unwrap();
+        let values = chromosome
+            .values()
+            .as_any()
+            .downcast_ref::<arrow::array::StringArray>()
+            .unwrap();
+        assert_eq!(values.value(chromosome.keys().value(0) as usize), "1");
+        assert_eq!(
+            batches[0].column_by_name("filter").unwrap().data_type(),
+            &arrow::datatypes::DataType::List(std::sync::Arc::new(arrow::datatypes::Field::new(
+                "filter",
+                arrow::datatypes::DataType::Dictionary(
+                    Box::new(arrow::datatypes::DataType::Int32),
+                    Box::new(arrow::datatypes::DataType::Utf8)
+                ),
+                false
+            )))
+        );
+    }
+
+    #[test]
+    fn convert_compact() {
+        let mut vcf = b"##fileformat=VCFv4.3
+##INFO=<ID=DP,Number=1,Type=Integer,Description=\"depth\">
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+"
+        .to_vec();
+        for position in 1..=20 {
+            vcf.extend_from_slice(format!("1\t{position}\t.\tA\tC\t.\t.\tDP=10\n").as_bytes());
+        }
+        // value out of int8 range after many records
+        vcf.extend_from_slice(b"1\t21\t.\tA\tC\t.\t.\tDP=1000\n");
+
+        let options = compact_integer(&vcf[..], &options::Options::new()).unwrap();
+        assert_eq!(options.info_width("DP"), Some(options::Width::Int16));
+
+        let mut output = tempfile::tempfile().unwrap();
+        vcf2parquet_with_options(
+            &mut &vcf[..],
+            &mut output,
+            5,
+            parquet::basic::Compression::UNCOMPRESSED,
+            &options,
+            WriterVersion::PARQUET_2_0,
+        )
+        .unwrap();
+
+        let reader =
+            parquet::arrow::arrow_reader::ParquetRecordBatchReader::try_new(output, 100).unwrap();
+        let batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+
+        let depth = batches[0]
+            .column_by_name("info_DP")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow::array::Int16Array>()
+            .unwrap();
+        assert_eq!(depth.len(), 21);
+        assert_eq!(depth.value(20), 1000);
+        // position isn't a vcf integer field, it isn't compacted
+        assert_eq!(
+            batches[0].column_by_name("position").unwrap().data_type(),
+            &arrow::datatypes::DataType::Int32
+        );
+    }
+
+    #[test]
+    fn convert_memory_budget() {
+        let mut input = std::io::BufReader::new(VCF_FILE);
+        let mut output = tempfile::tempfile().unwrap();
+
+        let stats = vcf2parquet_with_options(
+            &mut input,
+            &mut output,
+            100,
+            parquet::basic::Compression::UNCOMPRESSED,
+            &options::Options::new().set_memory_budget(Some(1)),
+            WriterVersion::PARQUET_2_0,
+        )
+        .unwrap();
+        assert!(stats.peak_memory() > 0);
+
+        // each record exceed budget, so each record is write in its own row group
+        let reader = parquet::file::serialized_reader::SerializedFileReader::new(output).unwrap();
+        assert_eq!(
+            parquet::file::reader::FileReader::metadata(&reader).num_row_groups(),
+            stats.records()
+        );
+
+        let batches = vcf2batches(VCF_FILE, 100, &options::Options::new())
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(batches.len(), 1);
+    }
+
+    #[test]
+    fn convert_override() {
+        let vcf = b"##fileformat=VCFv4.3
+##INFO=<ID=DP,Number=1,Type=Integer,Description=\"depth\">
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+1\t925952\t.\tG\tA\t.\t.\tDP=1,2
+";
+        let mut input = std::io::BufReader::new(&vcf[..]);
+        let mut output = tempfile::tempfile().unwrap();
+
+        let overrides = overrides::Overrides::from_toml(
+            "[info.DP]\nnumber = \".\"\nrename = \"depth\"\nnullable = true",
+        )
+        .unwrap();
+
+        vcf2parquet_with_options(
+            &mut input,
+            &mut output,
+            1,
+            parquet::basic::Compression::UNCOMPRESSED,
+            &options::Options::new().set_overrides(overrides),
+            WriterVersion::PARQUET_2_0,
+        )
+        .unwrap();
+
+        let reader =
+            parquet::arrow::arrow_reader::ParquetRecordBatchReader::try_new(output, 1).unwrap();
+        let batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+
+        let depth = batches[0].column_by_name("info_depth").unwrap();
+        let depth = depth
+            .as_any()
+            .downcast_ref::<arrow::array::ListArray>()
+            .unwrap();
+        assert!(batches[0]
+            .schema()
+            .field_with_name("info_depth")
+            .unwrap()
+            .is_nullable());
+        assert_eq!(
+            depth
+                .value(0)
+                .as_any()
+                .downcast_ref::<arrow::array::Int32Array>()
+                .unwrap()
+                .values(),
+            &[1, 2]
+        );
+    }
+
+    #[test]
+    fn convert_undeclared() {
+        let vcf = b"##fileformat=VCFv4.3
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+1\t925952\t.\tG\tA\t.\t.\tUND=1
+";
+        let mut output = Vec::new();
+        let result = vcf2parquet_with_options(
+            &mut std::io::BufReader::new(&vcf[..]),
+            &mut output,
+            1,
+            parquet::basic::Compression::UNCOMPRESSED,
+            &options::Options::new().set_undeclared(options::Undeclared::Error),
+            WriterVersion::PARQUET_2_0,
+        );
+        assert!(result.is_err());
+
+        let mut output = tempfile::tempfile().unwrap();
+        vcf2parquet_with_options(
+            &mut std::io::BufReader::new(&vcf[..]),
+            &mut output,
+            1,
+            parquet::basic::Compression::UNCOMPRESSED,
+            &options::Options::new().set_undeclared(options::Undeclared::Extra),
+            WriterVersion::PARQUET_2_0,
+        )
+        .unwrap();
+
+        let reader =
+            parquet::arrow::arrow_reader::ParquetRecordBatchReader::try_new(output, 1).unwrap();
+        let batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+
+        let extra = batches[0].column_by_name("info_extra").unwrap();
+        let extra = extra
+            .as_any()
+            .downcast_ref::<arrow::array::MapArray>()
+            .unwrap();
+        assert_eq!(
+            extra
+                .keys()
+                .as_any()
+                .downcast_ref::<arrow::array::StringArray>()
+                .unwrap()
+                .value(0),
+            "UND"
+        );
+    }
+
+    #[test]
+    fn convert_lenient() {
+        let vcf = b"##fileformat=VCFv4.3
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+1\t925952\t.\tG\tA\t.\t.\t.
+1\tnot_a_position\t.\tG\tA\t.\t.\t.
+1\t925954\t.\tG\tA\t.\t.\t.
+";
+        let mut output = Vec::new();
+        let result = vcf2parquet_with_options(
+            &mut std::io::BufReader::new(&vcf[..]),
+            &mut output,
+            10,
+            parquet::basic::Compression::UNCOMPRESSED,
+            &options::Options::new(),
+            WriterVersion::PARQUET_2_0,
+        );
+        assert!(matches!(result, Err(error::Error::InvalidRecord(2, _))));
+
+        let mut output = tempfile::tempfile().unwrap();
+        vcf2parquet_with_options(
+            &mut std::io::BufReader::new(&vcf[..]),
+            &mut output,
+            10,
+            parquet::basic::Compression::UNCOMPRESSED,
+            &options::Options::new().set_lenient(true),
+            WriterVersion::PARQUET_2_0,
+        )
+        .unwrap();
+
+        let reader =
+            parquet::arrow::arrow_reader::ParquetRecordBatchReader::try_new(output, 10).unwrap();
+        let batches = reader.collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(batches[0].num_rows(), 2);
+    }
+
+    #[test]
+    fn convert_float64() {
+        let vcf = b"##fileformat=VCFv4.3
+##INFO=<ID=AF,Number=A,Type=Float,Description=\"frequency\">
+##INFO=<ID=PV,Number=1,Type=Float,Description=\"p-value\">
+##INFO=<ID=SC,Number=.,Type=Float,Description=\"scores\">
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+1\t925952\t.\tG\tA,C\t.\t.\tAF=0.123456789012,.;PV=1.23456789e-12;SC=0.5,0.333333333333
+";
+        // all columns are read fully, a selection is read lazily
+        for columns in [vec![], vec!["info_AF".to_string(), "info_SC".to_string()]] {
+            let options = options::Options::new()
+                .set_info_optional(true)
+                .set_info_float64(true)
+                .set_columns(columns);
+            let batch = vcf2batches(&vcf[..], 10, &options)
+                .unwrap()
+                .next()
+                .unwrap()
+                .unwrap();
+
+            let af = batch
+                .column_by_name("info_AF")
+                .unwrap()
+                .as_any()
+                .downcast_ref::<arrow::array::Float64Array>()
+                .unwrap();
+            assert_eq!(af.value(0), 0.123456789012);
+            assert!(arrow::array::Array::is_null(af, 1));
+
+            let sc = batch
+                .column_by_name("info_SC")
+                .unwrap()
+                .as_any()
+                .downcast_ref::<arrow::array::ListArray>()
+                .unwrap()
+                .value(0);
+            assert_eq!(
+                sc.as_any()
+                    .downcast_ref::<arrow::array::Float64Array>()
+                    .unwrap()
+                    .values(),
+                &[0.5, 0.333333333333]
+            );
+
+            if let Some(pv) = batch.column_by_name("info_PV") {
+                let pv = pv
+                    .as_any()
+                    .downcast_ref::<arrow::array::Float64Array>()
+                    .unwrap();
+                assert_eq!(pv.value(0), 1.23456789e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn batches() {
+        let vcf = b"##fileformat=VCFv4.3
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+1\t925952\t.\tG\tA\t.\t.\t.
+1\t925953\t.\tG\tA\t.\t.\t.
+1\t925954\t.\tG\tA\t.\t.\t.
+";
+        let batches = vcf2batches(&vcf[..], 2, &options::Options::new()).unwrap();
+        assert_eq!(batches.schema().fields().len(), 7);
+
+        let rows = batches
+            .map(|b| b.unwrap().num_rows())
+            .collect::<Vec<usize>>();
+        assert_eq!(rows, vec![2, 1]);
+
+        let options = options::Options::new()
+            .set_columns(vec!["position".to_string()])
+            .set_regions(vec!["1:925953-".parse().unwrap()]);
+        let batch = vcf2batches(&vcf[..], 10, &options)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(batch.num_columns(), 1);
+        assert_eq!(
+            batch
+                .column(0)
+                .as_any()
+                .downcast_ref::<arrow::array::Int32Array>()
+                .unwrap()
+                .values(),
+            &[925953, 925954]
+        );
+    }
+
     #[test]
     fn not_a_vcf() {
         let raw_data = [b'#', b'a', b'b', b'c', 255, 0x7F, b'\n'].to_vec();
@@ -3,723 +3,1151 @@
 /* std use */
 
 /* crate use */
-use arrow::datatypes::Field;
-use noodles::vcf::record::genotypes::sample::value::genotype::allele::Phasing;
 
 /* project use */
+use crate::cohort;
 use crate::columndata::ColumnData;
+use crate::error;
+use crate::options;
+use crate::reference;
+use crate::schema;
+use crate::variant;
 
-///Alias of [std::collections::HashMap] that associate a column name and [ColumnData], a proxy of arrow2 datastructure
+/// Vcf fixed field stored in a core column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Core {
+    Chromosome,
+    Position,
+    Identifier,
+    Reference,
+    Alternate,
+    Quality,
+    Filter,
+    VariantId,
+    VariantHash,
+    VariantType,
+    LengthChange,
+    Transition,
+    SvType,
+    SvLength,
+    SvEnd,
+    MateChromosome,
+    MatePosition,
+    Orientation,
+    CiposStart,
+    CiposEnd,
+    CiendStart,
+    CiendEnd,
+    Context,
+    ReferenceMatch,
+}
+
+/// Aggregate genotype statistic stored in a column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stat {
+    AlleleCount,
+    AlleleNumber,
+    AlleleFrequency,
+    Het,
+    HomAlt,
+    Missing,
+    CallRate,
+    Hwe,
+}
+
+/// Index in schema of columns fed by each vcf field, build once from header and reuse for all records
+#[derive(Debug, Clone, Default)]
+pub struct Plan {
+    names: Vec<String>,
+    index: rustc_hash::FxHashMap<String, usize>,
+    core: Vec<(usize, Core)>,
+    structural: bool,
+    stats: Vec<(usize, Stat)>,
+    stat_samples: Vec<usize>,
+    genotypes: Vec<bool>,
+    infos: Vec<Option<usize>>,
+    info_extra: Option<usize>,
+    formats: Vec<Vec<Option<usize>>>,
+    format_extras: Vec<Option<usize>>,
+}
+
+impl Plan {
+    /// Create a new Plan, `schema` must be generated by [schema::from_header] with same `header` and `options`
+    pub fn new(
+        header: &noodles::vcf::Header,
+        schema: &arrow::datatypes::Schema,
+        options: &options::Options,
+    ) -> Self {
+        let names = schema
+            .fields()
+            .iter()
+            .map(|f| f.name().to_string())
+            .collect::<Vec<String>>();
+
+        let mut index = rustc_hash::FxHashMap::default();
+        for (position, name) in names.iter().enumerate() {
+            index.entry(name.clone()).or_insert(position);
+        }
+
+        let core = [
+            ("chromosome", Core::Chromosome),
+            ("position", Core::Position),
+            ("identifier", Core::Identifier),
+            ("reference", Core::Reference),
+            ("alternate", Core::Alternate),
+            ("quality", Core::Quality),
+            ("filter", Core::Filter),
+            ("variant_id", Core::VariantId),
+            ("variant_hash", Core::VariantHash),
+            ("variant_type", Core::VariantType),
+            ("length_change", Core::LengthChange),
+            ("transition", Core::Transition),
+            ("sv_type", Core::SvType),
+            ("sv_length", Core::SvLength),
+            ("sv_end", Core::SvEnd),
+            ("mate_chromosome", Core::MateChromosome),
+            ("mate_position", Core::MatePosition),
+            ("orientation", Core::Orientation),
+            ("cipos_start", Core::CiposStart),
+            ("cipos_end", Core::CiposEnd),
+            ("ciend_start", Core::CiendStart),
+            ("ciend_end", Core::CiendEnd),
+            ("context", Core::Context),
+            ("reference_match", Core::ReferenceMatch),
+        ]
+        .into_iter()
+        .filter(|(_, field)| match field {
+            Core::VariantId => options.variant_id(),
+            Core::VariantHash => options.variant_hash(),
+            Core::VariantType | Core::LengthChange | Core::Transition => options.classify(),
+            Core::SvType | Core::SvLength => options.classify() || options.sv(),
+            Core::SvEnd
+            | Core::MateChromosome
+            | Core::MatePosition
+            | Core::Orientation
+            | Core::CiposStart
+            | Core::CiposEnd
+            | Core::CiendStart
+            | Core::CiendEnd => options.sv(),
+            Core::Context => options.context() != 0,
+            Core::ReferenceMatch => options.check_reference(),
+            _ => true,
+        })
+        .filter_map(|(name, field)| index.get(name).map(|i| (*i, field)))
+        .collect::<Vec<(usize, Core)>>();
+
+        // structural variant is parse only if one of its columns is write
+        let structural = core.iter().any(|(_, field)| {
+            matches!(
+                field,
+                Core::SvType
+                    | Core::SvLength
+                    | Core::SvEnd
+                    | Core::MateChromosome
+                    | Core::MatePosition
+                    | Core::Orientation
+                    | Core::CiposStart
+                    | Core::CiposEnd
+                    | Core::CiendStart
+                    | Core::CiendEnd
+            )
+        });
+
+        let stats = [
+            ("ac", Stat::AlleleCount),
+            ("an", Stat::AlleleNumber),
+            ("af", Stat::AlleleFrequency),
+            ("n_het", Stat::Het),
+            ("n_hom_alt", Stat::HomAlt),
+            ("n_missing", Stat::Missing),
+            ("call_rate", Stat::CallRate),
+            ("hwe_pvalue", Stat::Hwe),
+        ]
+        .into_iter()
+        .filter(|_| options.genotype_stats())
+        .filter_map(|(name, stat)| index.get(name).map(|i| (*i, stat)))
+        .collect::<Vec<(usize, Stat)>>();
+
+        // statistics are compute on samples keep in output
+        let stat_samples = header
+            .sample_names()
+            .iter()
+            .enumerate()
+            .filter(|(_, sample)| options.keep_sample(sample))
+            .map(|(idx, _)| idx)
+            .collect::<Vec<usize>>();
+
+        let infos = header
+            .infos()
+            .keys()
+            .map(|key| {
+                index
+                    .get(&schema::info_column(key.as_ref(), options))
+                    .copied()
+            })
+            .collect();
+
+        let formats: Vec<Vec<Option<usize>>> = header
+            .formats()
+            .keys()
+            .map(|key| {
+                header
+                    .sample_names()
+                    .iter()
+                    .map(|sample| {
+                        index
+                            .get(&schema::format_column(sample, key.as_ref(), options))
+                            .copied()
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // GT of a sample is parsed once by record if it's write in a column or used by statistics
+        let gt = header.formats().keys().position(|key| key.as_ref() == "GT");
+        let genotypes = (0..header.sample_names().len())
+            .map(|idx| {
+                gt.is_some_and(|gt| formats[gt][idx].is_some())
+                    || (!stats.is_empty() && stat_samples.contains(&idx))
+            })
+            .collect();
+
+        let format_extras = header
+            .sample_names()
+            .iter()
+            .map(|sample| {
+                index
+                    .get(&schema::format_extra_column(sample, options))
+                    .copied()
+            })
+            .collect();
+
+        Self {
+            info_extra: index.get(&schema::info_extra_column(options)).copied(),
+            names,
+            index,
+            core,
+            structural,
+            stats,
+            stat_samples,
+            genotypes,
+            infos,
+            formats,
+            format_extras,
+        }
+    }
+
+    /// Get index of a column
+    pub fn index(&self, name: &str) -> Option<usize> {
+        self.index.get(name).copied()
+    }
+}
+
+/// Associate a column name and [ColumnData], a proxy of arrow datastructure, columns are stored in schema order and found by index with a [Plan]
 #[derive(Debug)]
-pub struct Name2Data(rustc_hash::FxHashMap<String, ColumnData>);
+pub struct Name2Data {
+    columns: Vec<ColumnData>,
+    plan: std::sync::Arc<Plan>,
+    calls: Vec<Call>,
+    buffer: String,
+    reference_mismatch: usize,
+}
 
 impl Name2Data {
-    /// Create a new Name2Data, vcf header is required to add info and genotype column
+    /// Create a new Name2Data, plan is required to add info and genotype column
     /// length parameter is used to preallocate memory
-    pub fn new(length: usize, schema: &arrow::datatypes::Schema) -> Self {
-        let mut name2data = rustc_hash::FxHashMap::default();
+    pub fn new(
+        length: usize,
+        schema: &arrow::datatypes::Schema,
+        plan: std::sync::Arc<Plan>,
+    ) -> error::Result<Self> {
+        let mut columns = Vec::with_capacity(schema.fields.len());
         for field in schema.fields.iter() {
             let nullable = match field.data_type() {
                 arrow::datatypes::DataType::List(a) => a.is_nullable(),
                 _ => field.is_nullable(),
             };
 
-            let column = ColumnData::new(field.data_type(), length, field.name(), nullable);
-            name2data.insert(field.name().to_string(), column);
+            columns.push(ColumnData::new(
+                field.data_type(),
+                length,
+                field.name(),
+                nullable,
+            )?);
         }
-        Name2Data(name2data)
+
+        Ok(Name2Data {
+            columns,
+            calls: vec![Call::default(); plan.genotypes.len()],
+            plan,
+            buffer: String::new(),
+            reference_mismatch: 0,
+        })
+    }
+
+    /// Get index of a column
+    pub fn index(&self, key: &str) -> Option<usize> {
+        self.plan.index(key)
     }
 
-    /// Just a wrapper arround [std::collections::HashMap::get]
+    /// Get column by name
     pub fn get(&self, key: &str) -> Option<&ColumnData> {
-        self.0.get(key)
+        self.index(key).map(|i| &self.columns[i])
     }
 
-    /// Just a wrapper arround [std::collections::HashMap::get_mut]
+    /// Get mutable column by name
     pub fn get_mut(&mut self, key: &str) -> Option<&mut ColumnData> {
-        self.0.get_mut(key)
+        self.index(key).map(|i| &mut self.columns[i])
     }
 
-    /// Add a vcf record in [std::collections::HashMap] struct
+    /// Add a vcf record, one row by alternative allele, return number of values replace by null in lenient mode
     pub fn add_record(
         &mut self,
         record: noodles::vcf::Record,
         header: &noodles::vcf::Header,
-        schema: &rustc_hash::FxHashMap<String, Field>,
-    ) -> std::result::Result<(), arrow::error::ArrowError> {
+        options: &options::Options,
+    ) -> error::Result<usize> {
         let allele_count = record.alternate_bases().len() + 1;
+        let mut nulled = 0;
+        let (reference_bases, reference_match, context) = self.reference(&record, options);
+        let chromosome = record.chromosome().to_string();
+
+        // GT is parsed once for all alternative alleles
+        for (idx, call) in self.calls.iter_mut().enumerate() {
+            if self.plan.genotypes[idx] {
+                call.parse(record.genotypes().get_index(idx).and_then(|values| {
+                    values
+                        .get(&noodles::vcf::record::genotypes::keys::key::GENOTYPE)
+                        .flatten()
+                }));
+            }
+        }
+
         for (alt_id, allele) in record.alternate_bases().iter().enumerate() {
-            for (key, column) in self.0.iter_mut() {
-                match key.as_str() {
-                    "chromosome" => column.push_string(record.chromosome().to_string()),
-                    "position" => column.push_i32(Some(usize::from(record.position()) as i32)),
-                    "identifier" => column.push_vecstring(
-                        record.ids().iter().map(|s| Some(s.to_string())).collect(),
-                    )?,
-                    "reference" => column.push_string(record.reference_bases().to_string()),
-                    "alternate" => column.push_string(allele.to_string()),
-                    "quality" => column.push_f32(record.quality_score().map(|v| v.into())),
-                    "filter" => column.push_vecstring(
-                        record
-                            .filters()
-                            .iter()
-                            .map(|s| Some(s.to_string()))
-                            .collect(),
-                    )?,
-                    _ => {}
+            let allele = allele.to_string();
+            let mut variant_id = None;
+            let mut classification = None;
+            let structural = if self.plan.structural {
+                variant::structural(
+                    usize::from(record.position()),
+                    &allele,
+                    alt_id,
+                    record.info(),
+                )
+            } else {
+                None
+            };
+            for (index, field) in self.plan.core.iter() {
+                let column = &mut self.columns[*index];
+                match field {
+                    Core::Chromosome => column.push_string(&chromosome)?,
+                    Core::Position => {
+                        column.push_i32(Some(usize::from(record.position()) as i32))?
+                    }
+                    Core::Identifier => {
+                        column.push_vecstring(record.ids().iter().map(|id| Some(&**id)))?
+                    }
+                    Core::Reference => column.push_string(&reference_bases)?,
+                    Core::Alternate => column.push_string(&allele)?,
+                    Core::Quality => column.push_f32(record.quality_score().map(|v| v.into()))?,
+                    Core::Filter => column
+                        .push_vecstring(record.filters().iter().map(|s| Some(s.to_string())))?,
+                    Core::VariantId | Core::VariantHash => {
+                        if variant_id.is_none() {
+                            variant_id = Some(variant::id(
+                                &chromosome,
+                                usize::from(record.position()),
+                                &reference_bases,
+                                &allele,
+                                options.normalize(),
+                                options.reference(),
+                            )?);
+                        }
+                        let id = variant_id.as_deref().unwrap_or_default();
+
+                        match field {
+                            Core::VariantId => column.push_string(id)?,
+                            _ => column.push_i64(variant::hash(id))?,
+                        }
+                    }
+                    Core::VariantType | Core::LengthChange | Core::Transition => {
+                        let class = *classification
+                            .get_or_insert_with(|| variant::classify(&reference_bases, &allele));
+
+                        match field {
+                            Core::VariantType => column.push_string(class.class.as_str())?,
+                            Core::LengthChange => column.push_i32(class.length_change)?,
+                            _ => match class.transition {
+                                Some(value) => column.push_bool(value)?,
+                                None => column.push_null()?,
+                            },
+                        }
+                    }
+                    Core::Context => match &context {
+                        Some(value) => column.push_string(value)?,
+                        None => column.push_null()?,
+                    },
+                    Core::ReferenceMatch => match reference_match {
+                        Some(value) => column.push_bool(value)?,
+                        None => column.push_null()?,
+                    },
+                    Core::SvType | Core::MateChromosome | Core::Orientation => {
+                        let sv = structural.as_ref();
+                        let value = match field {
+                            Core::SvType => sv.and_then(|s| s.sv_type.as_deref()),
+                            Core::MateChromosome => sv.and_then(|s| s.mate_chromosome.as_deref()),
+                            _ => sv.and_then(|s| s.orientation),
+                        };
+
+                        match value {
+                            Some(value) => column.push_string(value)?,
+                            None => column.push_null()?,
+                        }
+                    }
+                    _ => {
+                        let sv = structural.as_ref();
+                        column.push_i32(match field {
+                            Core::SvLength => sv.and_then(|s| s.length),
+                            Core::SvEnd => sv.and_then(|s| s.end),
+                            Core::MatePosition => sv.and_then(|s| s.mate_position),
+                            Core::CiposStart => sv.and_then(|s| s.cipos).map(|c| c.0),
+                            Core::CiposEnd => sv.and_then(|s| s.cipos).map(|c| c.1),
+                            Core::CiendStart => sv.and_then(|s| s.ciend).map(|c| c.0),
+                            _ => sv.and_then(|s| s.ciend).map(|c| c.1),
+                        })?
+                    }
                 }
             }
-            self.add_info(&record, header, schema, alt_id, allele_count)?;
-            self.add_format(&record, header, schema, alt_id, allele_count)?;
+            nulled += self.add_info(&record, header, options, alt_id, allele_count)?;
+            nulled += self.add_format(&record, header, options, alt_id, allele_count)?;
         }
-        Ok(())
+        Ok(nulled)
     }
 
     fn add_info(
         &mut self,
         record: &noodles::vcf::Record,
         header: &noodles::vcf::Header,
-        schema: &rustc_hash::FxHashMap<String, Field>,
+        options: &options::Options,
         alt_id: usize,
         allele_count: usize,
-    ) -> std::result::Result<(), arrow::error::ArrowError> {
+    ) -> error::Result<usize> {
         let info = record.info();
+        let mut nulled = 0;
 
-        for key in header.infos().keys() {
-            let key_name = format!("info_{}", key);
-            let info_def = header.infos().get(key).unwrap();
-            if let Some(column) = self.0.get_mut(&key_name) {
-                match info.get(key).flatten() {
-                    Some(value) => match value {
-                        noodles::vcf::record::info::field::Value::Flag => {
-                            column.push_bool(true);
-                        }
-                        noodles::vcf::record::info::field::Value::Integer(value) => {
-                            column.push_i32(Some(*value));
-                        }
-                        noodles::vcf::record::info::field::Value::Float(value) => {
-                            column.push_f32(Some(*value));
-                        }
-                        noodles::vcf::record::info::field::Value::String(value) => {
-                            column.push_string(value.to_string());
-                        }
-                        noodles::vcf::record::info::field::Value::Character(value) => {
-                            column.push_string(value.to_string());
-                        }
-                        noodles::vcf::record::info::field::Value::Array(arr) => match arr.clone() {
-                            noodles::vcf::record::info::field::value::Array::Integer(array_val) => {
-                                match info_def.number() {
-                                    noodles::vcf::header::Number::Count(0 | 1) => {
-                                        unreachable!(
-                                            "Field {} declared as single value but found array",
-                                            key
-                                        )
-                                    }
-                                    noodles::vcf::header::Number::Count(_) => {
-                                        column.push_veci32(array_val)?;
-                                    }
-                                    noodles::vcf::header::Number::A => {
-                                        column.push_i32(*array_val.get(alt_id).unwrap_or(&None));
-                                    }
-                                    noodles::vcf::header::Number::R => {
-                                        column.push_veci32(vec![
-                                            *array_val.first().unwrap_or(&None),
-                                            *array_val.get(alt_id + 1).unwrap_or(&None),
-                                        ])?;
-                                    }
-                                    noodles::vcf::header::Number::G => {
-                                        if array_val.len()
-                                            == (allele_count * (allele_count + 1) / 2)
-                                        {
-                                            column.push_veci32(vec![
-                                                *array_val.first().unwrap_or(&None),
-                                                *array_val
-                                                    .get((alt_id * alt_id + 3 * alt_id + 2) / 2)
-                                                    .unwrap_or(&None),
-                                                *array_val
-                                                    .get((alt_id * alt_id + 5 * alt_id + 4) / 2)
-                                                    .unwrap_or(&None),
-                                            ])?;
-                                        } else if array_val.len() == allele_count {
-                                            column.push_veci32(vec![
-                                                *array_val.first().unwrap_or(&None),
-                                                Some(0),
-                                                *array_val.get(alt_id + 1).unwrap_or(&None),
-                                            ])?;
-                                        } else {
-                                            column.push_null();
-                                        }
-                                    }
-                                    noodles::vcf::header::Number::Unknown => {
-                                        column.push_veci32(array_val)?;
-                                    }
-                                }
-                            }
-                            noodles::vcf::record::info::field::value::Array::Float(array_val) => {
-                                match info_def.number() {
-                                    noodles::vcf::header::Number::Count(0 | 1) => {
-                                        unreachable!(
-                                            "Field {} declared as single value but found array",
-                                            key
-                                        )
-                                    }
-                                    noodles::vcf::header::Number::Count(_) => {
-                                        column.push_vecf32(array_val)?;
-                                    }
-                                    noodles::vcf::header::Number::A => {
-                                        column.push_f32(*array_val.get(alt_id).unwrap_or(&None));
-                                    }
-                                    noodles::vcf::header::Number::R => {
-                                        column.push_vecf32(vec![
-                                            *array_val.first().unwrap_or(&None),
-                                            *array_val.get(alt_id + 1).unwrap_or(&None),
-                                        ])?;
-                                    }
-                                    noodles::vcf::header::Number::G => {
-                                        if array_val.len()
-                                            == (allele_count * (allele_count + 1) / 2)
-                                        {
-                                            column.push_vecf32(vec![
-                                                *array_val.first().unwrap_or(&None),
-                                                *array_val
-                                                    .get((alt_id * alt_id + 3 * alt_id + 2) / 2)
-                                                    .unwrap_or(&None),
-                                                *array_val
-                                                    .get((alt_id * alt_id + 5 * alt_id + 4) / 2)
-                                                    .unwrap_or(&None),
-                                            ])?;
-                                        } else if array_val.len() == allele_count {
-                                            column.push_vecf32(vec![
-                                                *array_val.first().unwrap_or(&None),
-                                                Some(0.),
-                                                *array_val.get(alt_id + 1).unwrap_or(&None),
-                                            ])?;
-                                        } else {
-                                            column.push_null();
-                                        }
-                                    }
-                                    noodles::vcf::header::Number::Unknown => {
-                                        column.push_vecf32(array_val)?;
-                                    }
-                                }
-                            }
-                            noodles::vcf::record::info::field::value::Array::String(array_val) => {
-                                match info_def.number() {
-                                    noodles::vcf::header::Number::Count(0 | 1) => {
-                                        unreachable!(
-                                            "Field {} declared as single value but found array",
-                                            key_name
-                                        )
-                                    }
-                                    noodles::vcf::header::Number::Count(_) => {
-                                        column.push_vecstring(array_val)?;
-                                    }
-                                    noodles::vcf::header::Number::A => {
-                                        column.push_string(
-                                            array_val
-                                                .get(alt_id)
-                                                .unwrap()
-                                                .clone()
-                                                .unwrap_or_default(),
-                                        );
-                                    }
-                                    noodles::vcf::header::Number::R => {
-                                        column.push_vecstring(vec![
-                                            Some(
-                                                array_val
-                                                    .first()
-                                                    .unwrap()
-                                                    .clone()
-                                                    .unwrap_or_default(),
-                                            ),
-                                            Some(
-                                                array_val
-                                                    .get(alt_id + 1)
-                                                    .unwrap()
-                                                    .clone()
-                                                    .unwrap_or_default(),
-                                            ),
-                                        ])?;
-                                    }
-                                    noodles::vcf::header::Number::G => {
-                                        if array_val.len()
-                                            == (allele_count * (allele_count + 1) / 2)
-                                        {
-                                            column.push_vecstring(vec![
-                                                Some(
-                                                    array_val
-                                                        .first()
-                                                        .unwrap()
-                                                        .clone()
-                                                        .unwrap_or_default(),
-                                                ),
-                                                Some(
-                                                    array_val
-                                                        .get((alt_id * alt_id + 3 * alt_id + 2) / 2)
-                                                        .unwrap()
-                                                        .clone()
-                                                        .unwrap_or_default(),
-                                                ),
-                                                Some(
-                                                    array_val
-                                                        .get((alt_id * alt_id + 5 * alt_id + 4) / 2)
-                                                        .unwrap()
-                                                        .clone()
-                                                        .unwrap_or_default(),
-                                                ),
-                                            ])?;
-                                        } else if array_val.len() == allele_count {
-                                            column.push_vecstring(vec![
-                                                array_val.first().unwrap().clone(),
-                                                Some(".".to_string()),
-                                                array_val.get(alt_id + 1).unwrap().clone(),
-                                            ])?;
-                                        } else {
-                                            column.push_null();
-                                        }
-                                    }
-                                    noodles::vcf::header::Number::Unknown => {
-                                        column.push_vecstring(array_val)?;
-                                    }
-                                }
-                            }
-                            noodles::vcf::record::info::field::value::Array::Character(
-                                array_val,
-                            ) => match info_def.number() {
-                                noodles::vcf::header::Number::Count(0 | 1) => {
-                                    unreachable!(
-                                        "Field {} declared as single value but found array",
-                                        key_name
-                                    )
-                                }
-                                noodles::vcf::header::Number::Count(_) => {
-                                    column.push_vecstring(
-                                        array_val
-                                            .iter()
-                                            .map(|s| s.as_ref().map(|s| s.to_string()))
-                                            .collect::<Vec<Option<String>>>(),
-                                    )?;
-                                }
-                                noodles::vcf::header::Number::A => {
-                                    column.push_string(
-                                        (*array_val.get(alt_id).unwrap()).unwrap().to_string(),
-                                    );
-                                }
-                                noodles::vcf::header::Number::R => {
-                                    column.push_vecstring(vec![
-                                        Some(array_val.first().unwrap().unwrap().to_string()),
-                                        Some(
-                                            array_val.get(alt_id + 1).unwrap().unwrap().to_string(),
-                                        ),
-                                    ])?;
-                                }
-                                noodles::vcf::header::Number::G => {
-                                    if array_val.len() == (allele_count * (allele_count + 1) / 2) {
-                                        column.push_vecstring(vec![
-                                            Some(array_val.first().unwrap().unwrap().to_string()),
-                                            Some(
-                                                array_val
-                                                    .get((alt_id * alt_id + 3 * alt_id + 2) / 2)
-                                                    .unwrap()
-                                                    .unwrap()
-                                                    .to_string(),
-                                            ),
-                                            Some(
-                                                array_val
-                                                    .get((alt_id * alt_id + 5 * alt_id + 4) / 2)
-                                                    .unwrap()
-                                                    .unwrap()
-                                                    .to_string(),
-                                            ),
-                                        ])?;
-                                    } else if array_val.len() == allele_count {
-                                        column.push_vecstring(vec![
-                                            Some(array_val.first().unwrap().unwrap().to_string()),
-                                            Some(".".to_string()),
-                                            Some(
-                                                array_val
-                                                    .get(alt_id + 1)
-                                                    .unwrap()
-                                                    .unwrap()
-                                                    .to_string(),
-                                            ),
-                                        ])?;
-                                    } else {
-                                        column.push_null();
-                                    }
-                                }
-                                noodles::vcf::header::Number::Unknown => {
-                                    column.push_vecstring(
-                                        array_val
-                                            .iter()
-                                            .map(|s| s.as_ref().map(|s| s.to_string()))
-                                            .collect::<Vec<Option<String>>>(),
-                                    )?;
-                                }
-                            },
-                        },
-                    },
-                    None => {
-                        if info_def.ty()
-                            == noodles::vcf::header::record::value::map::info::Type::Flag
-                        {
-                            column.push_bool(false);
-                        } else {
-                            //Handle missing info field, only matters for FixedSizeList
-                            if schema.get(&key_name).is_some() {
-                                match column {
-                                    ColumnData::ListFloat(_) => {
-                                        column.push_vecf32(vec![])?;
-                                    }
-                                    ColumnData::ListInt(_) => {
-                                        column.push_veci32(vec![])?;
-                                    }
-                                    ColumnData::ListString(_) => {
-                                        column.push_vecstring(vec![])?;
-                                    }
-                                    _ => column.push_null(), //Otherwise, just push null
-                                }
-                            } else {
-                                unreachable!("Malformed VCF, {} should be in schema", key_name);
-                            }
-                        }
+        for ((key, info_def), index) in header.infos().iter().zip(self.plan.infos.iter()) {
+            if let Some(index) = index {
+                let column = &mut self.columns[*index];
+                let result = match info.get(key).flatten() {
+                    Some(value) => {
+                        push_info(column, value, info_def.number(), alt_id, allele_count)
                     }
+                    None if info_def.ty()
+                        == noodles::vcf::header::record::value::map::info::Type::Flag =>
+                    {
+                        column.push_bool(false).map_err(|e| e.to_string())
+                    }
+                    //Handle missing info field, only matters for FixedSizeList
+                    None => push_missing(column),
+                };
+
+                if let Err(reason) = result {
+                    invalid(column, &self.plan.names[*index], record, reason, options)?;
+                    nulled += 1;
                 }
             }
         }
-        Ok(())
+
+        if let Some(index) = self.plan.info_extra {
+            let column = &mut self.columns[index];
+            let extra = undeclared_info(record, header);
+            if extra.is_empty() {
+                column.push_null()?;
+            } else {
+                column.push_map(extra)?;
+            }
+        }
+
+        Ok(nulled)
     }
 
     fn add_format(
         &mut self,
         record: &noodles::vcf::Record,
         header: &noodles::vcf::Header,
-        schema: &rustc_hash::FxHashMap<String, Field>,
+        options: &options::Options,
         alt_id: usize,
         allele_count: usize,
-    ) -> std::result::Result<(), arrow::error::ArrowError> {
-        for key in header.formats().keys() {
-            for (idx, sample) in header.sample_names().iter().enumerate() {
-                let key_name = format!("format_{}_{}", sample, key);
-                let format_def = header.formats().get(key).unwrap();
-                if let Some(column) = self.0.get_mut(&key_name) {
-                    if let Some(format_field) = record.genotypes().get_index(idx) {
-                        match format_field.get(key).flatten() {
-                            Some(value) => match value {
-                                    noodles::vcf::record::genotypes::sample::Value::Integer(value) => column.push_i32(Some(*value)),
-                                    noodles::vcf::record::genotypes::sample::Value::Float(value) => column.push_f32(Some(*value)),
-                                    noodles::vcf::record::genotypes::sample::Value::String(value) => {
-                                    if key.to_string()=="GT" {
-                                        let mut gt_str = String::with_capacity(32); //Arbitrary capacity
-                                        if let Some(gt) = format_field.genotype().and_then(|g|g.ok())
-                                        {
-                                            gt.iter().enumerate().for_each(|(i,allele)| {
-                                                let (position, phasing) = (allele.position(), allele.phasing());
-                                                match position {
-                                                    Some(a) if a == alt_id + 1 => {
-                                                        gt_str.push('1');
-                                                    }
-                                                    Some(0)=>{
-                                                        gt_str.push('0');
-                                                    }
-                                                    Some(_) =>{
-                                                        gt_str.push('.');
-                                                    }
-                                                    None=>{
-                                                        gt_str.push('.');
-                                                    }
-                                                }
-                                                if i < gt.len() - 1 {
-                                                    gt_str.push(match phasing {
-							Phasing::Phased => '|',
-							Phasing::Unphased => '/',
-                                                    });
-                                                }
-                                            });
-                                        }
-                                        else {
-                                            unreachable!("If GT is not present, the match arm won't take us there")
-                                        }
-                                        column.push_string(gt_str);
-                                    } else {
-                                        column.push_string(value.to_string());
-                                    }
-                                }
-                                    noodles::vcf::record::genotypes::sample::Value::Character(
-                                        value
-                                ) => {
-                                    column.push_string(value.to_string());
-                                }
-                                    noodles::vcf::record::genotypes::sample::Value::Array(arr)
-                                 => match arr.clone() {
-                                    noodles::vcf::record::genotypes::sample::value::Array::Integer(
-                                        array_val,
-                                    ) => match format_def.number() {
-                                        noodles::vcf::header::Number::Count(0 | 1) => {
-                                            unreachable!(
-                                                "Field {} declared as single value but found array",
-                                                key
-                                            )
-                                        }
-                                        noodles::vcf::header::Number::Count(_) => {
-                                            column.push_veci32(array_val)?;
-                                        }
-                                        noodles::vcf::header::Number::A => {
-                                            column.push_i32(*array_val.get(alt_id).unwrap());
-                                        }
-                                        noodles::vcf::header::Number::R => {
-                                            //TODO: Use push_fixed_size_i32
-                                            column.push_veci32(vec![
-                                                *array_val.first().unwrap(),
-                                                *array_val.get(alt_id + 1).unwrap(),
-                                            ])?;
-                                        }
-                                        noodles::vcf::header::Number::G => {
-                                            if array_val.len()
-                                            == (allele_count * (allele_count + 1) / 2)
-                                            {
-                                                column.push_veci32(vec![
-                                                    *array_val.first().unwrap(),
-                                                    *array_val
-                                                        .get((alt_id * alt_id + 3 * alt_id + 2) / 2)
-                                                        .unwrap(),
-                                                    *array_val
-                                                        .get((alt_id * alt_id + 5 * alt_id + 4) / 2)
-                                                        .unwrap(),
-                                                ])?;
-                                            } else if array_val.len() == allele_count {
-                                                column.push_veci32(vec![
-                                                    *array_val.first().unwrap(),
-                                                    Some(0),
-                                                    *array_val.get(alt_id + 1).unwrap(),
-                                                ])?;
-                                            } else {
-                                                column.push_null();
-                                            }
-                                        }
-                                        noodles::vcf::header::Number::Unknown => {
-                                            column.push_veci32(array_val)?;
-                                        }
-                                    },
-                                    noodles::vcf::record::genotypes::sample::value::Array::Float(
-                                        array_val,
-                                    ) => match format_def.number() {
-                                        noodles::vcf::header::Number::Count(0 | 1) => {
-                                            unreachable!(
-                                                "Field {} declared as single value but found array",
-                                                key
-                                            )
-                                        }
-                                        noodles::vcf::header::Number::Count(_) => {
-                                            column.push_vecf32(array_val)?;
-                                        }
-                                        noodles::vcf::header::Number::A => {
-                                            column.push_f32(*array_val.get(alt_id).unwrap());
-                                        }
-                                        noodles::vcf::header::Number::R => {
-                                            //TODO: Use push_fixed_size_f32
-                                            column.push_vecf32(vec![
-                                                *array_val.first().unwrap(),
-                                                *array_val.get(alt_id + 1).unwrap(),
-                                            ])?;
-                                        }
-                                        noodles::vcf::header::Number::G => {
-                                            if array_val.len()
-                                            == (allele_count * (allele_count + 1) / 2)
-                                            {
-                                                column.push_vecf32(vec![
-                                                    *array_val.first().unwrap(),
-                                                    *array_val
-                                                        .get((alt_id * alt_id + 3 * alt_id + 2) / 2)
-                                                        .unwrap(),
-                                                    *array_val
-                                                        .get((alt_id * alt_id + 5 * alt_id + 4) / 2)
-                                                        .unwrap(),
-                                                ])?;
-                                            } else if array_val.len() == allele_count {
-                                                column.push_vecf32(vec![
-                                                    *array_val.first().unwrap(),
-                                                    Some(0.),
-                                                    *array_val.get(alt_id + 1).unwrap(),
-                                                ])?;
-                                            } else {
-                                                column.push_null();
-                                            }
-                                        }
-                                        noodles::vcf::header::Number::Unknown => {
-                                            column.push_vecf32(array_val)?;
-                                        }
-                                    },
-                                    noodles::vcf::record::genotypes::sample::value::Array::String(
-                                        array_val,
-                                    ) => match format_def.number() {
-                                        noodles::vcf::header::Number::Count(0 | 1) => {
-                                            unreachable!(
-                                                "Field {} declared as single value but found array",
-                                                key_name
-                                            )
-                                        },
-                                        noodles::vcf::header::Number::Count(_) => {
-                                            column.push_vecstring(array_val)?;
-                                        },
-                                        noodles::vcf::header::Number::A => {
-                                            column.push_string(
-                                                array_val.get(alt_id).unwrap().clone().unwrap(),
-                                            );
-                                        },
-                                        noodles::vcf::header::Number::R => {
-                                            //TODO: Use push_fixed_size_string
-                                            column.push_vecstring(vec![
-                                                Some(array_val.first().unwrap().clone().unwrap()),
-                                                Some(array_val.get(alt_id + 1).unwrap().clone().unwrap()),
-                                            ])?;
-                                        },
-                                        noodles::vcf::header::Number::G => {
-                                            if array_val.len()
-                                            == (allele_count * (allele_count + 1) / 2)
-                                            {
-                                                column.push_vecstring(vec![
-                                                    array_val.first().unwrap().clone(),
-                                                    array_val
-                                                        .get((alt_id * alt_id + 3 * alt_id + 2) / 2)
-                                                        .unwrap()
-                                                        .clone(),
-                                                    array_val
-                                                        .get((alt_id * alt_id + 5 * alt_id + 4) / 2)
-                                                        .unwrap()
-                                                        .clone(),
-                                                ])?;
-                                            } else if array_val.len() == allele_count {
-                                                column.push_vecstring(vec![
-                                                    array_val.first().unwrap().clone(),
-                                                    Some(".".to_string()),
-                                                    array_val.get(alt_id + 1).unwrap().clone(),
-                                                ])?;
-                                            } else {
-                                                column.push_null();
-                                            }
-                                        }
-                                        noodles::vcf::header::Number::Unknown => {
-                                            column.push_vecstring(array_val)?;
-                                        }
-                                    },
-                                    noodles::vcf::record::genotypes::sample::value::Array::Character(
-                                        array_val,
-                                    ) => match format_def.number() {
-                                        noodles::vcf::header::Number::Count(0 | 1) => {
-                                            unreachable!(
-                                                "Field {} declared as single value but found array",
-                                                key_name
-                                            )
-                                        },
-                                        noodles::vcf::header::Number::Count(_) => {
-                                            column.push_vecstring(
-                                                array_val
-                                                    .iter()
-                                                    .map(|s| s.as_ref().map(|s| s.to_string()))
-                                                    .collect::<Vec<Option<String>>>(),
-                                            )?;
-                                        },
-                                        noodles::vcf::header::Number::A => {
-                                            column.push_string(
-                                                (*array_val.get(alt_id).unwrap()).unwrap().to_string(),
-                                            );
-                                        },
-                                        noodles::vcf::header::Number::R => {
-                                            column.push_vecstring(vec![
-                                                Some(array_val.first().unwrap().unwrap().to_string()),
-                                                Some(array_val.get(alt_id + 1).unwrap().unwrap().to_string()),
-                                            ])?;
-                                        },
-                                        noodles::vcf::header::Number::G => {
-                                            if array_val.len() == (allele_count * (allele_count + 1) / 2) {
-                                                column.push_vecstring(vec![
-                                                    Some(array_val.first().unwrap().unwrap().to_string()),
-                                                    Some(
-                                                        array_val
-                                                            .get((alt_id * alt_id + 3 * alt_id + 2) / 2)
-                                                            .unwrap()
-                                                            .unwrap()
-                                                            .to_string(),
-                                                    ),
-                                                    Some(
-                                                        array_val
-                                                            .get((alt_id * alt_id + 5 * alt_id + 4) / 2)
-                                                            .unwrap()
-                                                            .unwrap()
-                                                            .to_string(),
-                                                    ),
-                                                ])?;
-                                            } else if array_val.len() == allele_count {
-                                                column.push_vecstring(vec![
-                                                    Some(array_val.first().unwrap().unwrap().to_string()),
-                                                    Some(".".to_string()),
-                                                    Some(
-                                                        array_val.get(alt_id + 1).unwrap().unwrap().to_string(),
-                                                    ),
-                                                ])?;
-                                            } else {
-                                                column.push_null();
-                                            }
-                                        }
-                                        ,
-                                        noodles::vcf::header::Number::Unknown => {
-                                            column.push_vecstring(
-                                                array_val
-                                                    .iter()
-                                                    .map(|s| s.as_ref().map(|s| s.to_string()))
-                                                    .collect::<Vec<Option<String>>>(),
-                                            )?;
-                                        },
-                                    },
-
-                                },
-                            },
-                            None => if schema.get(&key_name).is_some() {
-                                match column {
-                                    ColumnData::ListFloat(_) => {
-                                        column.push_vecf32(vec![])?;
-                                    }
-                                    ColumnData::ListInt(_) => {
-                                        column.push_veci32(vec![])?;
-                                    }
-                                    ColumnData::ListString(_) => {
-                                        column.push_vecstring(vec![])?;
-                                    }
-                                    _ if key.to_string() == "GT" => {
-                                        column.push_string("./.".to_string());
-                                    }
-                                    _ => column.push_null(),
-                                }
+    ) -> error::Result<usize> {
+        let mut nulled = 0;
+
+        for ((key, format_def), indexes) in header.formats().iter().zip(self.plan.formats.iter()) {
+            for (idx, index) in indexes.iter().enumerate() {
+                if let Some(index) = index {
+                    let column = &mut self.columns[*index];
+                    // a record without value for this sample is manage as a missing value
+                    let values = record.genotypes().get_index(idx);
+                    let result = match values.as_ref().and_then(|v| v.get(key)).flatten() {
+                        Some(_) if key.as_ref() == "GT" => {
+                            if self.calls[idx].write(alt_id, &mut self.buffer) {
+                                column.push_string(&self.buffer).map_err(|e| e.to_string())
                             } else {
-                                unreachable!("{} should be in schema", key_name);
-                            },
+                                Err("genotype can't be parsed".to_string())
+                            }
                         }
-                    } else {
-                        todo!("Understand how we could get there (the tests never did)");
+                        Some(value) => {
+                            push_format(column, value, format_def.number(), alt_id, allele_count)
+                        }
+                        None if key.as_ref() == "GT" && !is_list(column) => {
+                            column.push_string("./.").map_err(|e| e.to_string())
+                        }
+                        None => push_missing(column),
+                    };
+
+                    if let Err(reason) = result {
+                        invalid(column, &self.plan.names[*index], record, reason, options)?;
+                        nulled += 1;
                     }
                 }
             }
         }
+
+        if !self.plan.stats.is_empty() {
+            let mut counts = cohort::Counts::new();
+            for idx in self.plan.stat_samples.iter() {
+                // a genotype that can't be parsed is count as missing
+                counts.add(self.calls[*idx].alleles(), alt_id + 1);
+            }
+
+            for (index, stat) in self.plan.stats.iter() {
+                let column = &mut self.columns[*index];
+                match stat {
+                    Stat::AlleleCount => column.push_i32(Some(counts.allele_count() as i32))?,
+                    Stat::AlleleNumber => column.push_i32(Some(counts.allele_number() as i32))?,
+                    Stat::AlleleFrequency => column.push_f64(counts.allele_frequency())?,
+                    Stat::Het => column.push_i32(Some(counts.het() as i32))?,
+                    Stat::HomAlt => column.push_i32(Some(counts.hom_alt() as i32))?,
+                    Stat::Missing => column.push_i32(Some(counts.missing() as i32))?,
+                    Stat::CallRate => column.push_f64(counts.call_rate())?,
+                    Stat::Hwe => column.push_f64(counts.hwe())?,
+                }
+            }
+        }
+
+        for (idx, index) in self.plan.format_extras.iter().enumerate() {
+            if let Some(index) = index {
+                let column = &mut self.columns[*index];
+                let extra = undeclared_format(record, header, idx);
+                if extra.is_empty() {
+                    column.push_null()?;
+                } else {
+                    column.push_map(extra)?;
+                }
+            }
+        }
+
+        Ok(nulled)
+    }
+
+    /// Get number of records with a REF that doesn't match reference
+    pub fn reference_mismatch(&self) -> usize {
+        self.reference_mismatch
+    }
+
+    /// Compare REF with reference, return REF, filled if it's only N, if REF match reference and sequence context
+    fn reference(
+        &mut self,
+        record: &noodles::vcf::Record,
+        options: &options::Options,
+    ) -> (String, Option<bool>, Option<String>) {
+        let mut bases = record.reference_bases().to_string();
+
+        let Some(fasta) = options.reference() else {
+            return (bases, None, None);
+        };
+        if options.context() == 0 && !options.check_reference() && !options.fill_reference() {
+            return (bases, None, None);
+        }
+
+        let chromosome = record.chromosome().to_string();
+        let start = usize::from(record.position());
+        let end = start + bases.len().max(1) - 1;
+
+        // chromosome absent of reference or REF after sequence end, nothing can be check
+        let Ok(expected) = fasta.fetch(&chromosome, start, end) else {
+            return (bases, None, None);
+        };
+
+        if options.fill_reference() && bases.bytes().all(|b| b.eq_ignore_ascii_case(&b'N')) {
+            bases = String::from_utf8_lossy(&expected).to_string();
+        }
+
+        let matches = options.check_reference().then(|| {
+            let matches = reference::same_sequence(bases.as_bytes(), &expected);
+            if !matches {
+                self.reference_mismatch += 1;
+                log::debug!(
+                    "REF {} of record {}:{} doesn't match reference {}",
+                    bases,
+                    chromosome,
+                    start,
+                    String::from_utf8_lossy(&expected)
+                );
+            }
+            matches
+        });
+
+        let context = (options.context() != 0)
+            .then(|| fasta.context(&chromosome, start, end, options.context()))
+            .and_then(|c| c.ok())
+            .map(|c| String::from_utf8_lossy(&c).to_string());
+
+        (bases, matches, context)
+    }
+
+    /// Estimate memory used by all column builders in bytes
+    pub fn memory(&self) -> usize {
+        self.columns.iter().map(|c| c.memory()).sum()
+    }
+
+    /// Convert Name2Data in vector of arrow array, in schema order
+    pub fn into_arc(self) -> Vec<std::sync::Arc<dyn arrow::array::Array>> {
+        self.columns.into_iter().map(|c| c.into_arc()).collect()
+    }
+}
+
+/// Get position of record as `chromosome:position`, used in error message
+pub fn record_position(record: &noodles::vcf::Record) -> String {
+    format!("{}:{}", record.chromosome(), record.position())
+}
+
+/// Values of an array field associate to an allele, `V` is the view of a value `T` of record
+enum Selection<'a, T, V> {
+    /// Field store one value by allele
+    One(Option<V>),
+    /// Field store multiple values
+    Many(Many<'a, T, V>),
+    /// Number of values doesn't match any ploidy
+    Null,
+}
+
+/// Multiple values of a field, borrowed from record so no vector is build for each value
+enum Many<'a, T, V> {
+    /// All values of field
+    All(&'a [Option<T>], fn(&'a T) -> V),
+    /// Values picked for allele, only first `usize` are used
+    Picked([Option<V>; 3], usize),
+}
+
+impl<'a, T, V> Many<'a, T, V>
+where
+    V: Copy + 'a,
+{
+    /// Iterate on values
+    fn values(self) -> impl Iterator<Item = Option<V>> + Clone + 'a {
+        let length = match self {
+            Many::All(values, _) => values.len(),
+            Many::Picked(_, length) => length,
+        };
+
+        (0..length).map(move |i| match self {
+            Many::All(values, view) => values[i].as_ref().map(view),
+            Many::Picked(values, _) => values[i],
+        })
+    }
+}
+
+impl<T, V> Clone for Many<'_, T, V>
+where
+    V: Copy,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, V> Copy for Many<'_, T, V> where V: Copy {}
+
+/// Select values of an array field associate to allele `alt_id`, `filler` is used for haploid Number=G field
+///
+/// A value absent of a too short array is manage as a missing value, `missing` is used for missing value of Number=A, R and G field.
+fn select<'a, T, V>(
+    values: &'a [Option<T>],
+    view: fn(&'a T) -> V,
+    number: noodles::vcf::header::Number,
+    alt_id: usize,
+    allele_count: usize,
+    filler: Option<V>,
+    missing: Option<V>,
+) -> std::result::Result<Selection<'a, T, V>, String>
+where
+    V: Copy,
+{
+    let raw = |i: usize| values.get(i).and_then(|v| v.as_ref()).map(view);
+    let get = |i: usize| raw(i).or(missing);
+
+    match number {
+        noodles::vcf::header::Number::Count(0 | 1) => Err(format!(
+            "field declared as single value but found {} values",
+            values.len()
+        )),
+        noodles::vcf::header::Number::Count(_) | noodles::vcf::header::Number::Unknown => {
+            Ok(Selection::Many(Many::All(values, view)))
+        }
+        noodles::vcf::header::Number::A => Ok(Selection::One(get(alt_id))),
+        noodles::vcf::header::Number::R => Ok(Selection::Many(Many::Picked(
+            [get(0), get(alt_id + 1), None],
+            2,
+        ))),
+        noodles::vcf::header::Number::G
+            if values.len() == allele_count * (allele_count + 1) / 2 =>
+        {
+            Ok(Selection::Many(Many::Picked(
+                [
+                    get(0),
+                    get((alt_id * alt_id + 3 * alt_id + 2) / 2),
+                    get((alt_id * alt_id + 5 * alt_id + 4) / 2),
+                ],
+                3,
+            )))
+        }
+        noodles::vcf::header::Number::G if values.len() == allele_count => Ok(Selection::Many(
+            Many::Picked([raw(0), filler, raw(alt_id + 1)], 3),
+        )),
+        noodles::vcf::header::Number::G => Ok(Selection::Null),
+    }
+}
+
+fn push_integers(
+    column: &mut ColumnData,
+    selection: Selection<'_, i32, i32>,
+) -> std::result::Result<(), String> {
+    match selection {
+        Selection::One(value) => column.push_i32(value),
+        Selection::Many(values) => column.push_veci32(values.values()),
+        Selection::Null => column.push_null(),
+    }
+    .map_err(|e| e.to_string())
+}
+
+fn push_floats(
+    column: &mut ColumnData,
+    selection: Selection<'_, f32, f32>,
+) -> std::result::Result<(), String> {
+    match selection {
+        Selection::One(value) => column.push_f32(value),
+        Selection::Many(values) => column.push_vecf32(values.values()),
+        Selection::Null => column.push_null(),
+    }
+    .map_err(|e| e.to_string())
+}
+
+fn push_doubles(
+    column: &mut ColumnData,
+    selection: Selection<'_, f64, f64>,
+) -> std::result::Result<(), String> {
+    match selection {
+        Selection::One(value) => column.push_f64(value),
+        Selection::Many(values) => column.push_vecf64(values.values()),
+        Selection::Null => column.push_null(),
+    }
+    .map_err(|e| e.to_string())
+}
+
+/// Parse raw text of a 64 bits float INFO field, see [crate::records::float64_infos]
+fn push_raw_floats(
+    column: &mut ColumnData,
+    value: &str,
+    number: noodles::vcf::header::Number,
+    alt_id: usize,
+    allele_count: usize,
+) -> std::result::Result<(), String> {
+    // text is already parsed by noodles, only missing value can't be parsed
+    let values = value
+        .split(',')
+        .map(|v| v.parse::<f64>().ok())
+        .collect::<Vec<Option<f64>>>();
+
+    match (number, values.as_slice()) {
+        (noodles::vcf::header::Number::Count(0 | 1), [value]) => {
+            column.push_f64(*value).map_err(|e| e.to_string())
+        }
+        _ => push_doubles(
+            column,
+            select(
+                &values,
+                f64::clone,
+                number,
+                alt_id,
+                allele_count,
+                Some(0.),
+                None,
+            )?,
+        ),
+    }
+}
+
+fn push_strings<'a>(
+    column: &mut ColumnData,
+    selection: Selection<'a, String, &'a str>,
+) -> std::result::Result<(), String> {
+    match selection {
+        Selection::One(Some(value)) => column.push_string(value),
+        Selection::Many(values) => column.push_vecstring(values.values()),
+        Selection::One(None) | Selection::Null => column.push_null(),
+    }
+    .map_err(|e| e.to_string())
+}
+
+fn push_info(
+    column: &mut ColumnData,
+    value: &noodles::vcf::record::info::field::Value,
+    number: noodles::vcf::header::Number,
+    alt_id: usize,
+    allele_count: usize,
+) -> std::result::Result<(), String> {
+    use noodles::vcf::record::info::field::value::Array;
+    use noodles::vcf::record::info::field::Value;
+
+    match value {
+        Value::Flag => column.push_bool(true).map_err(|e| e.to_string()),
+        Value::Integer(value) => column.push_i32(Some(*value)).map_err(|e| e.to_string()),
+        Value::Float(value) => column.push_f32(Some(*value)).map_err(|e| e.to_string()),
+        Value::String(value)
+            if matches!(column, ColumnData::Float64(_) | ColumnData::ListFloat64(_)) =>
+        {
+            push_raw_floats(column, value, number, alt_id, allele_count)
+        }
+        Value::String(value) => column.push_string(value).map_err(|e| e.to_string()),
+        Value::Character(value) => column
+            .push_string(value.to_string())
+            .map_err(|e| e.to_string()),
+        Value::Array(Array::Integer(values)) => push_integers(
+            column,
+            select(
+                values,
+                i32::clone,
+                number,
+                alt_id,
+                allele_count,
+                Some(0),
+                None,
+            )?,
+        ),
+        Value::Array(Array::Float(values)) => push_floats(
+            column,
+            select(
+                values,
+                f32::clone,
+                number,
+                alt_id,
+                allele_count,
+                Some(0.),
+                None,
+            )?,
+        ),
+        // missing string of an allele is write as empty string
+        Value::Array(Array::String(values)) => push_strings(
+            column,
+            select(
+                values,
+                String::as_str,
+                number,
+                alt_id,
+                allele_count,
+                Some("."),
+                Some(""),
+            )?,
+        ),
+        Value::Array(Array::Character(values)) => push_strings(
+            column,
+            select(
+                &characters(values),
+                String::as_str,
+                number,
+                alt_id,
+                allele_count,
+                Some("."),
+                Some(""),
+            )?,
+        ),
+    }
+}
+
+fn push_format(
+    column: &mut ColumnData,
+    value: &noodles::vcf::record::genotypes::sample::Value,
+    number: noodles::vcf::header::Number,
+    alt_id: usize,
+    allele_count: usize,
+) -> std::result::Result<(), String> {
+    use noodles::vcf::record::genotypes::sample::value::Array;
+    use noodles::vcf::record::genotypes::sample::Value;
+
+    match value {
+        Value::Integer(value) => column.push_i32(Some(*value)).map_err(|e| e.to_string()),
+        Value::Float(value) => column.push_f32(Some(*value)).map_err(|e| e.to_string()),
+        Value::String(value) => column.push_string(value).map_err(|e| e.to_string()),
+        Value::Character(value) => column
+            .push_string(value.to_string())
+            .map_err(|e| e.to_string()),
+        Value::Array(Array::Integer(values)) => push_integers(
+            column,
+            select(
+                values,
+                i32::clone,
+                number,
+                alt_id,
+                allele_count,
+                Some(0),
+                None,
+            )?,
+        ),
+        Value::Array(Array::Float(values)) => push_floats(
+            column,
+            select(
+                values,
+                f32::clone,
+                number,
+                alt_id,
+                allele_count,
+                Some(0.),
+                None,
+            )?,
+        ),
+        Value::Array(Array::String(values)) => push_strings(
+            column,
+            select(
+                values,
+                String::as_str,
+                number,
+                alt_id,
+                allele_count,
+                Some("."),
+                None,
+            )?,
+        ),
+        Value::Array(Array::Character(values)) => push_strings(
+            column,
+            select(
+                &characters(values),
+                String::as_str,
+                number,
+                alt_id,
+                allele_count,
+                Some("."),
+                None,
+            )?,
+        ),
+    }
+}
+
+fn characters(values: &[Option<char>]) -> Vec<Option<String>> {
+    values
+        .iter()
+        .map(|c| c.as_ref().map(|c| c.to_string()))
+        .collect()
+}
+
+/// Push missing value, list column get an empty list other a null
+fn push_missing(column: &mut ColumnData) -> std::result::Result<(), String> {
+    match column {
+        ColumnData::ListFloat16(_) | ColumnData::ListFloat(_) | ColumnData::ListFloat64(_) => {
+            column.push_vecf32(vec![])
+        }
+        ColumnData::ListInt8(_) | ColumnData::ListInt16(_) | ColumnData::ListInt(_) => {
+            column.push_veci32(vec![])
+        }
+        ColumnData::ListString(_) | ColumnData::ListDictString(_) => {
+            column.push_vecstring(std::iter::empty::<Option<&str>>())
+        }
+        _ => column.push_null(), //Otherwise, just push null
+    }
+    .map_err(|e| e.to_string())
+}
+
+fn is_list(column: &ColumnData) -> bool {
+    matches!(
+        column,
+        ColumnData::ListFloat16(_)
+            | ColumnData::ListFloat(_)
+            | ColumnData::ListFloat64(_)
+            | ColumnData::ListInt8(_)
+            | ColumnData::ListInt16(_)
+            | ColumnData::ListInt(_)
+            | ColumnData::ListString(_)
+            | ColumnData::ListDictString(_)
+    )
+}
+
+/// Genotype of a sample parsed from GT text, vectors are reuse between records
+#[derive(Debug, Clone, Default)]
+struct Call {
+    valid: bool,
+    alleles: Vec<Option<usize>>,
+    /// Phasing of each allele, as noodles it's the separator before allele except for the first one
+    phasings: Vec<char>,
+}
+
+impl Call {
+    /// Parse GT value, a missing value or a value that isn't a valid genotype is invalid
+    fn parse(&mut self, value: Option<&noodles::vcf::record::genotypes::sample::Value>) {
+        self.alleles.clear();
+        self.phasings.clear();
+
+        self.valid = match value {
+            Some(noodles::vcf::record::genotypes::sample::Value::String(text)) => {
+                self.parse_text(text)
+            }
+            _ => false,
+        };
+    }
+
+    /// Same rules as noodles, phasing of first allele is explicit (VCF 4.4) or unphased if any other allele is unphased
+    fn parse_text(&mut self, text: &str) -> bool {
+        let (explicit, mut rest) = match text.chars().next() {
+            Some(c @ ('/' | '|')) => (Some(c), &text[1..]),
+            Some(_) => (None, text),
+            None => return false,
+        };
+
+        let mut phasing = '|';
+        loop {
+            let end = rest.find(['/', '|']).unwrap_or(rest.len());
+            let position = match &rest[..end] {
+                "." => None,
+                raw => match raw.parse() {
+                    Ok(position) => Some(position),
+                    Err(_) => return false,
+                },
+            };
+            self.alleles.push(position);
+            self.phasings.push(phasing);
+
+            let Some(next) = rest[end..].chars().next() else {
+                break;
+            };
+            phasing = next;
+            rest = &rest[end + 1..];
+        }
+
+        self.phasings[0] = explicit.unwrap_or(if self.phasings[1..].contains(&'/') {
+            '/'
+        } else {
+            '|'
+        });
+
+        true
+    }
+
+    /// Get allele positions, None if genotype is invalid
+    fn alleles(&self) -> Option<impl Iterator<Item = Option<usize>> + '_> {
+        self.valid.then(|| self.alleles.iter().copied())
+    }
+
+    /// Write in `buffer` genotype string for allele `alt_id`, other alternative allele are replace by `.`, return false if genotype is invalid
+    fn write(&self, alt_id: usize, buffer: &mut String) -> bool {
+        buffer.clear();
+        if !self.valid {
+            return false;
+        }
+
+        for (i, position) in self.alleles.iter().enumerate() {
+            if i != 0 {
+                buffer.push(self.phasings[i - 1]);
+            }
+            buffer.push(match position {
+                Some(a) if *a == alt_id + 1 => '1',
+                Some(0) => '0',
+                _ => '.',
+            });
+        }
+
+        true
+    }
+}
+
+/// Manage a value that can't be convert, in lenient mode value is replace by null else an error is return
+fn invalid(
+    column: &mut ColumnData,
+    field: &str,
+    record: &noodles::vcf::Record,
+    reason: String,
+    options: &options::Options,
+) -> error::Result<()> {
+    if options.lenient() {
+        log::debug!(
+            "Value of field {} in record {} is replace by null: {}",
+            field,
+            record_position(record),
+            reason
+        );
+        column.push_null()?;
         Ok(())
+    } else {
+        Err(error::Error::InvalidValue(
+            field.to_string(),
+            record_position(record),
+            reason,
+        ))
     }
+}
 
-    ///Convert Name2Data in vector of arrow2 array
-    pub fn into_arc(
-        mut self,
-        schema: &arrow::datatypes::Schema,
-    ) -> Vec<std::sync::Arc<dyn arrow::array::Array>> {
-        schema
-            .fields
+/// Get INFO and FORMAT keys of record not declared in header, keys are prefix by section e.g. `INFO/KEY`
+pub fn undeclared_keys(
+    record: &noodles::vcf::Record,
+    header: &noodles::vcf::Header,
+) -> Vec<String> {
+    let info = record
+        .info()
+        .keys()
+        .filter(|key| !header.infos().contains_key(*key))
+        .map(|key| format!("INFO/{key}"));
+
+    let format = record
+        .genotypes()
+        .keys()
+        .iter()
+        .filter(|key| !header.formats().contains_key(*key))
+        .map(|key| format!("FORMAT/{key}"));
+
+    info.chain(format).collect()
+}
+
+/// Get INFO fields of record not declared in header, value are convert in string
+pub fn undeclared_info(
+    record: &noodles::vcf::Record,
+    header: &noodles::vcf::Header,
+) -> Vec<(String, Option<String>)> {
+    record
+        .info()
+        .as_ref()
+        .iter()
+        .filter(|(key, _)| !header.infos().contains_key(*key))
+        .map(|(key, value)| (key.to_string(), value.as_ref().map(|v| v.to_string())))
+        .collect()
+}
+
+/// Get FORMAT fields of a sample of record not declared in header, value are convert in string
+pub fn undeclared_format(
+    record: &noodles::vcf::Record,
+    header: &noodles::vcf::Header,
+    sample: usize,
+) -> Vec<(String, Option<String>)> {
+    match record.genotypes().get_index(sample) {
+        Some(values) => values
+            .keys()
             .iter()
-            .map(|x| self.0.remove(x.name()).unwrap().into_arc())
-            .collect()
+            .zip(values.values())
+            .filter(|(key, _)| !header.formats().contains_key(*key))
+            .map(|(key, value)| (key.to_string(), value.as_ref().map(|v| v.to_string())))
+            .collect(),
+        None => Vec::new(),
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::schema;
     use arrow::array::ArrayBuilder;
 
     use super::*;
@@ -800,15 +1228,52 @@ NC_016845.1	1273217582	.	A	cA	185	Filter_1	info_Integer_1=1702504238;info_Intege
 ENA|LT795502|LT795502.1	566884162	.	t	c	22	.	info_Integer_1=-63306296;info_Integer_2=1391506844,-1503768112;info_Integer_A=340548256;info_Integer_R=-1286314818,288781403;info_Integer_G=-800469678,-1311787939,-793948174,1533475939,755254594;info_Integer_.=-1341990003;info_Float_1=-76.227356;info_Float_2=-54.977512,-39.39898;info_Float_A=-35.61332;info_Float_R=-70.32056,-42.79394;info_Float_G=67.78093,43.006317,92.26671,-48.16651,4.3726654;info_Float_.=-60.336803,-45.87288,92.96947,-43.244385;info_Character_1=[;info_Character_2=c,J;info_Character_A=R;info_Character_R=o,d;info_Character_G=h,`,F,\\,q;info_Character_.=T;info_String_1=q^HZe_mW_C;info_String_2=FPSDvSVXAd,YbrjDSdRXm;info_String_A=IxDTHZYoq[;info_String_R=OsOWlbXzO\\,hAhG_b\\Ifw;info_String_G=jb^GYiHZRT,_[`_aqmUIf,PtWWNPUINQ,WkqQaaxSee,jRMUC_IYwu;info_String_.=ZVqn\\yRJEI,`vlpPiWkLZ,aVHocDfVJv	GT:format_Integer_1:format_Integer_2:format_Integer_A:format_Integer_R:format_Integer_G:format_Integer_.:format_Float_1:format_Float_2:format_Float_A:format_Float_R:format_Float_G:format_Float_.:format_Character_1:format_Character_2:format_Character_A:format_Character_R:format_Character_G:format_Character_.:format_String_1:format_String_2:format_String_A:format_String_R:format_String_G:format_String_.	0/1:389250658:-1173892904,-995837010:380428736:-350796083,-1946061625:-1985077526,-956832721,-2103216081,1213731248,-1361646347:212134446:-93.3871:73.895645,-82.49681:-59.703255:-53.21877,-11.0794525:98.62854,-40.406464,36.850067,-61.214233,28.269058:-80.0885,25.734207,92.746826,24.650955:Q:l,w:j:t,Z:K,X,a,E,S:i:VAXYF^LWPG:SudBRfeYRI,axYzALsh[m:gWvHMgghOt:cIIIEUOOnN,Q`yNRLvwIx:HeiQgtTGFY,A[RlKUJYGM,EDyo[bNg]Z,[DQbbRhs\\H,DNoj_HFJZ]:u[WuJ]OfAC,ToajkjZMqO	0/1:1247618239:1495558316,1270330192:-1812953658:2099386438,-1719636933:-1719318579,-2036965806,-1361738579,-438246128,154780382:-2087289599:99.94664:-24.0057,11.140228:-54.74951:41.22667,-17.469597:62.76808,-47.069477,-82.23286,97.09668,34.973145:-90.37955,-0.9262085,87.107376,69.280334:y:M,T:Z:Z,`:K,y,f,N,c:s:DmKvSoUTTo:zjlBpBcCYU,wEhOIx\\sXm:YYmcVdAtGt:UlifazYxMd,snMxUXwcD^:AHixNQliMD,JdpvgRsGQe,fPSRoIIRVL,AHM[ETIVla,GXPIPLRtqa:pvvKB`NrwP,lTl[KlJinZ	0/1:-1942376961:1024730712,39811746:1702586628:1394978346,832590142:1113681009,1611955235,-169392027,706295232,-1382855589:1043164434,456932470,-1198813064:74.17302:79.9146,-59.669567:-14.853073:-39.896774,-26.528046:71.47615,-73.95854,14.603233,-59.66177,-35.773087:8.042595,-81.39966:i:\\,`:s:Y,S:f,y,L,s,e:W,a,s:vLy\\C]]Bkb:cU[]e_icry,peVDqFyCOL:pmMspaUUXk:uIhBoRPWTP,^jGL[\\`Ei]:CFKAibZSAV,]jyZA_dhSu,UVV]AtIjJu,Than`WdhfE,GzqGzeGtmq:vj\\WHa^Crz,lzLRWOruNj,[V\\LWB]XnM
 ";
 
+    #[test]
+    fn plan() {
+        let mut reader = noodles::vcf::Reader::new(VCF_FILE);
+
+        let header: noodles::vcf::Header = reader.read_header().unwrap();
+        let options = options::Options::new()
+            .set_samples(vec!["sample_1".to_string()])
+            .set_undeclared(options::Undeclared::Extra);
+        let schema = schema::from_header_with_options(&header, &options).unwrap();
+
+        let plan = Plan::new(&header, &schema, &options);
+
+        assert_eq!(plan.core.len(), 7);
+        assert_eq!(plan.infos.len(), header.infos().len());
+        assert_eq!(plan.infos[0], Some(7));
+        assert_eq!(
+            plan.info_extra,
+            plan.index(&schema::info_extra_column(&options))
+        );
+        assert!(plan
+            .formats
+            .iter()
+            .all(|f| f[0].is_none() && f[1].is_some()));
+        assert_eq!(
+            plan.formats[0][1],
+            plan.index(&schema::format_column("sample_1", "GT", &options))
+        );
+        assert_eq!(plan.format_extras[0], None);
+        assert!(plan.format_extras[1].is_some());
+        assert_eq!(plan.names.len(), schema.fields().len());
+    }
+
     #[test]
     fn init() {
         let mut reader = noodles::vcf::Reader::new(VCF_FILE);
 
         let header: noodles::vcf::Header = reader.read_header().unwrap();
-        let schema = schema::from_header(&header, false).unwrap();
+        let schema = schema::from_header_with_options(&header, &options::Options::new()).unwrap();
+
+        let plan = std::sync::Arc::new(Plan::new(&header, &schema, &options::Options::new()));
 
-        let mut data = Name2Data::new(10, &schema);
-        let mut col_names = data.0.keys().cloned().collect::<Vec<String>>();
+        let mut data = Name2Data::new(10, &schema, plan).unwrap();
+        assert_eq!(data.index("chromosome"), Some(0));
+        assert_eq!(data.index("filter"), Some(6));
+
+        let mut col_names = data.plan.index.keys().cloned().collect::<Vec<String>>();
         col_names.sort();
 
         assert_eq!(
@@ -914,19 +1379,16 @@ ENA|LT795502|LT795502.1	566884162	.	t	c	22	.	info_Integer_1=-63306296;info_Integ
 
         let header: noodles::vcf::Header = reader.read_header().unwrap();
 
-        let schema = schema::from_header(&header, false).unwrap();
-        let schema_map: rustc_hash::FxHashMap<String, Field> = schema
-            .flattened_fields()
-            .into_iter()
-            .map(|f| (f.name().to_string(), f.clone()))
-            .collect::<rustc_hash::FxHashMap<String, Field>>();
+        let schema = schema::from_header_with_options(&header, &options::Options::new()).unwrap();
+        let plan = std::sync::Arc::new(Plan::new(&header, &schema, &options::Options::new()));
 
-        let mut data = Name2Data::new(10, &schema);
+        let mut data = Name2Data::new(10, &schema, plan.clone()).unwrap();
 
         let mut iterator = reader.records(&header);
         let record = iterator.next().unwrap().unwrap();
 
-        data.add_record(record, &header, &schema_map).unwrap();
+        data.add_record(record, &header, &options::Options::new())
+            .unwrap();
         match data.get("chromosome") {
             Some(ColumnData::String(a)) => {
                 assert_eq!(a.len(), 1);
@@ -1018,4 +1480,286 @@ ENA|LT795502|LT795502.1	566884162	.	t	c	22	.	info_Integer_1=-63306296;info_Integ
             _ => panic!("Column info_info_Integer_R does not match type"),
         }
     }
+
+    #[test]
+    fn undeclared() {
+        let vcf = b"##fileformat=VCFv4.3
+##INFO=<ID=DP,Number=1,Type=Integer,Description=\"depth\">
+##FORMAT=<ID=GT,Number=1,Type=String,Description=\"genotype\">
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tfirst
+1\t1\t.\tA\tC\t.\t.\tDP=1;UND=a,b;FL\tGT:XX\t0/1:3
+";
+        let mut reader = noodles::vcf::Reader::new(&vcf[..]);
+        let header: noodles::vcf::Header = reader.read_header().unwrap();
+        let record = reader.records(&header).next().unwrap().unwrap();
+
+        assert_eq!(
+            undeclared_keys(&record, &header),
+            vec![
+                "INFO/UND".to_string(),
+                "INFO/FL".to_string(),
+                "FORMAT/XX".to_string()
+            ]
+        );
+        assert_eq!(
+            undeclared_info(&record, &header),
+            vec![
+                ("UND".to_string(), Some("a,b".to_string())),
+                ("FL".to_string(), Some(String::new()))
+            ]
+        );
+        assert_eq!(
+            undeclared_format(&record, &header, 0),
+            vec![("XX".to_string(), Some("3".to_string()))]
+        );
+
+        let options = options::Options::new().set_undeclared(options::Undeclared::Extra);
+        let schema = schema::from_header_with_options(&header, &options).unwrap();
+        let plan = std::sync::Arc::new(Plan::new(&header, &schema, &options));
+
+        let mut data = Name2Data::new(1, &schema, plan.clone()).unwrap();
+        data.add_record(record, &header, &options).unwrap();
+
+        match data.get_mut("info_extra") {
+            Some(ColumnData::Map(a)) => {
+                assert_eq!(a.len(), 1);
+                assert_eq!(a.keys().values_slice(), b"UNDFL");
+                assert_eq!(a.values().values_slice(), b"a,b");
+            }
+            _ => panic!("Column info_extra does not match type"),
+        }
+        match data.get_mut("format_first_extra") {
+            Some(ColumnData::Map(a)) => {
+                assert_eq!(a.keys().values_slice(), b"XX");
+                assert_eq!(a.values().values_slice(), b"3");
+            }
+            _ => panic!("Column format_first_extra does not match type"),
+        }
+    }
+
+    #[test]
+    fn invalid_value() {
+        let vcf = b"##fileformat=VCFv4.3
+##INFO=<ID=XA,Number=A,Type=Integer,Description=\"by alternative\">
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+1\t1\t.\tA\tC,G\t.\t.\tXA=1,1000
+";
+        let mut reader = noodles::vcf::Reader::new(&vcf[..]);
+        let header: noodles::vcf::Header = reader.read_header().unwrap();
+        let record = reader.records(&header).next().unwrap().unwrap();
+
+        // 1000 doesn't fit in int8
+        let options = options::Options::new().set_type_hints(vec!["INFO/XA=int8".parse().unwrap()]);
+        let schema = schema::from_header_with_options(&header, &options).unwrap();
+        let plan = std::sync::Arc::new(Plan::new(&header, &schema, &options));
+
+        let mut data = Name2Data::new(2, &schema, plan.clone()).unwrap();
+        match data.add_record(record.clone(), &header, &options) {
+            Err(error::Error::InvalidValue(field, position, _)) => {
+                assert_eq!(field, "info_XA");
+                assert_eq!(position, "1:1");
+            }
+            _ => panic!("Invalid value must return an error"),
+        }
+
+        let options = options.set_lenient(true);
+        let mut data = Name2Data::new(2, &schema, plan.clone()).unwrap();
+        assert_eq!(data.add_record(record, &header, &options).unwrap(), 1);
+        match data.get("info_XA") {
+            Some(ColumnData::Int8(a)) => {
+                assert_eq!(a.len(), 2);
+                assert_eq!(a.validity_slice(), Some(&[0b01][..]));
+            }
+            _ => panic!("Column info_XA does not match type"),
+        }
+    }
+
+    #[test]
+    fn short_array() {
+        let vcf = b"##fileformat=VCFv4.3
+##INFO=<ID=XA,Number=A,Type=Integer,Description=\"by alternative\">
+##INFO=<ID=XS,Number=A,Type=String,Description=\"by alternative\">
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+1\t1\t.\tA\tC,G\t.\t.\tXA=1;XS=a,.
+";
+        let mut reader = noodles::vcf::Reader::new(&vcf[..]);
+        let header: noodles::vcf::Header = reader.read_header().unwrap();
+        let record = reader.records(&header).next().unwrap().unwrap();
+
+        // too short array and missing value aren't an error in default mode
+        let options = options::Options::new();
+        let schema = schema::from_header_with_options(&header, &options).unwrap();
+        let plan = std::sync::Arc::new(Plan::new(&header, &schema, &options));
+
+        let mut data = Name2Data::new(2, &schema, plan).unwrap();
+        assert_eq!(data.add_record(record, &header, &options).unwrap(), 0);
+
+        match data.get("info_XA") {
+            Some(ColumnData::Int(a)) => {
+                assert_eq!(a.values_slice()[0], 1);
+                assert_eq!(a.validity_slice(), Some(&[0b01][..]));
+            }
+            _ => panic!("Column info_XA does not match type"),
+        }
+        match data.get("info_XS") {
+            Some(ColumnData::String(a)) => {
+                assert_eq!(a.values_slice(), b"a");
+                assert_eq!(a.offsets_slice(), &[0, 1, 1]);
+                assert_eq!(a.validity_slice(), None);
+            }
+            _ => panic!("Column info_XS does not match type"),
+        }
+    }
+
+    #[test]
+    fn call() {
+        use noodles::vcf::record::genotypes::sample::value::genotype::allele::Phasing;
+        use noodles::vcf::record::genotypes::sample::value::Genotype;
+        use noodles::vcf::record::genotypes::sample::Value;
+
+        let mut call = Call::default();
+        let mut buffer = String::new();
+
+        // same result as noodles parser
+        for text in [
+            "0/1", "1|2", "./.", "1", ".", "|0/1", "/1|0", "0|1/2", "2/2/1", "0/a", "", "0//1",
+            "0/", "|",
+        ] {
+            call.parse(Some(&Value::String(text.to_string())));
+
+            match text.parse::<Genotype>() {
+                Ok(gt) => {
+                    assert_eq!(
+                        call.alleles().unwrap().collect::<Vec<_>>(),
+                        gt.iter().map(|a| a.position()).collect::<Vec<_>>(),
+                        "{}",
+                        text
+                    );
+                    assert_eq!(
+                        call.phasings,
+                        gt.iter()
+                            .map(|a| match a.phasing() {
+                                Phasing::Phased => '|',
+                                Phasing::Unphased => '/',
+                            })
+                            .collect::<Vec<_>>(),
+                        "{}",
+                        text
+                    );
+                }
+                Err(_) => assert!(call.alleles().is_none(), "{}", text),
+            }
+        }
+
+        call.parse(Some(&Value::String("1|2".to_string())));
+        assert!(call.write(0, &mut buffer));
+        assert_eq!(buffer, "1|.");
+        assert!(call.write(1, &mut buffer));
+        assert_eq!(buffer, ".|1");
+
+        call.parse(Some(&Value::String("0/1|2".to_string())));
+        assert!(call.write(1, &mut buffer));
+        assert_eq!(buffer, "0/./1");
+
+        call.parse(None);
+        assert!(!call.write(0, &mut buffer));
+        assert!(call.alleles().is_none());
+    }
+
+    #[test]
+    fn select_allele() {
+        let values = vec![Some(0), Some(1), Some(2), Some(3), Some(4), Some(5)];
+
+        assert!(matches!(
+            select(
+                &values,
+                i32::clone,
+                noodles::vcf::header::Number::A,
+                1,
+                3,
+                None,
+                None
+            ),
+            Ok(Selection::One(Some(1)))
+        ));
+        assert!(select(
+            &values,
+            i32::clone,
+            noodles::vcf::header::Number::Count(1),
+            0,
+            3,
+            None,
+            None
+        )
+        .is_err());
+        match select(
+            &values,
+            i32::clone,
+            noodles::vcf::header::Number::G,
+            1,
+            3,
+            None,
+            None,
+        ) {
+            Ok(Selection::Many(v)) => assert_eq!(
+                v.values().collect::<Vec<_>>(),
+                vec![Some(0), Some(3), Some(5)]
+            ),
+            _ => panic!("Number=G selection failled"),
+        }
+        match select(
+            &values[..3],
+            i32::clone,
+            noodles::vcf::header::Number::G,
+            1,
+            3,
+            Some(-1),
+            None,
+        ) {
+            Ok(Selection::Many(v)) => assert_eq!(
+                v.values().collect::<Vec<_>>(),
+                vec![Some(0), Some(-1), Some(2)]
+            ),
+            _ => panic!("Haploid Number=G selection failled"),
+        }
+        assert!(matches!(
+            select(
+                &values[..4],
+                i32::clone,
+                noodles::vcf::header::Number::G,
+                1,
+                3,
+                None,
+                None
+            ),
+            Ok(Selection::Null)
+        ));
+        // too short array, absent value is missing
+        match select(
+            &values[..1],
+            i32::clone,
+            noodles::vcf::header::Number::R,
+            1,
+            3,
+            None,
+            None,
+        ) {
+            Ok(Selection::Many(v)) => {
+                assert_eq!(v.values().collect::<Vec<_>>(), vec![Some(0), None])
+            }
+            _ => panic!("Number=R selection failled"),
+        }
+        assert!(matches!(
+            select(
+                &values[..1],
+                i32::clone,
+                noodles::vcf::header::Number::A,
+                1,
+                3,
+                None,
+                Some(-1)
+            ),
+            Ok(Selection::One(Some(-1)))
+        ));
+    }
 }
@@ -0,0 +1,830 @@
+//! Options that control how a vcf is converted
+
+/* std use */
+
+/* crate use */
+
+/* project use */
+use crate::error;
+use crate::overrides;
+use crate::reference;
+use crate::stats;
+
+/// Arrow type used to store a numeric vcf field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Width {
+    /// 8 bits integer
+    Int8,
+    /// 16 bits integer
+    Int16,
+    /// 32 bits integer
+    Int32,
+    /// 16 bits float
+    Float16,
+    /// 32 bits float
+    Float32,
+    /// 64 bits float
+    Float64,
+}
+
+impl Width {
+    /// Get arrow type corresponding to width
+    pub fn arrow_type(&self) -> arrow::datatypes::DataType {
+        match self {
+            Width::Int8 => arrow::datatypes::DataType::Int8,
+            Width::Int16 => arrow::datatypes::DataType::Int16,
+            Width::Int32 => arrow::datatypes::DataType::Int32,
+            Width::Float16 => arrow::datatypes::DataType::Float16,
+            Width::Float32 => arrow::datatypes::DataType::Float32,
+            Width::Float64 => arrow::datatypes::DataType::Float64,
+        }
+    }
+
+    /// Return true if width is an integer type
+    pub fn is_integer(&self) -> bool {
+        matches!(self, Width::Int8 | Width::Int16 | Width::Int32)
+    }
+}
+
+impl std::str::FromStr for Width {
+    type Err = error::Error;
+
+    fn from_str(s: &str) -> error::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "int8" => Ok(Width::Int8),
+            "int16" => Ok(Width::Int16),
+            "int32" => Ok(Width::Int32),
+            "float16" => Ok(Width::Float16),
+            "float32" => Ok(Width::Float32),
+            "float64" => Ok(Width::Float64),
+            _ => Err(error::Error::TypeHintParse(s.to_string())),
+        }
+    }
+}
+
+/// Section of vcf where a field is define
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Section {
+    /// INFO field
+    Info,
+    /// FORMAT field
+    Format,
+}
+
+/// Associate a vcf field to an arrow type width, parsed from `INFO/DP=int16` or `FORMAT/AD=int8`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeHint {
+    section: Section,
+    key: String,
+    width: Width,
+}
+
+impl TypeHint {
+    /// Create a new TypeHint
+    pub fn new(section: Section, key: String, width: Width) -> Self {
+        Self {
+            section,
+            key,
+            width,
+        }
+    }
+
+    /// Get section
+    pub fn section(&self) -> Section {
+        self.section
+    }
+
+    /// Get key
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Get width
+    pub fn width(&self) -> Width {
+        self.width
+    }
+}
+
+impl std::str::FromStr for TypeHint {
+    type Err = error::Error;
+
+    fn from_str(s: &str) -> error::Result<Self> {
+        let (field, width) = s
+            .split_once('=')
+            .ok_or_else(|| error::Error::TypeHintParse(s.to_string()))?;
+        let (section, key) = field
+            .split_once('/')
+            .ok_or_else(|| error::Error::TypeHintParse(s.to_string()))?;
+
+        let section = match section.to_uppercase().as_str() {
+            "INFO" => Section::Info,
+            "FORMAT" => Section::Format,
+            _ => return Err(error::Error::TypeHintParse(s.to_string())),
+        };
+
+        Ok(Self::new(section, key.to_string(), width.parse()?))
+    }
+}
+
+/// Policy apply on INFO and FORMAT keys present in records but not declared in header
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Undeclared {
+    /// Conversion failled
+    Error,
+    /// Value is drop, a warning is log the first time a key is found
+    #[default]
+    Warn,
+    /// Value is store as string in a map column `info_extra` or `format_{sample}_extra`
+    Extra,
+}
+
+impl std::str::FromStr for Undeclared {
+    type Err = error::Error;
+
+    fn from_str(s: &str) -> error::Result<Self> {
+        match s.to_lowercase().as_str() {
+            "error" => Ok(Undeclared::Error),
+            "warn" => Ok(Undeclared::Warn),
+            "extra" => Ok(Undeclared::Extra),
+            _ => Err(error::Error::UndeclaredParse(s.to_string())),
+        }
+    }
+}
+
+/// Genomic region used to select records, parsed from `chr`, `chr:start-` or `chr:start-end`, positions are 1-based and inclusive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    name: String,
+    start: usize,
+    end: usize,
+}
+
+impl Region {
+    /// Create a new Region
+    pub fn new(name: String, start: usize, end: usize) -> Self {
+        Self { name, start, end }
+    }
+
+    /// Get name of chromosome
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get first position
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Get last position
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    /// Return true if position on chromosome is in region
+    pub fn contains(&self, chromosome: &str, position: usize) -> bool {
+        self.name == chromosome && self.start <= position && position <= self.end
+    }
+}
+
+impl std::str::FromStr for Region {
+    type Err = error::Error;
+
+    fn from_str(s: &str) -> error::Result<Self> {
+        let error = || error::Error::RegionParse(s.to_string());
+
+        let (name, interval) = match s.rsplit_once(':') {
+            Some((name, interval)) => (name, Some(interval)),
+            None => (s, None),
+        };
+        if name.is_empty() {
+            return Err(error());
+        }
+
+        let (start, end) = match interval {
+            None => (1, usize::MAX),
+            Some(interval) => {
+                let (start, end) = interval.split_once('-').unwrap_or((interval, interval));
+                let start = start.replace(',', "").parse().map_err(|_| error())?;
+                let end = match end {
+                    "" => usize::MAX,
+                    end => end.replace(',', "").parse().map_err(|_| error())?,
+                };
+                (start, end)
+            }
+        };
+
+        if start == 0 || start > end {
+            return Err(error());
+        }
+
+        Ok(Self::new(name.to_string(), start, end))
+    }
+}
+
+/// Policy used to build column names from INFO and FORMAT keys and sample names
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Naming {
+    info_template: String,
+    format_template: String,
+    sanitize: bool,
+    lowercase: bool,
+}
+
+impl Default for Naming {
+    fn default() -> Self {
+        Self {
+            info_template: "info_{key}".to_string(),
+            format_template: "format_{sample}_{key}".to_string(),
+            sanitize: false,
+            lowercase: false,
+        }
+    }
+}
+
+impl Naming {
+    /// Create Naming with default value, `info_{key}` and `format_{sample}_{key}` without sanitization
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set template of INFO column name, `{key}` is replace by key
+    pub fn set_info_template(mut self, value: String) -> Self {
+        self.info_template = value;
+        self
+    }
+
+    /// Set template of FORMAT column name, `{sample}` and `{key}` are replace by sample name and key
+    pub fn set_format_template(mut self, value: String) -> Self {
+        self.format_template = value;
+        self
+    }
+
+    /// Set if characters other than `[A-Za-z0-9_]` are replace by `_`
+    pub fn set_sanitize(mut self, value: bool) -> Self {
+        self.sanitize = value;
+        self
+    }
+
+    /// Set if column names are write in lowercase
+    pub fn set_lowercase(mut self, value: bool) -> Self {
+        self.lowercase = value;
+        self
+    }
+
+    /// Get template of INFO column name
+    pub fn info_template(&self) -> &str {
+        &self.info_template
+    }
+
+    /// Get template of FORMAT column name
+    pub fn format_template(&self) -> &str {
+        &self.format_template
+    }
+
+    /// Get sanitize
+    pub fn sanitize(&self) -> bool {
+        self.sanitize
+    }
+
+    /// Get lowercase
+    pub fn lowercase(&self) -> bool {
+        self.lowercase
+    }
+
+    /// Return true if naming is the default one
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    /// Check templates contains required placeholders
+    pub fn check(&self) -> error::Result<()> {
+        if !self.info_template.contains("{key}") {
+            return Err(error::Error::NamingTemplate(self.info_template.clone()));
+        }
+
+        if !self.format_template.contains("{key}") || !self.format_template.contains("{sample}") {
+            return Err(error::Error::NamingTemplate(self.format_template.clone()));
+        }
+
+        Ok(())
+    }
+
+    /// Build name of INFO column
+    pub fn info(&self, key: &str) -> String {
+        self.apply(self.info_template.replace("{key}", key))
+    }
+
+    /// Build name of FORMAT column
+    pub fn format(&self, sample: &str, key: &str) -> String {
+        self.apply(
+            self.format_template
+                .replace("{sample}", sample)
+                .replace("{key}", key),
+        )
+    }
+
+    fn apply(&self, mut name: String) -> String {
+        if self.sanitize {
+            name = name
+                .chars()
+                .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+                .collect();
+
+            if name.starts_with(|c: char| c.is_ascii_digit()) {
+                name.insert(0, '_');
+            }
+        }
+
+        if self.lowercase {
+            name = name.to_lowercase();
+        }
+
+        name
+    }
+}
+
+/// Options that control how a vcf is converted
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    info_optional: bool,
+    dictionary: Vec<String>,
+    type_hints: Vec<TypeHint>,
+    info_float64: bool,
+    overrides: overrides::Overrides,
+    undeclared: Undeclared,
+    lenient: bool,
+    columns: Vec<String>,
+    samples: Vec<String>,
+    regions: Vec<Region>,
+    progress: Option<stats::Progress>,
+    naming: Naming,
+    memory_budget: Option<usize>,
+    variant_id: bool,
+    variant_hash: bool,
+    normalize: bool,
+    reference: Option<std::sync::Arc<reference::Reference>>,
+    classify: bool,
+    sv: bool,
+    context: usize,
+    check_reference: bool,
+    fill_reference: bool,
+    genotype_stats: bool,
+    sample_qc: bool,
+}
+
+impl Options {
+    /// Create Options with default value
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set if all information fields are optional
+    pub fn set_info_optional(mut self, value: bool) -> Self {
+        self.info_optional = value;
+        self
+    }
+
+    /// Set name of columns stored as dictionary of string
+    pub fn set_dictionary(mut self, value: Vec<String>) -> Self {
+        self.dictionary = value;
+        self
+    }
+
+    /// Set arrow type width of some fields, last hint of a field win
+    pub fn set_type_hints(mut self, value: Vec<TypeHint>) -> Self {
+        self.type_hints = value;
+        self
+    }
+
+    /// Set if info float are store in 64 bits
+    pub fn set_info_float64(mut self, value: bool) -> Self {
+        self.info_float64 = value;
+        self
+    }
+
+    /// Set overrides of header definition
+    pub fn set_overrides(mut self, value: overrides::Overrides) -> Self {
+        self.overrides = value;
+        self
+    }
+
+    /// Set policy apply on keys not declared in header
+    pub fn set_undeclared(mut self, value: Undeclared) -> Self {
+        self.undeclared = value;
+        self
+    }
+
+    /// Set if value that can't be convert are replace by null instead of return an error
+    pub fn set_lenient(mut self, value: bool) -> Self {
+        self.lenient = value;
+        self
+    }
+
+    /// Set name of columns keep in output, empty keep all columns
+    pub fn set_columns(mut self, value: Vec<String>) -> Self {
+        self.columns = value;
+        self
+    }
+
+    /// Set name of samples keep in output, empty keep all samples
+    pub fn set_samples(mut self, value: Vec<String>) -> Self {
+        self.samples = value;
+        self
+    }
+
+    /// Set regions of records keep in output, empty keep all records
+    pub fn set_regions(mut self, value: Vec<Region>) -> Self {
+        self.regions = value;
+        self
+    }
+
+    /// Set counter of bytes read in input, used to report progress
+    pub fn set_progress(mut self, value: Option<stats::Progress>) -> Self {
+        self.progress = value;
+        self
+    }
+
+    /// Set policy used to build column names
+    pub fn set_naming(mut self, value: Naming) -> Self {
+        self.naming = value;
+        self
+    }
+
+    /// Set memory budget of column builders in bytes
+    pub fn set_memory_budget(mut self, value: Option<usize>) -> Self {
+        self.memory_budget = value;
+        self
+    }
+
+    /// Set if column variant_id is add
+    pub fn set_variant_id(mut self, value: bool) -> Self {
+        self.variant_id = value;
+        self
+    }
+
+    /// Set if column variant_hash is add
+    pub fn set_variant_hash(mut self, value: bool) -> Self {
+        self.variant_hash = value;
+        self
+    }
+
+    /// Set if alleles are normalized before computation of variant_id and variant_hash
+    pub fn set_normalize(mut self, value: bool) -> Self {
+        self.normalize = value;
+        self
+    }
+
+    /// Set reference used to left align indels, imply normalization
+    pub fn set_reference(mut self, value: Option<std::sync::Arc<reference::Reference>>) -> Self {
+        self.reference = value;
+        self
+    }
+
+    /// Set if variant classification columns are add
+    pub fn set_classify(mut self, value: bool) -> Self {
+        self.classify = value;
+        self
+    }
+
+    /// Set if symbolic alleles and breakends are parsed in structural variant columns
+    pub fn set_sv(mut self, value: bool) -> Self {
+        self.sv = value;
+        self
+    }
+
+    /// Set number of reference bases on each side of REF write in context column, 0 disable column
+    pub fn set_context(mut self, value: usize) -> Self {
+        self.context = value;
+        self
+    }
+
+    /// Set if REF is compare to reference in reference_match column
+    pub fn set_check_reference(mut self, value: bool) -> Self {
+        self.check_reference = value;
+        self
+    }
+
+    /// Set if REF made only of N is replace by reference bases
+    pub fn set_fill_reference(mut self, value: bool) -> Self {
+        self.fill_reference = value;
+        self
+    }
+
+    /// Set if aggregate genotype statistics columns are add
+    pub fn set_genotype_stats(mut self, value: bool) -> Self {
+        self.genotype_stats = value;
+        self
+    }
+
+    /// Set if per sample quality control summary is accumulated during conversion
+    pub fn set_sample_qc(mut self, value: bool) -> Self {
+        self.sample_qc = value;
+        self
+    }
+
+    /// Get info optional
+    pub fn info_optional(&self) -> bool {
+        self.info_optional
+    }
+
+    /// Get name of columns stored as dictionary of string
+    pub fn dictionary(&self) -> &[String] {
+        &self.dictionary
+    }
+
+    /// Get type hints
+    pub fn type_hints(&self) -> &[TypeHint] {
+        &self.type_hints
+    }
+
+    /// Get width hint of an info field
+    pub fn info_width(&self, key: &str) -> Option<Width> {
+        self.width(Section::Info, key)
+    }
+
+    /// Get width hint of a format field
+    pub fn format_width(&self, key: &str) -> Option<Width> {
+        self.width(Section::Format, key)
+    }
+
+    /// Get if info float are store in 64 bits
+    pub fn info_float64(&self) -> bool {
+        self.info_float64
+    }
+
+    /// Get overrides of header definition
+    pub fn overrides(&self) -> &overrides::Overrides {
+        &self.overrides
+    }
+
+    /// Get policy apply on keys not declared in header
+    pub fn undeclared(&self) -> Undeclared {
+        self.undeclared
+    }
+
+    /// Get if value that can't be convert are replace by null
+    pub fn lenient(&self) -> bool {
+        self.lenient
+    }
+
+    /// Get name of columns keep in output
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    /// Get name of samples keep in output
+    pub fn samples(&self) -> &[String] {
+        &self.samples
+    }
+
+    /// Get regions of records keep in output
+    pub fn regions(&self) -> &[Region] {
+        &self.regions
+    }
+
+    /// Get policy used to build column names
+    pub fn naming(&self) -> &Naming {
+        &self.naming
+    }
+
+    /// Get memory budget of column builders in bytes
+    pub fn memory_budget(&self) -> Option<usize> {
+        self.memory_budget
+    }
+
+    /// Get if column variant_id is add
+    pub fn variant_id(&self) -> bool {
+        self.variant_id
+    }
+
+    /// Get if column variant_hash is add
+    pub fn variant_hash(&self) -> bool {
+        self.variant_hash
+    }
+
+    /// Get if alleles are normalized, always true if a reference is set
+    pub fn normalize(&self) -> bool {
+        self.normalize || self.reference.is_some()
+    }
+
+    /// Get reference used to left align indels
+    pub fn reference(&self) -> Option<&reference::Reference> {
+        self.reference.as_deref()
+    }
+
+    /// Get if variant classification columns are add
+    pub fn classify(&self) -> bool {
+        self.classify
+    }
+
+    /// Get if symbolic alleles and breakends are parsed in structural variant columns
+    pub fn sv(&self) -> bool {
+        self.sv
+    }
+
+    /// Get number of reference bases on each side of REF write in context column
+    pub fn context(&self) -> usize {
+        self.context
+    }
+
+    /// Get if REF is compare to reference
+    pub fn check_reference(&self) -> bool {
+        self.check_reference
+    }
+
+    /// Get if REF made only of N is replace by reference bases
+    pub fn fill_reference(&self) -> bool {
+        self.fill_reference
+    }
+
+    /// Get if aggregate genotype statistics columns are add
+    pub fn genotype_stats(&self) -> bool {
+        self.genotype_stats
+    }
+
+    /// Get if per sample quality control summary is accumulated during conversion
+    pub fn sample_qc(&self) -> bool {
+        self.sample_qc
+    }
+
+    /// Get counter of bytes read in input
+    pub fn progress(&self) -> Option<&stats::Progress> {
+        self.progress.as_ref()
+    }
+
+    /// Return true if sample is keep in output
+    pub fn keep_sample(&self, sample: &str) -> bool {
+        self.samples.is_empty() || self.samples.iter().any(|s| s == sample)
+    }
+
+    /// Return true if record at position on chromosome is keep in output
+    pub fn keep_record(&self, chromosome: &str, position: usize) -> bool {
+        self.regions.is_empty()
+            || self
+                .regions
+                .iter()
+                .any(|r| r.contains(chromosome, position))
+    }
+
+    fn width(&self, section: Section, key: &str) -> Option<Width> {
+        self.type_hints
+            .iter()
+            .rev()
+            .find(|h| h.section == section && h.key == key)
+            .map(|h| h.width)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_value() {
+        let options = Options::new();
+
+        assert!(!options.info_optional());
+        assert!(options.dictionary().is_empty());
+        assert!(options.type_hints().is_empty());
+        assert!(!options.info_float64());
+        assert!(options.overrides().is_empty());
+        assert_eq!(options.undeclared(), Undeclared::Warn);
+        assert!(!options.lenient());
+        assert!(options.columns().is_empty());
+        assert!(options.samples().is_empty());
+        assert!(options.regions().is_empty());
+        assert_eq!(options.memory_budget(), None);
+        assert!(!options.variant_id());
+        assert!(!options.variant_hash());
+        assert!(!options.normalize());
+        assert!(options.reference().is_none());
+        assert!(!options.classify());
+        assert!(!options.sv());
+        assert_eq!(options.context(), 0);
+        assert!(!options.check_reference());
+        assert!(!options.fill_reference());
+        assert!(!options.genotype_stats());
+        assert!(!options.sample_qc());
+        assert!(options.keep_sample("first"));
+        assert!(options.keep_record("1", 10));
+    }
+
+    #[test]
+    fn set_value() {
+        let options = Options::new()
+            .set_info_optional(true)
+            .set_dictionary(vec!["chromosome".to_string()])
+            .set_undeclared("Extra".parse().unwrap())
+            .set_memory_budget(Some(1 << 20))
+            .set_variant_id(true)
+            .set_variant_hash(true)
+            .set_normalize(true)
+            .set_classify(true)
+            .set_sv(true)
+            .set_context(1)
+            .set_check_reference(true)
+            .set_fill_reference(true)
+            .set_genotype_stats(true)
+            .set_sample_qc(true);
+
+        assert!(options.info_optional());
+        assert_eq!(options.memory_budget(), Some(1 << 20));
+        assert!(options.variant_id());
+        assert!(options.variant_hash());
+        assert!(options.normalize());
+        assert!(options.classify());
+        assert!(options.sv());
+        assert_eq!(options.context(), 1);
+        assert!(options.check_reference());
+        assert!(options.fill_reference());
+        assert!(options.genotype_stats());
+        assert!(options.sample_qc());
+        assert_eq!(options.undeclared(), Undeclared::Extra);
+        assert!("drop".parse::<Undeclared>().is_err());
+        assert_eq!(options.dictionary(), &["chromosome".to_string()]);
+    }
+
+    #[test]
+    fn type_hint() {
+        let options = Options::new().set_type_hints(vec![
+            "INFO/DP=int16".parse().unwrap(),
+            "format/AD=Int8".parse().unwrap(),
+            "INFO/DP=int8".parse().unwrap(),
+        ]);
+
+        assert_eq!(options.info_width("DP"), Some(Width::Int8));
+        assert_eq!(options.format_width("AD"), Some(Width::Int8));
+        assert_eq!(options.format_width("DP"), None);
+
+        assert!("DP=int8".parse::<TypeHint>().is_err());
+        assert!("INFO/DP".parse::<TypeHint>().is_err());
+        assert!("FILTER/DP=int8".parse::<TypeHint>().is_err());
+        assert!("INFO/DP=int64".parse::<TypeHint>().is_err());
+    }
+
+    #[test]
+    fn region() {
+        assert_eq!(
+            "chr1".parse::<Region>().unwrap(),
+            Region::new("chr1".to_string(), 1, usize::MAX)
+        );
+        assert_eq!(
+            "chr1:100-".parse::<Region>().unwrap(),
+            Region::new("chr1".to_string(), 100, usize::MAX)
+        );
+        assert_eq!(
+            "HLA-A*01:01:1,000-2,000".parse::<Region>().unwrap(),
+            Region::new("HLA-A*01:01".to_string(), 1000, 2000)
+        );
+        assert_eq!(
+            "chr1:10".parse::<Region>().unwrap(),
+            Region::new("chr1".to_string(), 10, 10)
+        );
+
+        assert!("".parse::<Region>().is_err());
+        assert!("chr1:0-10".parse::<Region>().is_err());
+        assert!("chr1:20-10".parse::<Region>().is_err());
+        assert!("chr1:a-10".parse::<Region>().is_err());
+
+        let options = Options::new()
+            .set_regions(vec!["1:10-20".parse().unwrap(), "2".parse().unwrap()])
+            .set_samples(vec!["first".to_string()]);
+
+        assert!(options.keep_record("1", 10));
+        assert!(options.keep_record("1", 20));
+        assert!(!options.keep_record("1", 21));
+        assert!(options.keep_record("2", 1_000_000));
+        assert!(!options.keep_record("3", 15));
+        assert!(options.keep_sample("first"));
+        assert!(!options.keep_sample("second"));
+    }
+
+    #[test]
+    fn naming() {
+        let naming = Naming::new();
+
+        assert!(naming.is_default());
+        assert!(naming.check().is_ok());
+        assert_eq!(naming.info("Info_."), "info_Info_.");
+        assert_eq!(naming.format("s-1", "AD"), "format_s-1_AD");
+
+        let naming = Naming::new()
+            .set_info_template("i.{key}".to_string())
+            .set_format_template("{sample}__{key}".to_string())
+            .set_sanitize(true)
+            .set_lowercase(true);
+
+        assert!(!naming.is_default());
+        assert_eq!(naming.info("Info_."), "i_info__");
+        assert_eq!(naming.format("1 s-1", "AD"), "_1_s_1__ad");
+
+        assert!(Naming::new()
+            .set_info_template("info".to_string())
+            .check()
+            .is_err());
+        assert!(Naming::new()
+            .set_format_template("{key}".to_string())
+            .check()
+            .is_err());
+    }
+}
@@ -0,0 +1,331 @@
+//! Override vcf header definition of INFO and FORMAT fields
+
+/* std use */
+
+/* crate use */
+
+/* project use */
+use crate::error;
+
+/// Override of one INFO or FORMAT field, each value not set keep header definition
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Override {
+    number: Option<String>,
+    #[serde(rename = "type")]
+    ty: Option<String>,
+    nullable: Option<bool>,
+    rename: Option<String>,
+    description: Option<String>,
+}
+
+impl Override {
+    /// Get number
+    pub fn number(&self) -> Option<&str> {
+        self.number.as_deref()
+    }
+
+    /// Get type
+    pub fn ty(&self) -> Option<&str> {
+        self.ty.as_deref()
+    }
+
+    /// Get nullable
+    pub fn nullable(&self) -> Option<bool> {
+        self.nullable
+    }
+
+    /// Get rename
+    pub fn rename(&self) -> Option<&str> {
+        self.rename.as_deref()
+    }
+
+    /// Get description
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+}
+
+/// Overrides of INFO and FORMAT fields read from a toml or json file
+///
+/// ```toml
+/// [info.DP]
+/// number = "1"
+/// type = "Integer"
+/// nullable = true
+/// rename = "depth"
+///
+/// [format.XD]
+/// number = "R"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Overrides {
+    #[serde(default)]
+    info: std::collections::BTreeMap<String, Override>,
+    #[serde(default)]
+    format: std::collections::BTreeMap<String, Override>,
+}
+
+impl Overrides {
+    /// Read overrides from a file, file with json extension are parsed as json other as toml
+    pub fn from_path<P>(path: P) -> error::Result<Self>
+    where
+        P: AsRef<std::path::Path>,
+    {
+        let content = std::fs::read_to_string(path.as_ref())?;
+
+        match path.as_ref().extension().and_then(|e| e.to_str()) {
+            Some("json") => Self::from_json(&content),
+            _ => Self::from_toml(&content),
+        }
+    }
+
+    /// Parse overrides from a toml string
+    pub fn from_toml(content: &str) -> error::Result<Self> {
+        toml::from_str(content)
+            .map_err(|e| error::Error::Override(e.to_string()))
+            .and_then(Self::check)
+    }
+
+    /// Parse overrides from a json string
+    pub fn from_json(content: &str) -> error::Result<Self> {
+        serde_json::from_str(content)
+            .map_err(|e| error::Error::Override(e.to_string()))
+            .and_then(Self::check)
+    }
+
+    /// Reject field set non nullable, a record without value would make conversion fail in the middle of file
+    fn check(self) -> error::Result<Self> {
+        let fields = self
+            .info
+            .iter()
+            .map(|(key, value)| ("info", key, value))
+            .chain(
+                self.format
+                    .iter()
+                    .map(|(key, value)| ("format", key, value)),
+            );
+
+        for (section, key, value) in fields {
+            if value.nullable() == Some(false) {
+                return Err(error::Error::Override(format!(
+                    "{section} {key} can't be non nullable, a missing value is store as null"
+                )));
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// Get override of an info field
+    pub fn info(&self, key: &str) -> Option<&Override> {
+        self.info.get(key)
+    }
+
+    /// Get override of a format field
+    pub fn format(&self, key: &str) -> Option<&Override> {
+        self.format.get(key)
+    }
+
+    /// Check if there is no override
+    pub fn is_empty(&self) -> bool {
+        self.info.is_empty() && self.format.is_empty()
+    }
+
+    /// Apply number, type and description override on header, field absent of header are added if number and type are set
+    pub fn apply(&self, header: &mut noodles::vcf::Header) -> error::Result<()> {
+        use noodles::vcf::header::record::value::map;
+
+        for (key, value) in self.info.iter() {
+            let id: noodles::vcf::record::info::field::Key = key
+                .parse()
+                .map_err(|_| error::Error::Override(format!("{key} isn't a valid info key")))?;
+            let number = value.number().map(parse_number).transpose()?;
+            let ty = value
+                .ty()
+                .map(|t| {
+                    t.parse::<map::info::Type>()
+                        .map_err(|_| error::Error::Override(format!("{t} isn't a valid info type")))
+                })
+                .transpose()?;
+
+            match header.infos_mut().get_mut(&id) {
+                Some(definition) => {
+                    if let Some(n) = number {
+                        *definition.number_mut() = n;
+                    }
+                    if let Some(t) = ty {
+                        *definition.type_mut() = t;
+                    }
+                    if let Some(d) = value.description() {
+                        *definition.description_mut() = d.to_string();
+                    }
+                }
+                None => {
+                    let (n, t) = number.zip(ty).ok_or_else(|| {
+                        error::Error::Override(format!(
+                            "info {key} isn't in header, number and type are required"
+                        ))
+                    })?;
+                    header.infos_mut().insert(
+                        id,
+                        map::Map::<map::Info>::new(n, t, value.description().unwrap_or_default()),
+                    );
+                }
+            }
+        }
+
+        for (key, value) in self.format.iter() {
+            let id: noodles::vcf::record::genotypes::keys::Key = key
+                .parse()
+                .map_err(|_| error::Error::Override(format!("{key} isn't a valid format key")))?;
+            let number = value.number().map(parse_number).transpose()?;
+            let ty = value
+                .ty()
+                .map(|t| {
+                    t.parse::<map::format::Type>().map_err(|_| {
+                        error::Error::Override(format!("{t} isn't a valid format type"))
+                    })
+                })
+                .transpose()?;
+
+            match header.formats_mut().get_mut(&id) {
+                Some(definition) => {
+                    if let Some(n) = number {
+                        *definition.number_mut() = n;
+                    }
+                    if let Some(t) = ty {
+                        *definition.type_mut() = t;
+                    }
+                    if let Some(d) = value.description() {
+                        *definition.description_mut() = d.to_string();
+                    }
+                }
+                None => {
+                    let (n, t) = number.zip(ty).ok_or_else(|| {
+                        error::Error::Override(format!(
+                            "format {key} isn't in header, number and type are required"
+                        ))
+                    })?;
+                    header.formats_mut().insert(
+                        id,
+                        map::Map::<map::Format>::new(n, t, value.description().unwrap_or_default()),
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_number(number: &str) -> error::Result<noodles::vcf::header::Number> {
+    number
+        .parse()
+        .map_err(|_| error::Error::Override(format!("{number} isn't a valid number")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static VCF_FILE: &[u8] = b"##fileformat=VCFv4.3
+##INFO=<ID=DP,Number=1,Type=Integer,Description=\"depth\">
+##INFO=<ID=CSQ,Number=1,Type=String,Description=\"consequence\">
+##FORMAT=<ID=XD,Number=1,Type=Integer,Description=\"allele depth\">
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tfirst
+1\t1\t.\tA\tC\t.\t.\tDP=1,2;CSQ=3;UND=a\tXD\t1,2
+";
+
+    static TOML: &str = "
+[info.DP]
+number = \".\"
+nullable = true
+rename = \"depth\"
+
+[info.CSQ]
+type = \"Integer\"
+
+[info.UND]
+number = \"1\"
+type = \"String\"
+description = \"undeclared\"
+
+[format.XD]
+number = \"R\"
+";
+
+    #[test]
+    fn parse() {
+        let toml = Overrides::from_toml(TOML).unwrap();
+        let json = Overrides::from_json(
+            r#"{"info": {"DP": {"number": ".", "nullable": true, "rename": "depth"}, "CSQ": {"type": "Integer"}, "UND": {"number": "1", "type": "String", "description": "undeclared"}}, "format": {"XD": {"number": "R"}}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(toml, json);
+        assert_eq!(toml.info("DP").unwrap().rename(), Some("depth"));
+        assert_eq!(toml.info("DP").unwrap().nullable(), Some(true));
+        assert!(toml.info("AD").is_none());
+        assert_eq!(toml.format("XD").unwrap().number(), Some("R"));
+
+        assert!(Overrides::from_toml("[info.DP]\nunknown = 1").is_err());
+        assert!(Overrides::from_toml("[info.DP]\nnullable = false").is_err());
+        assert!(Overrides::from_json(r#"{"format": {"XD": {"nullable": false}}}"#).is_err());
+    }
+
+    #[test]
+    fn apply() {
+        let mut reader = noodles::vcf::Reader::new(VCF_FILE);
+        let mut header: noodles::vcf::Header = reader.read_header().unwrap();
+
+        Overrides::from_toml(TOML)
+            .unwrap()
+            .apply(&mut header)
+            .unwrap();
+
+        let dp = header.infos().get("DP").unwrap();
+        assert_eq!(dp.number(), noodles::vcf::header::Number::Unknown);
+        assert_eq!(
+            header.infos().get("CSQ").unwrap().ty(),
+            noodles::vcf::header::record::value::map::info::Type::Integer
+        );
+        assert_eq!(
+            header.infos().get("UND").unwrap().description(),
+            "undeclared"
+        );
+        assert_eq!(
+            header.formats().get("XD").unwrap().number(),
+            noodles::vcf::header::Number::R
+        );
+
+        let record = reader.records(&header).next().unwrap().unwrap();
+        assert_eq!(
+            record
+                .info()
+                .get(
+                    &"CSQ"
+                        .parse::<noodles::vcf::record::info::field::Key>()
+                        .unwrap()
+                )
+                .flatten(),
+            Some(&noodles::vcf::record::info::field::Value::Integer(3))
+        );
+    }
+
+    #[test]
+    fn apply_error() {
+        let mut reader = noodles::vcf::Reader::new(VCF_FILE);
+        let mut header: noodles::vcf::Header = reader.read_header().unwrap();
+
+        let overrides = Overrides::from_toml("[info.NEW]\nnumber = \"1\"").unwrap();
+        assert!(overrides.apply(&mut header).is_err());
+
+        let overrides = Overrides::from_toml("[info.DP]\nnumber = \"X\"").unwrap();
+        assert!(overrides.apply(&mut header).is_err());
+
+        let overrides = Overrides::from_toml("[format.XD]\ntype = \"Flag\"").unwrap();
+        assert!(overrides.apply(&mut header).is_err());
+    }
+}
@@ -4,10 +4,12 @@
 
 /* crate use */
 
-use arrow::datatypes::Field;
-
 /* project use */
+use crate::error;
 use crate::name2data::*;
+use crate::options;
+use crate::sampleqc;
+use crate::stats;
 
 /// Convert vcf record iterator into Parquet chunk
 pub struct Record2Chunk<T> {
@@ -15,6 +17,12 @@ pub struct Record2Chunk<T> {
     length: usize,
     header: noodles::vcf::Header,
     schema: std::sync::Arc<arrow::datatypes::Schema>,
+    plan: std::sync::Arc<Plan>,
+    options: options::Options,
+    reported: rustc_hash::FxHashSet<String>,
+    stats: stats::Stats,
+    start: std::time::Instant,
+    last_report: std::time::Instant,
     end: bool,
 }
 
@@ -28,15 +36,173 @@ where
         length: usize,
         header: noodles::vcf::Header,
         schema: std::sync::Arc<arrow::datatypes::Schema>,
+        options: options::Options,
     ) -> Self {
+        let plan = std::sync::Arc::new(Plan::new(&header, &schema, &options));
+
+        let mut stats = stats::Stats::new();
+        if options.sample_qc() {
+            stats.set_sample_qc(Some(sampleqc::SampleQc::new(&header, &options)));
+        }
+
         Self {
             inner,
             length,
             header,
             schema,
+            plan,
+            options,
+            reported: rustc_hash::FxHashSet::default(),
+            stats,
+            start: std::time::Instant::now(),
+            last_report: std::time::Instant::now(),
             end: false,
         }
     }
+
+    /// Get number of records read
+    pub fn records(&self) -> usize {
+        self.stats.records()
+    }
+
+    /// Get number of values replace by null in lenient mode
+    pub fn nulled(&self) -> usize {
+        self.stats.nulled()
+    }
+
+    /// Get number of records that can't be parsed and are skipped in lenient mode
+    pub fn skipped(&self) -> usize {
+        self.stats.skipped()
+    }
+
+    /// Get statistics of conversion
+    pub fn stats(&self) -> &stats::Stats {
+        &self.stats
+    }
+
+    /// Get vcf header, after overrides
+    pub fn header(&self) -> &noodles::vcf::Header {
+        &self.header
+    }
+
+    /// Log progress of conversion, at most one time by second
+    fn progress(&mut self, chromosome: &str) {
+        if self.last_report.elapsed() < std::time::Duration::from_secs(1) {
+            return;
+        }
+        self.last_report = std::time::Instant::now();
+
+        let rate = self.stats.records() as f64 / self.start.elapsed().as_secs_f64();
+        match self.options.progress() {
+            Some(progress) => match progress.total() {
+                Some(total) => log::info!(
+                    "{} records read ({:.0} records/s), {} / {} bytes read ({:.1}%), current chromosome {}",
+                    self.stats.records(),
+                    rate,
+                    progress.bytes(),
+                    total,
+                    progress.bytes() as f64 * 100.0 / total.max(1) as f64,
+                    chromosome
+                ),
+                None => log::info!(
+                    "{} records read ({:.0} records/s), {} bytes read, current chromosome {}",
+                    self.stats.records(),
+                    rate,
+                    progress.bytes(),
+                    chromosome
+                ),
+            },
+            None => log::info!(
+                "{} records read ({:.0} records/s), current chromosome {}",
+                self.stats.records(),
+                rate,
+                chromosome
+            ),
+        }
+    }
+
+    /// Apply undeclared key policy on record, in extra mode keys are managed by [Name2Data]
+    fn undeclared(&mut self, record: &noodles::vcf::Record) -> error::Result<()> {
+        if self.options.undeclared() == options::Undeclared::Extra {
+            return Ok(());
+        }
+
+        for key in undeclared_keys(record, &self.header) {
+            match self.options.undeclared() {
+                options::Undeclared::Error => {
+                    return Err(error::Error::UndeclaredKey(key, record_position(record)))
+                }
+                _ => {
+                    if !self.reported.contains(&key) {
+                        log::warn!(
+                            "Key {} found in record {} isn't declared in vcf header, values of this key are drop",
+                            key,
+                            record_position(record)
+                        );
+                        self.reported.insert(key);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fill a Name2Data with at most `length` records or until memory budget is exceed, return true if inner iterator is consumed
+    fn fill(&mut self, name2data: &mut Name2Data) -> error::Result<bool> {
+        let mut added = 0;
+        while added < self.length {
+            match self.inner.next() {
+                Some(Ok(record)) => {
+                    self.stats.add_read();
+                    let chromosome = record.chromosome().to_string();
+                    if !self
+                        .options
+                        .keep_record(&chromosome, usize::from(record.position()))
+                    {
+                        self.stats.add_filtered();
+                        continue;
+                    }
+
+                    self.undeclared(&record)?;
+                    self.stats
+                        .add_record(&chromosome, record.alternate_bases().len());
+                    self.stats.add_sample_qc(&record);
+                    let nulled = name2data.add_record(record, &self.header, &self.options)?;
+                    self.stats.add_nulled(nulled);
+                    added += 1;
+
+                    if self
+                        .options
+                        .memory_budget()
+                        .is_some_and(|budget| name2data.memory() >= budget)
+                    {
+                        return Ok(false);
+                    }
+
+                    if self.stats.records() % 10_000 == 0 {
+                        self.progress(&chromosome);
+                    }
+                }
+                Some(Err(e)) => {
+                    self.stats.add_read();
+                    if !self.options.lenient() {
+                        return Err(error::Error::InvalidRecord(self.stats.records(), e));
+                    }
+
+                    log::warn!(
+                        "Record {} can't be parsed and is skip: {}",
+                        self.stats.records(),
+                        e
+                    );
+                    self.stats.add_skipped();
+                }
+                None => return Ok(true),
+            }
+        }
+
+        Ok(false)
+    }
 }
 
 impl<T> Iterator for Record2Chunk<T>
@@ -50,41 +216,58 @@ where
             return None;
         }
 
-        let mut name2data = Name2Data::new(self.length, &self.schema);
+        let mut name2data = match Name2Data::new(self.length, &self.schema, self.plan.clone()) {
+            Ok(name2data) => name2data,
+            Err(e) => return Some(Err(e.into())),
+        };
 
-        for _ in 0..self.length {
-            match self.inner.next() {
-                Some(Ok(record)) => {
-                    if let Err(e) = name2data.add_record(
-                        record,
-                        &self.header,
-                        &self
-                            .schema
-                            .flattened_fields()
-                            .into_iter()
-                            .map(|f| (f.name().to_string(), f.clone()))
-                            .collect::<rustc_hash::FxHashMap<String, Field>>(),
-                    ) {
-                        return Some(Err(e));
-                    }
-                }
-                Some(Err(e)) => {
-                    return Some(Err(arrow::error::ArrowError::IoError("".to_string(), e)))
-                }
-                None => {
-                    self.end = true;
+        match self.fill(&mut name2data) {
+            Ok(end) => self.end = end,
+            Err(e) => return Some(Err(e.into())),
+        }
+        self.stats.add_memory(name2data.memory());
+        self.stats
+            .add_reference_mismatch(name2data.reference_mismatch());
 
-                    return Some(arrow::record_batch::RecordBatch::try_new(
-                        self.schema.clone(),
-                        name2data.into_arc(&self.schema),
-                    ));
-                }
+        if self.end {
+            self.stats.set_end(
+                self.options.progress().map(|p| p.bytes()),
+                self.start.elapsed().as_secs_f64(),
+            );
+            self.stats.log();
+
+            if self.stats.nulled() != 0 {
+                log::warn!(
+                    "{} values can't be convert and are replace by null",
+                    self.stats.nulled()
+                );
+            }
+            if self.stats.skipped() != 0 {
+                log::warn!(
+                    "{} records can't be parsed and are skip",
+                    self.stats.skipped()
+                );
+            }
+            if self.stats.reference_mismatch() != 0 {
+                log::warn!(
+                    "{} records have a REF that doesn't match reference",
+                    self.stats.reference_mismatch()
+                );
             }
         }
 
         Some(arrow::record_batch::RecordBatch::try_new(
             self.schema.clone(),
-            name2data.into_arc(&self.schema),
+            name2data.into_arc(),
         ))
     }
 }
+
+impl<T> arrow::record_batch::RecordBatchReader for Record2Chunk<T>
+where
+    T: Iterator<Item = std::io::Result<noodles::vcf::Record>>,
+{
+    fn schema(&self) -> arrow::datatypes::SchemaRef {
+        self.schema.clone()
+    }
+}
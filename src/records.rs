@@ -0,0 +1,455 @@
+//! Owned iterator on vcf records
+
+/* std use */
+
+/* crate use */
+
+/* project use */
+use crate::options;
+use crate::schema;
+
+/// INFO keys, FORMAT keys and samples used by a schema, other fields are not decoded
+#[derive(Debug, Clone)]
+pub struct Selection {
+    infos: rustc_hash::FxHashMap<String, bool>,
+    formats: rustc_hash::FxHashMap<String, bool>,
+    samples: Vec<bool>,
+}
+
+impl Selection {
+    /// Create a Selection from columns of schema, return None if all fields and samples are used
+    pub fn new(
+        header: &noodles::vcf::Header,
+        schema: &arrow::datatypes::Schema,
+        options: &options::Options,
+    ) -> Option<Self> {
+        let names = schema
+            .fields()
+            .iter()
+            .map(|f| f.name().as_str())
+            .collect::<rustc_hash::FxHashSet<&str>>();
+
+        // INFO fields read by computed columns
+        let computed = schema::computed_column(options)
+            .iter()
+            .filter(|f| names.contains(f.name().as_str()))
+            .flat_map(|f| schema::computed_source(f.name()).split(", "))
+            .collect::<Vec<&str>>();
+        let genotype = computed.contains(&"FORMAT/GT") || options.sample_qc();
+
+        let infos = header
+            .infos()
+            .keys()
+            .map(|key| {
+                let used = names.contains(schema::info_column(key.as_ref(), options).as_str())
+                    || computed.contains(&format!("INFO/{}", key).as_str());
+                (key.to_string(), used)
+            })
+            .collect::<rustc_hash::FxHashMap<String, bool>>();
+
+        let formats = header
+            .formats()
+            .keys()
+            .map(|key| {
+                let used = (genotype && key.as_ref() == "GT")
+                    || (options.sample_qc() && matches!(key.as_ref(), "DP" | "GQ"))
+                    || header.sample_names().iter().any(|sample| {
+                        names
+                            .contains(schema::format_column(sample, key.as_ref(), options).as_str())
+                    });
+                (key.to_string(), used)
+            })
+            .collect::<rustc_hash::FxHashMap<String, bool>>();
+
+        let samples = header
+            .sample_names()
+            .iter()
+            .map(|sample| {
+                (genotype && options.keep_sample(sample))
+                    || names.contains(schema::format_extra_column(sample, options).as_str())
+                    || header.formats().keys().any(|key| {
+                        names
+                            .contains(schema::format_column(sample, key.as_ref(), options).as_str())
+                    })
+            })
+            .collect::<Vec<bool>>();
+
+        if infos.values().all(|u| *u) && formats.values().all(|u| *u) && samples.iter().all(|u| *u)
+        {
+            None
+        } else {
+            Some(Self {
+                infos,
+                formats,
+                samples,
+            })
+        }
+    }
+
+    /// Return true if INFO key is used, undeclared keys are always keep
+    pub fn info(&self, key: &str) -> bool {
+        self.infos.get(key).copied().unwrap_or(true)
+    }
+
+    /// Return true if FORMAT key is used, undeclared keys are always keep
+    pub fn format(&self, key: &str) -> bool {
+        self.formats.get(key).copied().unwrap_or(true)
+    }
+
+    /// Return true if sample at `index` is used
+    pub fn sample(&self, index: usize) -> bool {
+        self.samples.get(index).copied().unwrap_or(false)
+    }
+
+    /// Write in `line` a vcf line of `record` where unused INFO fields are remove and unused FORMAT values are replace by missing
+    pub fn reduce(&self, record: &noodles::vcf::lazy::Record, line: &mut String) {
+        line.clear();
+
+        for field in [
+            record.chromosome(),
+            record.position(),
+            record.ids().as_ref(),
+            record.reference_bases(),
+            record.alternate_bases(),
+            record.quality_score().unwrap_or(MISSING),
+            record
+                .filters()
+                .as_ref()
+                .map(|f| f.as_ref())
+                .unwrap_or(MISSING),
+        ] {
+            line.push_str(field);
+            line.push('\t');
+        }
+
+        // info
+        let mut empty = true;
+        for field in record.info().as_ref().split(';') {
+            let key = field.split_once('=').map_or(field, |(k, _)| k);
+            if !field.is_empty() && self.info(key) {
+                if !empty {
+                    line.push(';');
+                }
+                line.push_str(field);
+                empty = false;
+            }
+        }
+        if empty {
+            line.push_str(MISSING);
+        }
+
+        // genotypes
+        let genotypes = record.genotypes();
+        if genotypes.is_empty() {
+            return;
+        }
+
+        let mut columns = genotypes.as_ref().split('\t');
+        let kept = columns
+            .next()
+            .unwrap_or_default()
+            .split(':')
+            .enumerate()
+            .filter(|(_, key)| self.format(key))
+            .collect::<Vec<(usize, &str)>>();
+
+        line.push('\t');
+        if kept.is_empty() {
+            line.push_str(MISSING);
+        } else {
+            push_join(line, kept.iter().map(|(_, key)| *key));
+        }
+
+        for (index, column) in columns.enumerate() {
+            line.push('\t');
+            if kept.is_empty() || !self.sample(index) {
+                line.push_str(MISSING);
+                continue;
+            }
+
+            // trailing values can be omitted
+            let values = column.split(':').collect::<Vec<&str>>();
+            let length = kept
+                .iter()
+                .take_while(|(position, _)| *position < values.len())
+                .count();
+            if length == 0 {
+                line.push_str(MISSING);
+            } else {
+                push_join(
+                    line,
+                    kept[..length].iter().map(|(position, _)| values[*position]),
+                );
+            }
+        }
+    }
+}
+
+/// INFO keys store in a 64 bits float column, noodles parse float in 32 bits so raw text of these fields is keep
+pub fn float64_infos(
+    header: &noodles::vcf::Header,
+    schema: &arrow::datatypes::Schema,
+    options: &options::Options,
+) -> rustc_hash::FxHashSet<String> {
+    header
+        .infos()
+        .keys()
+        .filter(|key| {
+            schema
+                .field_with_name(&schema::info_column(key.as_ref(), options))
+                .is_ok_and(|field| match field.data_type() {
+                    arrow::datatypes::DataType::Float64 => true,
+                    arrow::datatypes::DataType::List(item) => {
+                        item.data_type() == &arrow::datatypes::DataType::Float64
+                    }
+                    _ => false,
+                })
+        })
+        .map(|key| key.to_string())
+        .collect()
+}
+
+/// Replace value of INFO fields of `keys` by their raw text in `line`
+fn raw_floats(record: &mut noodles::vcf::Record, line: &str, keys: &rustc_hash::FxHashSet<String>) {
+    let Some(info) = line.split('\t').nth(7) else {
+        return;
+    };
+
+    for (key, value) in info.split(';').filter_map(|field| field.split_once('=')) {
+        if keys.contains(key) {
+            if let Some(field) = record.info_mut().as_mut().get_mut(key) {
+                *field = Some(noodles::vcf::record::info::field::Value::String(
+                    value.to_string(),
+                ));
+            }
+        }
+    }
+}
+
+const MISSING: &str = ".";
+
+fn push_join<'a>(line: &mut String, values: impl Iterator<Item = &'a str>) {
+    for (index, value) in values.enumerate() {
+        if index != 0 {
+            line.push(':');
+        }
+        line.push_str(value);
+    }
+}
+
+/// Iterator on vcf records that own reader and header, records read in advance are return first
+pub struct Records<R> {
+    reader: noodles::vcf::Reader<R>,
+    header: noodles::vcf::Header,
+    buffer: std::collections::VecDeque<noodles::vcf::Record>,
+    selection: Option<Selection>,
+    float64: rustc_hash::FxHashSet<String>,
+    lazy: noodles::vcf::lazy::Record,
+    line: String,
+}
+
+impl<R> Records<R>
+where
+    R: std::io::BufRead,
+{
+    /// Create a new Records, reader must be positioned after header
+    pub fn new(reader: noodles::vcf::Reader<R>, header: noodles::vcf::Header) -> Self {
+        Self {
+            reader,
+            header,
+            buffer: std::collections::VecDeque::new(),
+            selection: None,
+            float64: rustc_hash::FxHashSet::default(),
+            lazy: noodles::vcf::lazy::Record::default(),
+            line: String::new(),
+        }
+    }
+
+    /// Set fields and samples decoded, records already read in advance are fully decoded
+    pub fn set_selection(&mut self, value: Option<Selection>) {
+        self.selection = value;
+    }
+
+    /// Set INFO keys store in 64 bits float, value of these fields is keep as raw text in record
+    pub fn set_float64(&mut self, value: rustc_hash::FxHashSet<String>) {
+        self.float64 = value;
+    }
+
+    /// Get header used to parse records
+    pub fn header(&self) -> &noodles::vcf::Header {
+        &self.header
+    }
+
+    /// Read at most `number` records in advance, they are still return by iterator
+    pub fn peek(&mut self, number: usize) -> std::io::Result<Vec<noodles::vcf::Record>> {
+        while self.buffer.len() < number {
+            match self.read()? {
+                Some(record) => self.buffer.push_back(record),
+                None => break,
+            }
+        }
+
+        Ok(self.buffer.iter().take(number).cloned().collect())
+    }
+
+    fn read(&mut self) -> std::io::Result<Option<noodles::vcf::Record>> {
+        match &self.selection {
+            None if self.float64.is_empty() => {
+                let mut record = noodles::vcf::Record::default();
+
+                return match self.reader.read_record(&self.header, &mut record)? {
+                    0 => Ok(None),
+                    _ => Ok(Some(record)),
+                };
+            }
+            None => {
+                self.line.clear();
+                if std::io::BufRead::read_line(self.reader.get_mut(), &mut self.line)? == 0 {
+                    return Ok(None);
+                }
+                let length = self.line.trim_end_matches(['\n', '\r']).len();
+                self.line.truncate(length);
+            }
+            Some(selection) => {
+                if self.reader.read_lazy_record(&mut self.lazy)? == 0 {
+                    return Ok(None);
+                }
+
+                selection.reduce(&self.lazy, &mut self.line);
+            }
+        }
+
+        let mut record = noodles::vcf::Record::try_from((&self.header, self.line.as_str()))
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        if !self.float64.is_empty() {
+            raw_floats(&mut record, &self.line, &self.float64);
+        }
+
+        Ok(Some(record))
+    }
+}
+
+impl<R> Iterator for Records<R>
+where
+    R: std::io::BufRead,
+{
+    type Item = std::io::Result<noodles::vcf::Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.buffer.pop_front() {
+            Some(record) => Some(Ok(record)),
+            None => self.read().transpose(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static VCF_FILE: &[u8] = b"##fileformat=VCFv4.3
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+1\t1\t.\tA\tC\t.\t.\t.
+1\t2\t.\tA\tC\t.\t.\t.
+1\t3\t.\tA\tC\t.\t.\t.
+";
+
+    #[test]
+    fn peek() {
+        let mut reader = noodles::vcf::Reader::new(VCF_FILE);
+        let header = reader.read_header().unwrap();
+        let mut records = Records::new(reader, header);
+
+        assert_eq!(records.peek(2).unwrap().len(), 2);
+        assert_eq!(records.peek(10).unwrap().len(), 3);
+        assert_eq!(records.header().sample_names().len(), 0);
+
+        let positions = records
+            .map(|r| usize::from(r.unwrap().position()))
+            .collect::<Vec<_>>();
+        assert_eq!(positions, vec![1, 2, 3]);
+    }
+
+    static SAMPLES_FILE: &[u8] = b"##fileformat=VCFv4.3
+##INFO=<ID=DP,Number=1,Type=Integer,Description=\"depth\">
+##INFO=<ID=AF,Number=A,Type=Float,Description=\"frequency\">
+##FORMAT=<ID=GT,Number=1,Type=String,Description=\"genotype\">
+##FORMAT=<ID=AD,Number=R,Type=Integer,Description=\"allele depth\">
+##FORMAT=<ID=GQ,Number=1,Type=Integer,Description=\"genotype quality\">
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tfirst\tsecond
+1\t1\trs1\tA\tC\t10\tPASS\tDP=4;AF=oops;UK=1\tGT:AD:GQ\t0/1:2,2:30\t1/1:0,4:40
+1\t2\t.\tA\tC\t.\t.\t.\tGT:AD\t0/1:bad\t1/1
+";
+
+    #[test]
+    fn selection() {
+        let mut reader = noodles::vcf::Reader::new(SAMPLES_FILE);
+        let header = reader.read_header().unwrap();
+
+        let options = options::Options::new();
+        let schema = schema::from_header_with_options(&header, &options).unwrap();
+        assert!(Selection::new(&header, &schema, &options).is_none());
+
+        let options = options::Options::new().set_columns(vec![
+            "position".to_string(),
+            "info_DP".to_string(),
+            "format_second_AD".to_string(),
+        ]);
+        let schema = schema::from_header_with_options(&header, &options).unwrap();
+        let selection = Selection::new(&header, &schema, &options).unwrap();
+
+        assert!(selection.info("DP"));
+        assert!(!selection.info("AF"));
+        assert!(selection.info("UK"));
+        assert!(selection.format("AD"));
+        assert!(!selection.format("GT"));
+        assert!(!selection.sample(0));
+        assert!(selection.sample(1));
+
+        let mut lazy = noodles::vcf::lazy::Record::default();
+        let mut line = String::new();
+
+        reader.read_lazy_record(&mut lazy).unwrap();
+        selection.reduce(&lazy, &mut line);
+        assert_eq!(line, "1\t1\trs1\tA\tC\t10\tPASS\tDP=4;UK=1\tAD\t.\t0,4");
+
+        reader.read_lazy_record(&mut lazy).unwrap();
+        selection.reduce(&lazy, &mut line);
+        assert_eq!(line, "1\t2\t.\tA\tC\t.\t.\t.\tAD\t.\t.");
+
+        // genotype statistics need GT of all kept samples
+        let options = options::Options::new()
+            .set_genotype_stats(true)
+            .set_columns(vec!["position".to_string(), "af".to_string()]);
+        let schema = schema::from_header_with_options(&header, &options).unwrap();
+        let selection = Selection::new(&header, &schema, &options).unwrap();
+
+        assert!(selection.format("GT"));
+        assert!(!selection.format("AD"));
+        assert!(selection.sample(0));
+        assert!(selection.sample(1));
+    }
+
+    #[test]
+    fn selected_records() {
+        let mut reader = noodles::vcf::Reader::new(SAMPLES_FILE);
+        let header = reader.read_header().unwrap();
+        let mut records = Records::new(reader, header.clone());
+
+        // invalid values are in fields not selected
+        let options = options::Options::new()
+            .set_columns(vec!["info_DP".to_string(), "format_second_AD".to_string()]);
+        let schema = schema::from_header_with_options(&header, &options).unwrap();
+        records.set_selection(Selection::new(&header, &schema, &options));
+
+        let record = records.next().unwrap().unwrap();
+        assert_eq!(record.info().len(), 2);
+        assert!(record.info().keys().all(|key| key.as_ref() != "AF"));
+        assert_eq!(record.genotypes().keys().len(), 1);
+        assert!(record.genotypes().get_index(0).unwrap().values().is_empty());
+        assert_eq!(record.genotypes().get_index(1).unwrap().values().len(), 1);
+
+        assert!(records.next().unwrap().is_ok());
+        assert!(records.next().is_none());
+    }
+}
@@ -0,0 +1,280 @@
+//! Random access to a fasta reference, used to normalize variants, add sequence context and check REF
+
+/* std use */
+use std::io::Read as _;
+use std::io::Seek as _;
+
+/* crate use */
+
+/* project use */
+use crate::error;
+
+/// Position of a sequence in fasta file, same fields as a samtools faidx index
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Entry {
+    length: u64,
+    offset: u64,
+    line_bases: u64,
+    line_width: u64,
+}
+
+/// A fasta file with its index
+#[derive(Debug)]
+pub struct Reference {
+    file: std::sync::Mutex<std::fs::File>,
+    index: rustc_hash::FxHashMap<String, Entry>,
+}
+
+impl Reference {
+    /// Open a fasta file, index is read from `{path}.fai` if it exists otherwise it's build by reading file
+    pub fn from_path<P>(path: P) -> error::Result<Self>
+    where
+        P: AsRef<std::path::Path>,
+    {
+        let path = path.as_ref();
+        let mut fai = path.as_os_str().to_owned();
+        fai.push(".fai");
+        let fai = std::path::PathBuf::from(fai);
+
+        let index = if fai.exists() {
+            read_index(std::io::BufReader::new(std::fs::File::open(&fai)?))?
+        } else {
+            build_index(std::io::BufReader::new(std::fs::File::open(path)?))?
+        };
+
+        Ok(Self {
+            file: std::sync::Mutex::new(std::fs::File::open(path)?),
+            index,
+        })
+    }
+
+    /// Get length of sequence
+    pub fn length(&self, chromosome: &str) -> Option<usize> {
+        self.index.get(chromosome).map(|e| e.length as usize)
+    }
+
+    /// Get bases of `chromosome` between `start` and `end`, 1-based and inclusive, in uppercase
+    pub fn fetch(&self, chromosome: &str, start: usize, end: usize) -> error::Result<Vec<u8>> {
+        let entry = self.index.get(chromosome).ok_or_else(|| {
+            error::Error::Reference(format!("sequence {} isn't in reference", chromosome))
+        })?;
+
+        if start == 0 || start > end || end as u64 > entry.length {
+            return Err(error::Error::Reference(format!(
+                "region {}:{}-{} is outside of sequence",
+                chromosome, start, end
+            )));
+        }
+
+        let first = file_offset(entry, start as u64 - 1);
+        let last = file_offset(entry, end as u64 - 1);
+        let mut buffer = vec![0; (last - first + 1) as usize];
+
+        let mut file = self
+            .file
+            .lock()
+            .map_err(|e| error::Error::Reference(e.to_string()))?;
+        file.seek(std::io::SeekFrom::Start(first))?;
+        file.read_exact(&mut buffer)?;
+
+        buffer.retain(|b| !b.is_ascii_whitespace());
+        buffer.make_ascii_uppercase();
+
+        Ok(buffer)
+    }
+
+    /// Get bases between `start` and `end` extend by `flank` bases on each side, clipped at sequence ends
+    pub fn context(
+        &self,
+        chromosome: &str,
+        start: usize,
+        end: usize,
+        flank: usize,
+    ) -> error::Result<Vec<u8>> {
+        let length = self.length(chromosome).ok_or_else(|| {
+            error::Error::Reference(format!("sequence {} isn't in reference", chromosome))
+        })?;
+
+        self.fetch(
+            chromosome,
+            start.saturating_sub(flank).max(1),
+            (end + flank).min(length),
+        )
+    }
+}
+
+/// Check if `bases` of a REF match `reference`, comparison ignore case and N match any base
+pub fn same_sequence(bases: &[u8], reference: &[u8]) -> bool {
+    bases.len() == reference.len()
+        && bases.iter().zip(reference).all(|(a, b)| {
+            a.eq_ignore_ascii_case(b)
+                || a.eq_ignore_ascii_case(&b'N')
+                || b.eq_ignore_ascii_case(&b'N')
+        })
+}
+
+fn file_offset(entry: &Entry, position: u64) -> u64 {
+    entry.offset + (position / entry.line_bases) * entry.line_width + position % entry.line_bases
+}
+
+fn read_index<R>(input: R) -> error::Result<rustc_hash::FxHashMap<String, Entry>>
+where
+    R: std::io::BufRead,
+{
+    let mut index = rustc_hash::FxHashMap::default();
+
+    for line in input.lines() {
+        let line = line?;
+        if line.is_empty() {
+            continue;
+        }
+
+        let fields = line.split('\t').collect::<Vec<&str>>();
+        let values = fields.get(1..5).map(|v| {
+            v.iter()
+                .map(|f| f.parse::<u64>())
+                .collect::<Result<Vec<u64>, _>>()
+        });
+        match values {
+            Some(Ok(values)) if values[2] != 0 => {
+                index.insert(
+                    fields[0].to_string(),
+                    Entry {
+                        length: values[0],
+                        offset: values[1],
+                        line_bases: values[2],
+                        line_width: values[3],
+                    },
+                );
+            }
+            _ => {
+                return Err(error::Error::Reference(format!(
+                    "fasta index line {} can't be parsed",
+                    line
+                )))
+            }
+        }
+    }
+
+    Ok(index)
+}
+
+fn build_index<R>(mut input: R) -> error::Result<rustc_hash::FxHashMap<String, Entry>>
+where
+    R: std::io::BufRead,
+{
+    let mut index = rustc_hash::FxHashMap::default();
+    let mut current: Option<(String, Entry)> = None;
+    let mut offset = 0;
+    let mut line = Vec::new();
+
+    loop {
+        line.clear();
+        let length = input.read_until(b'\n', &mut line)? as u64;
+        if length == 0 {
+            break;
+        }
+        offset += length;
+
+        if line.starts_with(b">") {
+            if let Some((name, entry)) = current.take() {
+                index.insert(name, entry);
+            }
+
+            let name = String::from_utf8_lossy(&line[1..])
+                .split_whitespace()
+                .next()
+                .unwrap_or_default()
+                .to_string();
+            current = Some((
+                name,
+                Entry {
+                    length: 0,
+                    offset,
+                    line_bases: 0,
+                    line_width: 0,
+                },
+            ));
+        } else if let Some((_, entry)) = current.as_mut() {
+            let bases = line.iter().filter(|b| !b.is_ascii_whitespace()).count() as u64;
+            if entry.line_bases == 0 {
+                entry.line_bases = bases;
+                entry.line_width = length;
+            }
+            entry.length += bases;
+        }
+    }
+
+    if let Some((name, entry)) = current.take() {
+        index.insert(name, entry);
+    }
+
+    Ok(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Write as _;
+
+    static FASTA: &[u8] = b">chr1 first
+ACGTA
+CCCCA
+GG
+>chr2
+tttt
+";
+
+    #[test]
+    fn index() {
+        let index = build_index(FASTA).unwrap();
+
+        assert_eq!(
+            index["chr1"],
+            Entry {
+                length: 12,
+                offset: 12,
+                line_bases: 5,
+                line_width: 6
+            }
+        );
+        assert_eq!(index["chr2"].length, 4);
+
+        let fai = read_index(&b"chr1\t12\t12\t5\t6\nchr2\t4\t33\t4\t5\n"[..]).unwrap();
+        assert_eq!(fai, index);
+
+        assert!(read_index(&b"chr1\t12\n"[..]).is_err());
+    }
+
+    #[test]
+    fn fetch() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(FASTA).unwrap();
+
+        let reference = Reference::from_path(file.path()).unwrap();
+
+        assert_eq!(reference.length("chr1"), Some(12));
+        assert_eq!(reference.fetch("chr1", 1, 1).unwrap(), b"A");
+        assert_eq!(reference.fetch("chr1", 4, 12).unwrap(), b"TACCCCAGG");
+        assert_eq!(reference.fetch("chr2", 2, 3).unwrap(), b"TT");
+
+        assert!(reference.fetch("chr3", 1, 1).is_err());
+        assert!(reference.fetch("chr1", 0, 1).is_err());
+        assert!(reference.fetch("chr1", 10, 13).is_err());
+
+        assert_eq!(reference.context("chr1", 4, 4, 1).unwrap(), b"GTA");
+        assert_eq!(reference.context("chr1", 1, 2, 2).unwrap(), b"ACGT");
+        assert_eq!(reference.context("chr1", 11, 12, 3).unwrap(), b"CCAGG");
+        assert!(reference.context("chr3", 1, 1, 1).is_err());
+    }
+
+    #[test]
+    fn same() {
+        assert!(same_sequence(b"ACGT", b"ACGT"));
+        assert!(same_sequence(b"acNT", b"ACGT"));
+        assert!(same_sequence(b"A", b"N"));
+        assert!(!same_sequence(b"ACGT", b"ACCT"));
+        assert!(!same_sequence(b"AC", b"ACG"));
+    }
+}
@@ -0,0 +1,315 @@
+//! Per sample quality control summary, accumulated during conversion
+
+/* std use */
+
+/* crate use */
+
+/* project use */
+use crate::error;
+use crate::options;
+use crate::variant;
+
+/// Counts of one sample
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Sample {
+    called: u64,
+    missing: u64,
+    hom_ref: u64,
+    het: u64,
+    hom_alt: u64,
+    transitions: u64,
+    transversions: u64,
+    depth: (f64, u64),
+    quality: (f64, u64),
+    chromosomes: std::collections::BTreeMap<String, (u64, u64)>,
+}
+
+/// Quality control summary of samples keep in output
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SampleQc {
+    names: Vec<String>,
+    indexes: Vec<usize>,
+    samples: Vec<Sample>,
+}
+
+impl SampleQc {
+    /// Create a new SampleQc for samples of `header` keep by `options`
+    pub fn new(header: &noodles::vcf::Header, options: &options::Options) -> Self {
+        let (indexes, names): (Vec<usize>, Vec<String>) = header
+            .sample_names()
+            .iter()
+            .enumerate()
+            .filter(|(_, sample)| options.keep_sample(sample))
+            .map(|(idx, sample)| (idx, sample.clone()))
+            .unzip();
+
+        Self {
+            samples: vec![Sample::default(); names.len()],
+            names,
+            indexes,
+        }
+    }
+
+    /// Add genotypes, DP and GQ of a record
+    pub fn add_record(&mut self, record: &noodles::vcf::Record) {
+        use noodles::vcf::record::genotypes::keys::key;
+        use noodles::vcf::record::genotypes::sample::Value;
+
+        let chromosome = record.chromosome().to_string();
+        let reference = record.reference_bases().to_string();
+        // transition flag of each allele, index 0 is reference
+        let transitions = std::iter::once(None)
+            .chain(
+                record
+                    .alternate_bases()
+                    .iter()
+                    .map(|allele| variant::classify(&reference, &allele.to_string()).transition),
+            )
+            .collect::<Vec<Option<bool>>>();
+
+        for (sample, idx) in self.samples.iter_mut().zip(self.indexes.iter()) {
+            let values = record.genotypes().get_index(*idx);
+
+            let integer = |k| match values.as_ref().and_then(|v| v.get(k)).flatten() {
+                Some(Value::Integer(value)) => Some(*value as f64),
+                _ => None,
+            };
+            if let Some(depth) = integer(&key::READ_DEPTH) {
+                sample.depth.0 += depth;
+                sample.depth.1 += 1;
+            }
+            if let Some(quality) = integer(&key::CONDITIONAL_GENOTYPE_QUALITY) {
+                sample.quality.0 += quality;
+                sample.quality.1 += 1;
+            }
+
+            // a genotype that can't be parsed is count as missing
+            let alleles = values
+                .as_ref()
+                .and_then(|v| v.genotype())
+                .and_then(|g| g.ok())
+                .map(|g| {
+                    g.iter()
+                        .map(|a| a.position())
+                        .collect::<Option<Vec<usize>>>()
+                })
+                .unwrap_or_default()
+                .filter(|a| !a.is_empty());
+
+            let Some(alleles) = alleles else {
+                sample.missing += 1;
+                continue;
+            };
+
+            sample.called += 1;
+            let het = alleles.iter().any(|a| *a != alleles[0]);
+            if het {
+                sample.het += 1;
+            } else if alleles[0] == 0 {
+                sample.hom_ref += 1;
+            } else {
+                sample.hom_alt += 1;
+            }
+
+            let chromosome = sample.chromosomes.entry(chromosome.clone()).or_default();
+            chromosome.0 += u64::from(het);
+            chromosome.1 += 1;
+
+            let mut carried = alleles.clone();
+            carried.sort_unstable();
+            carried.dedup();
+            for allele in carried {
+                match transitions.get(allele).copied().flatten() {
+                    Some(true) => sample.transitions += 1,
+                    Some(false) => sample.transversions += 1,
+                    None => (),
+                }
+            }
+        }
+    }
+
+    /// Get arrow schema of summary
+    pub fn schema() -> arrow::datatypes::Schema {
+        let count =
+            |name| arrow::datatypes::Field::new(name, arrow::datatypes::DataType::Int64, false);
+        let ratio =
+            |name| arrow::datatypes::Field::new(name, arrow::datatypes::DataType::Float64, true);
+
+        arrow::datatypes::Schema::new(vec![
+            arrow::datatypes::Field::new("sample", arrow::datatypes::DataType::Utf8, false),
+            count("called"),
+            count("missing"),
+            count("hom_ref"),
+            count("het"),
+            count("hom_alt"),
+            count("transitions"),
+            count("transversions"),
+            ratio("ti_tv"),
+            ratio("mean_dp"),
+            ratio("mean_gq"),
+            ratio("het_rate"),
+            arrow::datatypes::Field::new_map(
+                "chromosome_het_rate",
+                "entries",
+                arrow::datatypes::Field::new("keys", arrow::datatypes::DataType::Utf8, false),
+                arrow::datatypes::Field::new("values", arrow::datatypes::DataType::Float64, true),
+                false,
+                false,
+            ),
+        ])
+    }
+
+    /// Build a record batch with one row by sample
+    pub fn to_batch(&self) -> error::Result<arrow::array::RecordBatch> {
+        let count = |f: fn(&Sample) -> u64| -> std::sync::Arc<dyn arrow::array::Array> {
+            std::sync::Arc::new(arrow::array::Int64Array::from_iter_values(
+                self.samples.iter().map(|s| f(s) as i64),
+            ))
+        };
+        let ratio = |f: fn(&Sample) -> (f64, u64)| -> std::sync::Arc<dyn arrow::array::Array> {
+            std::sync::Arc::new(arrow::array::Float64Array::from_iter(
+                self.samples
+                    .iter()
+                    .map(f)
+                    .map(|(n, d)| (d != 0).then(|| n / d as f64)),
+            ))
+        };
+
+        let mut chromosomes = arrow::array::MapBuilder::new(
+            None,
+            arrow::array::StringBuilder::new(),
+            arrow::array::Float64Builder::new(),
+        );
+        for sample in self.samples.iter() {
+            for (chromosome, (het, called)) in sample.chromosomes.iter() {
+                chromosomes.keys().append_value(chromosome);
+                chromosomes
+                    .values()
+                    .append_value(*het as f64 / *called as f64);
+            }
+            chromosomes.append(true)?;
+        }
+
+        Ok(arrow::array::RecordBatch::try_new(
+            std::sync::Arc::new(Self::schema()),
+            vec![
+                std::sync::Arc::new(arrow::array::StringArray::from(self.names.clone())),
+                count(|s| s.called),
+                count(|s| s.missing),
+                count(|s| s.hom_ref),
+                count(|s| s.het),
+                count(|s| s.hom_alt),
+                count(|s| s.transitions),
+                count(|s| s.transversions),
+                ratio(|s| (s.transitions as f64, s.transversions)),
+                ratio(|s| s.depth),
+                ratio(|s| s.quality),
+                ratio(|s| (s.het as f64, s.called)),
+                std::sync::Arc::new(chromosomes.finish()),
+            ],
+        )?)
+    }
+
+    /// Write summary in parquet
+    pub fn write<W>(&self, output: W) -> error::Result<()>
+    where
+        W: std::io::Write + Send,
+    {
+        let batch = self.to_batch()?;
+
+        let mut writer = parquet::arrow::ArrowWriter::try_new(output, batch.schema(), None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow::array::Array as _;
+
+    static VCF: &[u8] = b"##fileformat=VCFv4.3
+##contig=<ID=chr1,length=1000>
+##contig=<ID=chr2,length=1000>
+##FORMAT=<ID=GT,Number=1,Type=String,Description=\"Genotype\">
+##FORMAT=<ID=DP,Number=1,Type=Integer,Description=\"Read depth\">
+##FORMAT=<ID=GQ,Number=1,Type=Integer,Description=\"Genotype quality\">
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\ts1\ts2\ts3
+chr1\t10\t.\tA\tG\t.\tPASS\t.\tGT:DP:GQ\t0/1:10:30\t1/1:20:.\t./.:.:.
+chr1\t20\t.\tC\tA,T\t.\tPASS\t.\tGT:DP\t1/2:30\t0/0:10\t0/.:5
+chr2\t30\t.\tG\tGT\t.\tPASS\t.\tGT\t0|0\t0|1\t1
+";
+
+    fn qc(options: &options::Options) -> SampleQc {
+        let mut reader = noodles::vcf::Reader::new(VCF);
+        let header = reader.read_header().unwrap();
+
+        let mut qc = SampleQc::new(&header, options);
+        for record in reader.records(&header) {
+            qc.add_record(&record.unwrap());
+        }
+
+        qc
+    }
+
+    #[test]
+    fn counts() {
+        let qc = qc(&options::Options::new());
+
+        assert_eq!(qc.names, vec!["s1", "s2", "s3"]);
+
+        let s1 = &qc.samples[0];
+        assert_eq!((s1.called, s1.missing), (3, 0));
+        assert_eq!((s1.hom_ref, s1.het, s1.hom_alt), (1, 2, 0));
+        assert_eq!((s1.transitions, s1.transversions), (2, 1));
+        assert_eq!(s1.depth, (40.0, 2));
+        assert_eq!(s1.quality, (30.0, 1));
+        assert_eq!(s1.chromosomes["chr1"], (2, 2));
+        assert_eq!(s1.chromosomes["chr2"], (0, 1));
+
+        let s3 = &qc.samples[2];
+        assert_eq!((s3.called, s3.missing), (1, 2));
+        assert_eq!((s3.hom_ref, s3.het, s3.hom_alt), (0, 0, 1));
+        assert_eq!(s3.depth, (5.0, 1));
+    }
+
+    #[test]
+    fn batch() {
+        let qc = qc(&options::Options::new().set_samples(vec!["s2".to_string()]));
+        let batch = qc.to_batch().unwrap();
+
+        assert_eq!(batch.num_rows(), 1);
+        assert_eq!(batch.schema().as_ref(), &SampleQc::schema());
+
+        let column = |name| {
+            batch
+                .column_by_name(name)
+                .unwrap()
+                .as_any()
+                .downcast_ref::<arrow::array::Float64Array>()
+                .unwrap()
+                .clone()
+        };
+        // s2 is 1/1, 0/0 and 0|1
+        assert_eq!(column("het_rate").value(0), 1.0 / 3.0);
+        assert_eq!(column("mean_dp").value(0), 15.0);
+        assert!(column("mean_gq").is_null(0));
+        assert!(column("ti_tv").is_null(0));
+
+        let chromosomes = batch
+            .column_by_name("chromosome_het_rate")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow::array::MapArray>()
+            .unwrap()
+            .clone();
+        assert_eq!(chromosomes.value(0).len(), 2);
+
+        let mut output = Vec::new();
+        qc.write(&mut output).unwrap();
+        assert!(output.starts_with(b"PAR1"));
+    }
+}
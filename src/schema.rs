@@ -13,21 +13,423 @@ pub fn from_header(
     header: &noodles::vcf::Header,
     info_optional: bool,
 ) -> error::Result<arrow::datatypes::Schema> {
+    from_header_with_options(
+        header,
+        &options::Options::new().set_info_optional(info_optional),
+    )
+}
+
+/// Generate a parquet schema corresponding to vcf header and `options`
+pub fn from_header_with_options(
+    header: &noodles::vcf::Header,
+    options: &options::Options,
+) -> error::Result<arrow::datatypes::Schema> {
+    options.naming().check()?;
+    if options.reference().is_none()
+        && (options.context() != 0 || options.check_reference() || options.fill_reference())
+    {
+        return Err(error::Error::Reference(
+            "sequence context, REF check and REF fill require a reference".to_string(),
+        ));
+    }
+
     let mut columns = Vec::new();
 
     // required column
     columns.extend(required_column());
 
+    // computed column
+    columns.extend(computed_column(options));
+
     // info field
-    columns.extend(info(header, info_optional));
+    columns.extend(info(header, options)?);
 
     // genotype field
-    columns.extend(genotype(header));
+    columns.extend(genotype(header, options)?);
+
+    // column name collision
+    collision(&columns, header, options)?;
+
+    // dictionary encoding
+    dictionary(&mut columns, options.dictionary())?;
+
+    // column selection
+    let columns = select(columns, options.columns())?;
 
     Ok(arrow::datatypes::Schema::new(columns))
 }
 
-fn required_column() -> Vec<arrow::datatypes::Field> {
+/// Get name of column associate to an info field
+pub fn info_column(key: &str, options: &options::Options) -> String {
+    let name = options
+        .overrides()
+        .info(key)
+        .and_then(|o| o.rename())
+        .unwrap_or(key);
+
+    options.naming().info(name)
+}
+
+/// Get name of column associate to a format field of a sample
+pub fn format_column(sample: &str, key: &str, options: &options::Options) -> String {
+    let name = options
+        .overrides()
+        .format(key)
+        .and_then(|o| o.rename())
+        .unwrap_or(key);
+
+    options.naming().format(sample, name)
+}
+
+/// Get name of column that store info fields not declared in header
+pub fn info_extra_column(options: &options::Options) -> String {
+    options.naming().info("extra")
+}
+
+/// Get name of column that store format fields not declared in header of a sample
+pub fn format_extra_column(sample: &str, options: &options::Options) -> String {
+    options.naming().format(sample, "extra")
+}
+
+/// Store original key and sample of a column in field metadata, only when naming policy isn't the default one
+fn origin(
+    field: &mut arrow::datatypes::Field,
+    key: &str,
+    sample: Option<&str>,
+    options: &options::Options,
+) {
+    if options.naming().is_default() {
+        return;
+    }
+
+    let mut metadata = std::collections::HashMap::from([("vcf_key".to_string(), key.to_string())]);
+    if let Some(sample) = sample {
+        metadata.insert("vcf_sample".to_string(), sample.to_string());
+    }
+
+    field.set_metadata(metadata);
+}
+
+fn collision(
+    columns: &[arrow::datatypes::Field],
+    header: &noodles::vcf::Header,
+    options: &options::Options,
+) -> error::Result<()> {
+    // many parquet readers ignore case of column names, names that differ only by case are an error only if naming policy is change
+    let strict = options.naming().sanitize() || options.naming().lowercase();
+    let mut names = rustc_hash::FxHashSet::default();
+    let mut lowercases = rustc_hash::FxHashMap::<String, &str>::default();
+
+    for field in columns {
+        let second = field.name().as_str();
+        let first = if !names.insert(second) {
+            second
+        } else {
+            match lowercases.get(&second.to_lowercase()) {
+                Some(first) if strict => *first,
+                Some(first) => {
+                    let (first_source, second_source) = sources(header, options, first, second);
+                    log::warn!(
+                        "Column names {} and {} produce by {} and {} are equal ignoring case, some parquet readers can't distinguish them",
+                        first,
+                        second,
+                        first_source,
+                        second_source
+                    );
+                    continue;
+                }
+                None => {
+                    lowercases.insert(second.to_lowercase(), second);
+                    continue;
+                }
+            }
+        };
+
+        let (first_source, second_source) = sources(header, options, first, second);
+        return Err(error::Error::ColumnCollision(
+            first.to_string(),
+            second.to_string(),
+            first_source,
+            second_source,
+        ));
+    }
+
+    Ok(())
+}
+
+/// Get vcf fields at origin of two colliding columns
+fn sources(
+    header: &noodles::vcf::Header,
+    options: &options::Options,
+    first: &str,
+    second: &str,
+) -> (String, String) {
+    let sources = |name: &str| {
+        origins(header, options)
+            .into_iter()
+            .filter(|(n, _)| n == name)
+            .map(|(_, source)| source)
+            .collect::<Vec<String>>()
+    };
+
+    let (first_source, second_source) = if first == second {
+        let both = sources(first);
+        (both.first().cloned(), both.get(1).cloned())
+    } else {
+        (
+            sources(first).first().cloned(),
+            sources(second).first().cloned(),
+        )
+    };
+
+    (
+        first_source.unwrap_or_default(),
+        second_source.unwrap_or_default(),
+    )
+}
+
+/// List name and vcf field at origin of each column, in schema order
+pub fn origins(header: &noodles::vcf::Header, options: &options::Options) -> Vec<(String, String)> {
+    let mut origins = required_column()
+        .iter()
+        .zip(["CHROM", "POS", "ID", "REF", "ALT", "QUAL", "FILTER"])
+        .map(|(f, s)| (f.name().to_string(), s.to_string()))
+        .collect::<Vec<(String, String)>>();
+    for field in computed_column(options) {
+        origins.push((
+            field.name().to_string(),
+            computed_source(field.name()).to_string(),
+        ));
+    }
+
+    for name in header.infos().keys() {
+        origins.push((
+            info_column(name.as_ref(), options),
+            format!("INFO/{}", name),
+        ));
+    }
+    if options.undeclared() == options::Undeclared::Extra {
+        origins.push((
+            info_extra_column(options),
+            "undeclared INFO keys".to_string(),
+        ));
+    }
+
+    for sample in header
+        .sample_names()
+        .iter()
+        .filter(|s| options.keep_sample(s))
+    {
+        for name in header.formats().keys() {
+            origins.push((
+                format_column(sample, name.as_ref(), options),
+                format!("FORMAT/{} of sample {}", name, sample),
+            ));
+        }
+        if options.undeclared() == options::Undeclared::Extra {
+            origins.push((
+                format_extra_column(sample, options),
+                format!("undeclared FORMAT keys of sample {}", sample),
+            ));
+        }
+    }
+
+    origins
+}
+
+/// Estimate number of bytes used by a row in column builders, list are suppose to contains two values
+pub fn row_bytes(schema: &arrow::datatypes::Schema) -> usize {
+    schema
+        .fields()
+        .iter()
+        .map(|field| type_bytes(field.data_type()))
+        .sum::<usize>()
+        .max(1)
+}
+
+fn type_bytes(arrow_type: &arrow::datatypes::DataType) -> usize {
+    match arrow_type {
+        arrow::datatypes::DataType::Boolean | arrow::datatypes::DataType::Int8 => 1,
+        arrow::datatypes::DataType::Int16 | arrow::datatypes::DataType::Float16 => 2,
+        arrow::datatypes::DataType::Int32 | arrow::datatypes::DataType::Float32 => 4,
+        arrow::datatypes::DataType::Int64 | arrow::datatypes::DataType::Float64 => 8,
+        arrow::datatypes::DataType::Dictionary(_, _) => 4,
+        arrow::datatypes::DataType::List(inner) => 4 + 2 * type_bytes(inner.data_type()),
+        arrow::datatypes::DataType::Map(_, _) => 4 + 2 * columndata::STRING_ESTIMATE,
+        _ => columndata::STRING_ESTIMATE,
+    }
+}
+
+/// Arrow type of column that store fields not declared in header, a map of string
+pub fn extra_type() -> arrow::datatypes::DataType {
+    arrow::datatypes::DataType::Map(
+        Arc::new(arrow::datatypes::Field::new(
+            "entries",
+            arrow::datatypes::DataType::Struct(arrow::datatypes::Fields::from(vec![
+                arrow::datatypes::Field::new("keys", arrow::datatypes::DataType::Utf8, false),
+                arrow::datatypes::Field::new("values", arrow::datatypes::DataType::Utf8, true),
+            ])),
+            false,
+        )),
+        false,
+    )
+}
+
+fn dictionary(columns: &mut [arrow::datatypes::Field], names: &[String]) -> error::Result<()> {
+    for name in names {
+        let field = columns
+            .iter_mut()
+            .find(|f| f.name() == name)
+            .ok_or_else(|| error::Error::DictionaryColumn(name.to_string()))?;
+
+        let arrow_type = match field.data_type() {
+            arrow::datatypes::DataType::Utf8 => dictionary_type(),
+            arrow::datatypes::DataType::List(inner)
+                if inner.data_type() == &arrow::datatypes::DataType::Utf8 =>
+            {
+                arrow::datatypes::DataType::List(Arc::new(
+                    inner.as_ref().clone().with_data_type(dictionary_type()),
+                ))
+            }
+            arrow::datatypes::DataType::Dictionary(_, _) => continue,
+            arrow::datatypes::DataType::List(inner)
+                if matches!(
+                    inner.data_type(),
+                    arrow::datatypes::DataType::Dictionary(_, _)
+                ) =>
+            {
+                continue
+            }
+            _ => return Err(error::Error::DictionaryColumn(name.to_string())),
+        };
+
+        *field = field.clone().with_data_type(arrow_type);
+    }
+
+    Ok(())
+}
+
+fn select(
+    columns: Vec<arrow::datatypes::Field>,
+    names: &[String],
+) -> error::Result<Vec<arrow::datatypes::Field>> {
+    if names.is_empty() {
+        return Ok(columns);
+    }
+
+    if let Some(name) = names
+        .iter()
+        .find(|n| !columns.iter().any(|f| f.name() == *n))
+    {
+        return Err(error::Error::SelectColumn(name.to_string()));
+    }
+
+    Ok(columns
+        .into_iter()
+        .filter(|f| names.contains(f.name()))
+        .collect())
+}
+
+fn dictionary_type() -> arrow::datatypes::DataType {
+    arrow::datatypes::DataType::Dictionary(
+        Box::new(arrow::datatypes::DataType::Int32),
+        Box::new(arrow::datatypes::DataType::Utf8),
+    )
+}
+
+/// Columns computed from core fields, variant identifier, its hash, classification, structural variant, reference comparison and genotype statistics
+pub fn computed_column(options: &options::Options) -> Vec<arrow::datatypes::Field> {
+    let mut columns = Vec::new();
+
+    if options.variant_id() {
+        columns.push(arrow::datatypes::Field::new(
+            "variant_id",
+            arrow::datatypes::DataType::Utf8,
+            false,
+        ));
+    }
+    if options.variant_hash() {
+        columns.push(arrow::datatypes::Field::new(
+            "variant_hash",
+            arrow::datatypes::DataType::Int64,
+            false,
+        ));
+    }
+    if options.classify() {
+        columns.extend([
+            arrow::datatypes::Field::new("variant_type", arrow::datatypes::DataType::Utf8, false),
+            arrow::datatypes::Field::new("length_change", arrow::datatypes::DataType::Int32, true),
+            arrow::datatypes::Field::new("transition", arrow::datatypes::DataType::Boolean, true),
+        ]);
+    }
+    if options.classify() || options.sv() {
+        columns.extend([
+            arrow::datatypes::Field::new("sv_type", arrow::datatypes::DataType::Utf8, true),
+            arrow::datatypes::Field::new("sv_length", arrow::datatypes::DataType::Int32, true),
+        ]);
+    }
+    if options.sv() {
+        columns.extend([
+            arrow::datatypes::Field::new("sv_end", arrow::datatypes::DataType::Int32, true),
+            arrow::datatypes::Field::new("mate_chromosome", arrow::datatypes::DataType::Utf8, true),
+            arrow::datatypes::Field::new("mate_position", arrow::datatypes::DataType::Int32, true),
+            arrow::datatypes::Field::new("orientation", arrow::datatypes::DataType::Utf8, true),
+            arrow::datatypes::Field::new("cipos_start", arrow::datatypes::DataType::Int32, true),
+            arrow::datatypes::Field::new("cipos_end", arrow::datatypes::DataType::Int32, true),
+            arrow::datatypes::Field::new("ciend_start", arrow::datatypes::DataType::Int32, true),
+            arrow::datatypes::Field::new("ciend_end", arrow::datatypes::DataType::Int32, true),
+        ]);
+    }
+    if options.context() != 0 {
+        columns.push(arrow::datatypes::Field::new(
+            "context",
+            arrow::datatypes::DataType::Utf8,
+            true,
+        ));
+    }
+    if options.check_reference() {
+        columns.push(arrow::datatypes::Field::new(
+            "reference_match",
+            arrow::datatypes::DataType::Boolean,
+            true,
+        ));
+    }
+    if options.genotype_stats() {
+        columns.extend([
+            arrow::datatypes::Field::new("ac", arrow::datatypes::DataType::Int32, false),
+            arrow::datatypes::Field::new("an", arrow::datatypes::DataType::Int32, false),
+            arrow::datatypes::Field::new("af", arrow::datatypes::DataType::Float64, true),
+            arrow::datatypes::Field::new("n_het", arrow::datatypes::DataType::Int32, false),
+            arrow::datatypes::Field::new("n_hom_alt", arrow::datatypes::DataType::Int32, false),
+            arrow::datatypes::Field::new("n_missing", arrow::datatypes::DataType::Int32, false),
+            arrow::datatypes::Field::new("call_rate", arrow::datatypes::DataType::Float64, true),
+            arrow::datatypes::Field::new("hwe_pvalue", arrow::datatypes::DataType::Float64, true),
+        ]);
+    }
+
+    columns
+}
+
+/// Get vcf fields used to compute a column of [computed_column], separate by `, `
+pub fn computed_source(name: &str) -> &'static str {
+    match name {
+        "variant_id" | "variant_hash" => "CHROM-POS-REF-ALT",
+        "sv_type" => "ALT, INFO/SVTYPE",
+        "sv_length" => "INFO/SVLEN",
+        "sv_end" => "INFO/END, INFO/SVLEN",
+        "mate_chromosome" | "mate_position" | "orientation" => "ALT",
+        "cipos_start" | "cipos_end" => "INFO/CIPOS",
+        "ciend_start" | "ciend_end" => "INFO/CIEND",
+        "context" | "reference_match" => "CHROM-POS-REF, reference",
+        "ac" | "an" | "af" | "n_het" | "n_hom_alt" | "n_missing" | "call_rate" | "hwe_pvalue" => {
+            "FORMAT/GT"
+        }
+        _ => "REF-ALT",
+    }
+}
+
+/// Columns present in all schema
+pub fn required_column() -> Vec<arrow::datatypes::Field> {
     vec![
         arrow::datatypes::Field::new("chromosome", arrow::datatypes::DataType::Utf8, false),
         arrow::datatypes::Field::new("position", arrow::datatypes::DataType::Int32, false),
@@ -55,16 +457,29 @@ fn required_column() -> Vec<arrow::datatypes::Field> {
     ]
 }
 
-fn info(header: &noodles::vcf::Header, info_optional: bool) -> Vec<arrow::datatypes::Field> {
+fn info(
+    header: &noodles::vcf::Header,
+    options: &options::Options,
+) -> error::Result<Vec<arrow::datatypes::Field>> {
     let mut fields = Vec::new();
 
     for (name, value) in header.infos() {
-        let key = format!("info_{name}");
+        let key = info_column(name.as_ref(), options);
+        let info_optional = options
+            .overrides()
+            .info(name.as_ref())
+            .and_then(|o| o.nullable())
+            .unwrap_or(options.info_optional() || options.lenient());
 
         let arrow_type = match value.ty() {
             noodles::vcf::header::record::value::map::info::Type::Integer => {
                 arrow::datatypes::DataType::Int32
             }
+            noodles::vcf::header::record::value::map::info::Type::Float
+                if options.info_float64() =>
+            {
+                arrow::datatypes::DataType::Float64
+            }
             noodles::vcf::header::record::value::map::info::Type::Float => {
                 arrow::datatypes::DataType::Float32
             }
@@ -78,6 +493,7 @@ fn info(header: &noodles::vcf::Header, info_optional: bool) -> Vec<arrow::dataty
                 arrow::datatypes::DataType::Utf8
             }
         };
+        let arrow_type = width(arrow_type, options.info_width(name.as_ref()), &key)?;
 
         match value.number() {
             noodles::vcf::header::Number::Count(0 | 1) | noodles::vcf::header::Number::A => fields
@@ -124,17 +540,49 @@ fn info(header: &noodles::vcf::Header, info_optional: bool) -> Vec<arrow::dataty
                 info_optional,
             )),
         }
+
+        if let Some(field) = fields.last_mut() {
+            origin(field, name.as_ref(), None, options);
+        }
+    }
+
+    if options.undeclared() == options::Undeclared::Extra {
+        fields.push(arrow::datatypes::Field::new(
+            info_extra_column(options),
+            extra_type(),
+            true,
+        ));
     }
 
-    fields
+    Ok(fields)
 }
 
-fn genotype(header: &noodles::vcf::Header) -> Vec<arrow::datatypes::Field> {
+fn genotype(
+    header: &noodles::vcf::Header,
+    options: &options::Options,
+) -> error::Result<Vec<arrow::datatypes::Field>> {
     let mut fields = Vec::new();
 
-    for sample in header.sample_names() {
+    if let Some(sample) = options
+        .samples()
+        .iter()
+        .find(|s| !header.sample_names().contains(*s))
+    {
+        return Err(error::Error::SelectSample(sample.to_string()));
+    }
+
+    for sample in header
+        .sample_names()
+        .iter()
+        .filter(|s| options.keep_sample(s))
+    {
         for (name, value) in header.formats() {
-            let key = format!("format_{sample}_{name}");
+            let key = format_column(sample, name.as_ref(), options);
+            let nullable = options
+                .overrides()
+                .format(name.as_ref())
+                .and_then(|o| o.nullable())
+                .unwrap_or(true);
 
             let arrow_type = match value.ty() {
                 noodles::vcf::header::record::value::map::format::Type::Integer => {
@@ -150,47 +598,193 @@ fn genotype(header: &noodles::vcf::Header) -> Vec<arrow::datatypes::Field> {
                     arrow::datatypes::DataType::Utf8
                 }
             };
+            let arrow_type = width(arrow_type, options.format_width(name.as_ref()), &key)?;
 
             match value.number() {
                 noodles::vcf::header::Number::Count(0 | 1) | noodles::vcf::header::Number::A => {
-                    fields.push(arrow::datatypes::Field::new(key, arrow_type, true))
+                    fields.push(arrow::datatypes::Field::new(key, arrow_type, nullable))
                 }
                 noodles::vcf::header::Number::R => fields.push(arrow::datatypes::Field::new(
                     &key,
                     arrow::datatypes::DataType::List(Arc::new(arrow::datatypes::Field::new(
-                        &key, arrow_type, true,
+                        &key, arrow_type, nullable,
                     ))),
-                    true,
+                    nullable,
                 )),
                 noodles::vcf::header::Number::Count(_n) => {
                     fields.push(arrow::datatypes::Field::new(
                         &key,
                         arrow::datatypes::DataType::List(Arc::new(arrow::datatypes::Field::new(
-                            &key, arrow_type, true,
+                            &key, arrow_type, nullable,
                         ))),
-                        true,
+                        nullable,
                     ))
                 }
                 noodles::vcf::header::Number::G => fields.push(arrow::datatypes::Field::new(
                     &key,
                     arrow::datatypes::DataType::List(Arc::new(arrow::datatypes::Field::new(
-                        &key, arrow_type, true,
+                        &key, arrow_type, nullable,
                     ))),
-                    true,
+                    nullable,
                 )),
 
                 noodles::vcf::header::Number::Unknown => fields.push(arrow::datatypes::Field::new(
                     &key,
                     arrow::datatypes::DataType::List(Arc::new(arrow::datatypes::Field::new(
-                        &key, arrow_type, true,
+                        &key, arrow_type, nullable,
                     ))),
-                    true,
+                    nullable,
                 )),
             }
+
+            if let Some(field) = fields.last_mut() {
+                origin(field, name.as_ref(), Some(sample), options);
+            }
+        }
+
+        if options.undeclared() == options::Undeclared::Extra {
+            fields.push(arrow::datatypes::Field::new(
+                format_extra_column(sample, options),
+                extra_type(),
+                true,
+            ));
         }
     }
 
-    fields
+    Ok(fields)
+}
+
+fn width(
+    arrow_type: arrow::datatypes::DataType,
+    width: Option<options::Width>,
+    name: &str,
+) -> error::Result<arrow::datatypes::DataType> {
+    match (arrow_type, width) {
+        (arrow_type, None) => Ok(arrow_type),
+        (arrow::datatypes::DataType::Int32, Some(w)) if w.is_integer() => Ok(w.arrow_type()),
+        (arrow::datatypes::DataType::Float32 | arrow::datatypes::DataType::Float64, Some(w))
+            if !w.is_integer() =>
+        {
+            Ok(w.arrow_type())
+        }
+        _ => Err(error::Error::TypeHint(name.to_string())),
+    }
+}
+
+/// Range of values of integer fields, used to choose narrowest integer type
+#[derive(Debug, Clone, Default)]
+pub struct IntegerRange {
+    info: rustc_hash::FxHashMap<String, (i32, i32)>,
+    format: rustc_hash::FxHashMap<String, (i32, i32)>,
+}
+
+impl IntegerRange {
+    /// Create a new IntegerRange
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Update range of integer fields with values of `record`
+    pub fn add_record(&mut self, header: &noodles::vcf::Header, record: &noodles::vcf::Record) {
+        use noodles::vcf::record::genotypes::sample;
+        use noodles::vcf::record::info::field;
+
+        for key in record.info().keys() {
+            let values = match record.info().get(key).flatten() {
+                Some(field::Value::Integer(i)) => vec![Some(*i)],
+                Some(field::Value::Array(field::value::Array::Integer(a))) => a.clone(),
+                _ => continue,
+            };
+
+            update(&mut self.info, key.as_ref(), values.into_iter().flatten());
+        }
+
+        for (key, _) in header.formats() {
+            let values = record
+                .genotypes()
+                .values()
+                .filter_map(|s| s.get(key).flatten().cloned())
+                .flat_map(|v| match v {
+                    sample::Value::Integer(i) => vec![Some(i)],
+                    sample::Value::Array(sample::value::Array::Integer(a)) => a,
+                    _ => vec![],
+                })
+                .flatten();
+
+            update(&mut self.format, key.as_ref(), values);
+        }
+    }
+
+    /// Choose narrowest integer type of integer fields, fields with a type hint in `options` or without value are ignored
+    pub fn hints(
+        &self,
+        header: &noodles::vcf::Header,
+        options: &options::Options,
+    ) -> Vec<options::TypeHint> {
+        let mut hints = Vec::new();
+
+        for (key, value) in header.infos() {
+            if value.ty() != noodles::vcf::header::record::value::map::info::Type::Integer
+                || options.info_width(key.as_ref()).is_some()
+            {
+                continue;
+            }
+
+            if let Some(range) = self.info.get(key.as_ref()) {
+                hints.push(options::TypeHint::new(
+                    options::Section::Info,
+                    key.to_string(),
+                    narrowest(*range),
+                ));
+            }
+        }
+
+        for (key, value) in header.formats() {
+            if value.ty() != noodles::vcf::header::record::value::map::format::Type::Integer
+                || options.format_width(key.as_ref()).is_some()
+            {
+                continue;
+            }
+
+            if let Some(range) = self.format.get(key.as_ref()) {
+                hints.push(options::TypeHint::new(
+                    options::Section::Format,
+                    key.to_string(),
+                    narrowest(*range),
+                ));
+            }
+        }
+
+        hints
+    }
+}
+
+fn update(
+    ranges: &mut rustc_hash::FxHashMap<String, (i32, i32)>,
+    key: &str,
+    values: impl Iterator<Item = i32>,
+) {
+    for value in values {
+        match ranges.get_mut(key) {
+            Some((min, max)) => {
+                *min = (*min).min(value);
+                *max = (*max).max(value);
+            }
+            None => {
+                ranges.insert(key.to_string(), (value, value));
+            }
+        }
+    }
+}
+
+fn narrowest((min, max): (i32, i32)) -> options::Width {
+    if i8::try_from(min).is_ok() && i8::try_from(max).is_ok() {
+        options::Width::Int8
+    } else if i16::try_from(min).is_ok() && i16::try_from(max).is_ok() {
+        options::Width::Int16
+    } else {
+        options::Width::Int32
+    }
 }
 
 #[cfg(test)]
@@ -393,7 +987,7 @@ mod tests {
 
         let header: noodles::vcf::Header = reader.read_header().unwrap();
 
-        assert_eq!(info(&header, false), *INFO_COLS);
+        assert_eq!(info(&header, &options::Options::new()).unwrap(), *INFO_COLS);
     }
 
     #[test]
@@ -402,10 +996,14 @@ mod tests {
 
         let header: noodles::vcf::Header = reader.read_header().unwrap();
 
-        assert_eq!(genotype(&header), *FORMAT_COLS);
+        assert_eq!(
+            genotype(&header, &options::Options::new()).unwrap(),
+            *FORMAT_COLS
+        );
     }
 
     #[test]
+    #[allow(clippy::map_clone)]
     fn all_cols() {
         let mut reader = noodles::vcf::Reader::new(VCF_FILE);
 
@@ -421,4 +1019,412 @@ mod tests {
             arrow::datatypes::Schema::new(data.iter().map(|x| x.clone()).collect::<Vec<_>>()),
         );
     }
+
+    #[test]
+    fn computed_cols() {
+        let mut reader = noodles::vcf::Reader::new(VCF_FILE);
+
+        let header: noodles::vcf::Header = reader.read_header().unwrap();
+
+        let options = options::Options::new()
+            .set_variant_id(true)
+            .set_variant_hash(true);
+        let schema = from_header_with_options(&header, &options).unwrap();
+
+        assert_eq!(
+            schema.fields().len(),
+            MINI_COLS.len() + INFO_COLS.len() + FORMAT_COLS.len() + 2
+        );
+        assert_eq!(schema.field(7).name(), "variant_id");
+        assert_eq!(
+            schema.field(8).data_type(),
+            &arrow::datatypes::DataType::Int64
+        );
+        assert_eq!(origins(&header, &options)[8].0, "variant_hash");
+
+        let options = options.set_columns(vec!["variant_hash".to_string()]);
+        assert_eq!(
+            from_header_with_options(&header, &options)
+                .unwrap()
+                .fields()
+                .len(),
+            1
+        );
+
+        let options = options::Options::new().set_classify(true);
+        let schema = from_header_with_options(&header, &options).unwrap();
+        assert_eq!(schema.field(7).name(), "variant_type");
+        assert!(schema.field_with_name("transition").unwrap().is_nullable());
+
+        let origins = origins(&header, &options);
+        assert_eq!(
+            origins[7],
+            ("variant_type".to_string(), "REF-ALT".to_string())
+        );
+        assert_eq!(
+            origins[10],
+            ("sv_type".to_string(), "ALT, INFO/SVTYPE".to_string())
+        );
+
+        // sv_type and sv_length are share by classification and structural variant
+        let options = options::Options::new().set_classify(true).set_sv(true);
+        let schema = from_header_with_options(&header, &options).unwrap();
+        assert_eq!(
+            schema.fields().len(),
+            MINI_COLS.len() + INFO_COLS.len() + FORMAT_COLS.len() + 13
+        );
+        assert_eq!(schema.field(12).name(), "sv_end");
+        assert_eq!(
+            super::origins(&header, &options)[12].1,
+            "INFO/END, INFO/SVLEN"
+        );
+    }
+
+    #[test]
+    fn dictionary_cols() {
+        let mut reader = noodles::vcf::Reader::new(VCF_FILE);
+
+        let header: noodles::vcf::Header = reader.read_header().unwrap();
+
+        let options = options::Options::new().set_dictionary(vec![
+            "chromosome".to_string(),
+            "filter".to_string(),
+            "info_Info_RChar".to_string(),
+            "format_second_Format_A".to_string(),
+        ]);
+        let schema = from_header_with_options(&header, &options).unwrap();
+
+        assert_eq!(
+            schema.field_with_name("chromosome").unwrap().data_type(),
+            &dictionary_type()
+        );
+        assert_eq!(
+            schema.field_with_name("filter").unwrap().data_type(),
+            &arrow::datatypes::DataType::List(Arc::new(arrow::datatypes::Field::new(
+                "filter",
+                dictionary_type(),
+                false,
+            )))
+        );
+        assert_eq!(
+            schema
+                .field_with_name("info_Info_RChar")
+                .unwrap()
+                .data_type(),
+            &arrow::datatypes::DataType::List(Arc::new(arrow::datatypes::Field::new(
+                "info_Info_RChar",
+                dictionary_type(),
+                false,
+            )))
+        );
+        assert_eq!(
+            schema
+                .field_with_name("format_second_Format_A")
+                .unwrap()
+                .data_type(),
+            &dictionary_type()
+        );
+        assert_eq!(
+            schema.field_with_name("reference").unwrap().data_type(),
+            &arrow::datatypes::DataType::Utf8
+        );
+    }
+
+    #[test]
+    fn row_size() {
+        let schema = arrow::datatypes::Schema::new(vec![
+            arrow::datatypes::Field::new("position", arrow::datatypes::DataType::Int32, false),
+            arrow::datatypes::Field::new("reference", arrow::datatypes::DataType::Utf8, false),
+            arrow::datatypes::Field::new(
+                "format_sample_AD",
+                arrow::datatypes::DataType::List(Arc::new(arrow::datatypes::Field::new(
+                    "format_sample_AD",
+                    arrow::datatypes::DataType::Int16,
+                    true,
+                ))),
+                true,
+            ),
+        ]);
+
+        assert_eq!(row_bytes(&schema), 4 + 14 + (4 + 2 * 2));
+        assert_eq!(row_bytes(&arrow::datatypes::Schema::empty()), 1);
+    }
+
+    #[test]
+    fn dictionary_error() {
+        let mut reader = noodles::vcf::Reader::new(VCF_FILE);
+
+        let header: noodles::vcf::Header = reader.read_header().unwrap();
+
+        let options = options::Options::new().set_dictionary(vec!["position".to_string()]);
+        assert!(matches!(
+            from_header_with_options(&header, &options),
+            Err(error::Error::DictionaryColumn(_))
+        ));
+
+        let options = options::Options::new().set_dictionary(vec!["not_a_column".to_string()]);
+        assert!(matches!(
+            from_header_with_options(&header, &options),
+            Err(error::Error::DictionaryColumn(_))
+        ));
+    }
+
+    #[test]
+    fn extra_cols() {
+        let mut reader = noodles::vcf::Reader::new(VCF_FILE);
+
+        let header: noodles::vcf::Header = reader.read_header().unwrap();
+
+        let schema = from_header_with_options(
+            &header,
+            &options::Options::new().set_undeclared(options::Undeclared::Extra),
+        )
+        .unwrap();
+
+        assert_eq!(
+            schema.field_with_name("info_extra").unwrap().data_type(),
+            &extra_type()
+        );
+        assert!(schema.field_with_name("format_first_extra").is_ok());
+        assert!(schema.field_with_name("format_second_extra").is_ok());
+        assert_eq!(
+            schema.fields().len(),
+            MINI_COLS.len() + INFO_COLS.len() + FORMAT_COLS.len() + 3
+        );
+    }
+
+    #[test]
+    fn select_cols() {
+        let mut reader = noodles::vcf::Reader::new(VCF_FILE);
+
+        let header: noodles::vcf::Header = reader.read_header().unwrap();
+
+        let schema = from_header_with_options(
+            &header,
+            &options::Options::new().set_samples(vec!["second".to_string()]),
+        )
+        .unwrap();
+        assert!(schema.field_with_name("format_first_Format_1").is_err());
+        assert!(schema.field_with_name("format_second_Format_1").is_ok());
+        assert_eq!(
+            schema.fields().len(),
+            MINI_COLS.len() + INFO_COLS.len() + FORMAT_COLS.len() / 2
+        );
+
+        let schema = from_header_with_options(
+            &header,
+            &options::Options::new()
+                .set_columns(vec!["position".to_string(), "chromosome".to_string()]),
+        )
+        .unwrap();
+        let names = schema
+            .fields()
+            .iter()
+            .map(|f| f.name().as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["chromosome", "position"]);
+
+        assert!(matches!(
+            from_header_with_options(
+                &header,
+                &options::Options::new().set_columns(vec!["not_a_column".to_string()])
+            ),
+            Err(error::Error::SelectColumn(_))
+        ));
+        assert!(matches!(
+            from_header_with_options(
+                &header,
+                &options::Options::new().set_samples(vec!["third".to_string()])
+            ),
+            Err(error::Error::SelectSample(_))
+        ));
+    }
+
+    #[test]
+    fn naming_cols() {
+        let mut reader = noodles::vcf::Reader::new(VCF_FILE);
+
+        let header: noodles::vcf::Header = reader.read_header().unwrap();
+
+        let schema = from_header_with_options(&header, &options::Options::new()).unwrap();
+        assert!(schema
+            .field_with_name("info_Info_.")
+            .unwrap()
+            .metadata()
+            .is_empty());
+
+        let options = options::Options::new().set_naming(
+            options::Naming::new()
+                .set_format_template("{sample}.{key}".to_string())
+                .set_sanitize(true)
+                .set_lowercase(true),
+        );
+        let schema = from_header_with_options(&header, &options).unwrap();
+
+        let field = schema.field_with_name("info_info__").unwrap();
+        assert_eq!(field.metadata().get("vcf_key"), Some(&"Info_.".to_string()));
+        assert_eq!(field.metadata().get("vcf_sample"), None);
+
+        let field = schema.field_with_name("second_format_1").unwrap();
+        assert_eq!(
+            field.metadata().get("vcf_key"),
+            Some(&"Format_1".to_string())
+        );
+        assert_eq!(
+            field.metadata().get("vcf_sample"),
+            Some(&"second".to_string())
+        );
+
+        let mut reader = noodles::vcf::Reader::new(
+            &b"##fileformat=VCFv4.3
+##INFO=<ID=AB,Number=1,Type=Integer,Description=\"upper\">
+##INFO=<ID=ab,Number=1,Type=Integer,Description=\"lower\">
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+"[..],
+        );
+        let header: noodles::vcf::Header = reader.read_header().unwrap();
+
+        // with default naming, names that differ only by case are only a warning
+        assert!(from_header_with_options(&header, &options::Options::new()).is_ok());
+        assert!(matches!(
+            from_header_with_options(
+                &header,
+                &options::Options::new().set_naming(options::Naming::new().set_sanitize(true))
+            ),
+            Err(error::Error::ColumnCollision(first, second, first_source, second_source))
+                if first == "info_AB" && second == "info_ab" && first_source == "INFO/AB" && second_source == "INFO/ab"
+        ));
+        assert!(matches!(
+            from_header_with_options(&header, &options),
+            Err(error::Error::ColumnCollision(first, second, first_source, second_source))
+                if first == "info_ab" && second == "info_ab" && first_source == "INFO/AB" && second_source == "INFO/ab"
+        ));
+
+        let mut reader = noodles::vcf::Reader::new(
+            &b"##fileformat=VCFv4.3
+##FORMAT=<ID=C,Number=1,Type=Integer,Description=\"c\">
+##FORMAT=<ID=b_C,Number=1,Type=Integer,Description=\"b c\">
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\ta_b\ta
+"[..],
+        );
+        let header: noodles::vcf::Header = reader.read_header().unwrap();
+
+        assert!(matches!(
+            from_header_with_options(&header, &options::Options::new()),
+            Err(error::Error::ColumnCollision(first, second, first_source, second_source))
+                if first == "format_a_b_C" && second == "format_a_b_C" && first_source == "FORMAT/C of sample a_b" && second_source == "FORMAT/b_C of sample a"
+        ));
+        assert!(from_header_with_options(
+            &header,
+            &options::Options::new().set_naming(
+                options::Naming::new().set_format_template("format_{sample}__{key}".to_string())
+            )
+        )
+        .is_ok());
+        assert!(matches!(
+            from_header_with_options(
+                &header,
+                &options::Options::new()
+                    .set_naming(options::Naming::new().set_info_template("info".to_string()))
+            ),
+            Err(error::Error::NamingTemplate(_))
+        ));
+    }
+
+    #[test]
+    fn type_hint_cols() {
+        let mut reader = noodles::vcf::Reader::new(VCF_FILE);
+
+        let header: noodles::vcf::Header = reader.read_header().unwrap();
+
+        let options = options::Options::new()
+            .set_info_float64(true)
+            .set_type_hints(vec![
+                "INFO/Info_G=int16".parse().unwrap(),
+                "FORMAT/Format_fixed=float16".parse().unwrap(),
+            ]);
+        let schema = from_header_with_options(&header, &options).unwrap();
+
+        assert_eq!(
+            schema.field_with_name("info_Info1").unwrap().data_type(),
+            &arrow::datatypes::DataType::Float64
+        );
+        assert_eq!(
+            schema.field_with_name("info_Info_G").unwrap().data_type(),
+            &arrow::datatypes::DataType::List(Arc::new(arrow::datatypes::Field::new(
+                "info_Info_G",
+                arrow::datatypes::DataType::Int16,
+                false,
+            )))
+        );
+        assert_eq!(
+            schema
+                .field_with_name("format_first_Format_fixed")
+                .unwrap()
+                .data_type(),
+            &arrow::datatypes::DataType::List(Arc::new(arrow::datatypes::Field::new(
+                "format_first_Format_fixed",
+                arrow::datatypes::DataType::Float16,
+                true,
+            )))
+        );
+
+        let options =
+            options::Options::new().set_type_hints(vec!["INFO/Info1=int8".parse().unwrap()]);
+        assert!(matches!(
+            from_header_with_options(&header, &options),
+            Err(error::Error::TypeHint(_))
+        ));
+    }
+
+    #[test]
+    fn compact() {
+        let vcf = b"##fileformat=VCFv4.3
+##INFO=<ID=Small,Number=1,Type=Integer,Description=\"small\">
+##INFO=<ID=Medium,Number=.,Type=Integer,Description=\"medium\">
+##INFO=<ID=Large,Number=1,Type=Integer,Description=\"large\">
+##INFO=<ID=Absent,Number=1,Type=Integer,Description=\"absent\">
+##INFO=<ID=Hint,Number=1,Type=Integer,Description=\"hint\">
+##FORMAT=<ID=DP,Number=1,Type=Integer,Description=\"depth\">
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO\tFORMAT\tfirst
+1\t1\t.\tA\tC\t.\t.\tSmall=-5;Medium=1,300;Large=70000;Hint=1\tDP\t42
+1\t2\t.\tA\tC\t.\t.\tSmall=127;Medium=-300\tDP\t128
+";
+        let mut reader = noodles::vcf::Reader::new(&vcf[..]);
+        let header: noodles::vcf::Header = reader.read_header().unwrap();
+        let mut range = IntegerRange::new();
+        for record in reader.records(&header) {
+            range.add_record(&header, &record.unwrap());
+        }
+
+        let options =
+            options::Options::new().set_type_hints(vec!["INFO/Hint=int32".parse().unwrap()]);
+        let hints = range.hints(&header, &options);
+
+        assert_eq!(
+            hints,
+            vec![
+                options::TypeHint::new(
+                    options::Section::Info,
+                    "Small".to_string(),
+                    options::Width::Int8
+                ),
+                options::TypeHint::new(
+                    options::Section::Info,
+                    "Medium".to_string(),
+                    options::Width::Int16
+                ),
+                options::TypeHint::new(
+                    options::Section::Info,
+                    "Large".to_string(),
+                    options::Width::Int32
+                ),
+                options::TypeHint::new(
+                    options::Section::Format,
+                    "DP".to_string(),
+                    options::Width::Int16
+                ),
+            ]
+        );
+    }
 }
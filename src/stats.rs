@@ -0,0 +1,290 @@
+//! Progress reporting and conversion statistics
+
+/* std use */
+use std::sync::atomic::Ordering;
+
+/* crate use */
+
+/* project use */
+use crate::sampleqc;
+
+/// Shared counter of bytes read in input, used to report progress
+#[derive(Debug, Clone, Default)]
+pub struct Progress {
+    bytes: std::sync::Arc<std::sync::atomic::AtomicU64>,
+    total: Option<u64>,
+}
+
+impl Progress {
+    /// Create a new Progress, `total` is size of input if known
+    pub fn new(total: Option<u64>) -> Self {
+        Self {
+            bytes: std::sync::Arc::new(std::sync::atomic::AtomicU64::new(0)),
+            total,
+        }
+    }
+
+    /// Wrap a reader to count bytes read
+    pub fn reader<R>(&self, inner: R) -> CountReader<R> {
+        CountReader {
+            inner,
+            bytes: self.bytes.clone(),
+        }
+    }
+
+    /// Get number of bytes read
+    pub fn bytes(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    /// Get size of input
+    pub fn total(&self) -> Option<u64> {
+        self.total
+    }
+}
+
+/// Reader that count bytes read by inner reader
+pub struct CountReader<R> {
+    inner: R,
+    bytes: std::sync::Arc<std::sync::atomic::AtomicU64>,
+}
+
+impl<R> std::io::Read for CountReader<R>
+where
+    R: std::io::Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let length = self.inner.read(buf)?;
+        self.bytes.fetch_add(length as u64, Ordering::Relaxed);
+
+        Ok(length)
+    }
+}
+
+/// Statistics of a conversion
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct Stats {
+    records: usize,
+    rows: usize,
+    no_alt: usize,
+    filtered: usize,
+    nulled: usize,
+    skipped: usize,
+    peak_memory: usize,
+    reference_mismatch: usize,
+    bytes: Option<u64>,
+    seconds: f64,
+    chromosomes: std::collections::BTreeMap<String, usize>,
+    #[serde(skip)]
+    sample_qc: Option<sampleqc::SampleQc>,
+}
+
+impl Stats {
+    /// Create a new Stats
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count a record converted, `alleles` is the number of alternative alleles, one row is written by allele
+    pub fn add_record(&mut self, chromosome: &str, alleles: usize) {
+        self.rows += alleles;
+        if alleles == 0 {
+            self.no_alt += 1;
+        }
+
+        match self.chromosomes.get_mut(chromosome) {
+            Some(count) => *count += 1,
+            None => {
+                self.chromosomes.insert(chromosome.to_string(), 1);
+            }
+        }
+    }
+
+    /// Count a record read
+    pub fn add_read(&mut self) {
+        self.records += 1;
+    }
+
+    /// Count a record remove by region selection
+    pub fn add_filtered(&mut self) {
+        self.filtered += 1;
+    }
+
+    /// Count values replace by null
+    pub fn add_nulled(&mut self, value: usize) {
+        self.nulled += value;
+    }
+
+    /// Count a record that can't be parsed
+    pub fn add_skipped(&mut self) {
+        self.skipped += 1;
+    }
+
+    /// Update peak memory of column builders with `bytes` used by a batch
+    pub fn add_memory(&mut self, bytes: usize) {
+        self.peak_memory = self.peak_memory.max(bytes);
+    }
+
+    /// Count records with a REF that doesn't match reference
+    pub fn add_reference_mismatch(&mut self, value: usize) {
+        self.reference_mismatch += value;
+    }
+
+    /// Set per sample quality control summary, it's update by [Stats::add_sample_qc]
+    pub fn set_sample_qc(&mut self, value: Option<sampleqc::SampleQc>) {
+        self.sample_qc = value;
+    }
+
+    /// Add record in per sample quality control summary, if it's set
+    pub fn add_sample_qc(&mut self, record: &noodles::vcf::Record) {
+        if let Some(qc) = self.sample_qc.as_mut() {
+            qc.add_record(record);
+        }
+    }
+
+    /// Set number of bytes read and duration of conversion
+    pub fn set_end(&mut self, bytes: Option<u64>, seconds: f64) {
+        self.bytes = bytes;
+        self.seconds = seconds;
+    }
+
+    /// Get number of records read
+    pub fn records(&self) -> usize {
+        self.records
+    }
+
+    /// Get number of rows written, after allele splitting
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Get number of records without alternative allele, they aren't written
+    pub fn no_alt(&self) -> usize {
+        self.no_alt
+    }
+
+    /// Get number of records remove by region selection
+    pub fn filtered(&self) -> usize {
+        self.filtered
+    }
+
+    /// Get number of values replace by null in lenient mode
+    pub fn nulled(&self) -> usize {
+        self.nulled
+    }
+
+    /// Get number of records that can't be parsed and are skipped in lenient mode
+    pub fn skipped(&self) -> usize {
+        self.skipped
+    }
+
+    /// Get peak memory estimate of column builders in bytes
+    pub fn peak_memory(&self) -> usize {
+        self.peak_memory
+    }
+
+    /// Get number of records with a REF that doesn't match reference
+    pub fn reference_mismatch(&self) -> usize {
+        self.reference_mismatch
+    }
+
+    /// Get number of bytes read in input
+    pub fn bytes(&self) -> Option<u64> {
+        self.bytes
+    }
+
+    /// Get duration of conversion in seconds
+    pub fn seconds(&self) -> f64 {
+        self.seconds
+    }
+
+    /// Get number of records converted by chromosome
+    pub fn chromosomes(&self) -> &std::collections::BTreeMap<String, usize> {
+        &self.chromosomes
+    }
+
+    /// Get per sample quality control summary
+    pub fn sample_qc(&self) -> Option<&sampleqc::SampleQc> {
+        self.sample_qc.as_ref()
+    }
+
+    /// Serialize statistics in json
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// Log a summary of statistics
+    pub fn log(&self) {
+        log::info!(
+            "{} records read in {:.1}s, {} rows written, {} records without alternative allele, {} records outside of regions, {} values replace by null, {} records skipped, peak memory of column builders {} bytes, {} REF mismatch with reference",
+            self.records,
+            self.seconds,
+            self.rows,
+            self.no_alt,
+            self.filtered,
+            self.nulled,
+            self.skipped,
+            self.peak_memory,
+            self.reference_mismatch
+        );
+        for (chromosome, count) in self.chromosomes.iter() {
+            log::debug!("chromosome {}: {} records", chromosome, count);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Read as _;
+
+    #[test]
+    fn progress() {
+        let progress = Progress::new(Some(10));
+
+        let mut data = Vec::new();
+        progress
+            .reader(&b"abcdef"[..])
+            .read_to_end(&mut data)
+            .unwrap();
+
+        assert_eq!(progress.bytes(), 6);
+        assert_eq!(progress.clone().bytes(), 6);
+        assert_eq!(progress.total(), Some(10));
+    }
+
+    #[test]
+    fn stats() {
+        let mut stats = Stats::new();
+
+        stats.add_read();
+        stats.add_record("1", 2);
+        stats.add_read();
+        stats.add_record("2", 0);
+        stats.add_read();
+        stats.add_filtered();
+        stats.add_read();
+        stats.add_skipped();
+        stats.add_nulled(3);
+        stats.add_memory(200);
+        stats.add_memory(100);
+        stats.add_reference_mismatch(2);
+        stats.set_end(Some(100), 1.5);
+
+        assert_eq!(stats.records(), 4);
+        assert_eq!(stats.rows(), 2);
+        assert_eq!(stats.no_alt(), 1);
+        assert_eq!(stats.filtered(), 1);
+        assert_eq!(stats.skipped(), 1);
+        assert_eq!(stats.nulled(), 3);
+        assert_eq!(stats.peak_memory(), 200);
+        assert_eq!(stats.reference_mismatch(), 2);
+        assert_eq!(stats.bytes(), Some(100));
+        assert_eq!(stats.chromosomes().get("1"), Some(&1));
+
+        let json: serde_json::Value = serde_json::from_str(&stats.to_json()).unwrap();
+        assert_eq!(json["rows"], 2);
+        assert_eq!(json["chromosomes"]["2"], 1);
+    }
+}
@@ -0,0 +1,320 @@
+//! Read vcf from and write parquet in object storage (S3, GCS, Azure or local filesystem)
+
+/* std use */
+
+/* crate use */
+use arrow::record_batch::RecordBatchReader as _;
+use futures::StreamExt as _;
+
+/* project use */
+use crate::error;
+use crate::options;
+use crate::stats;
+
+const SCHEMES: &[&str] = &[
+    "s3://",
+    "s3a://",
+    "gs://",
+    "az://",
+    "adl://",
+    "azure://",
+    "abfs://",
+    "abfss://",
+    "file://",
+    "memory://",
+];
+
+/// Return true if path is an object storage url, e.g. `s3://bucket/key.vcf.gz`
+pub fn is_url(path: &str) -> bool {
+    SCHEMES.iter().any(|s| path.starts_with(s))
+}
+
+/// Get path of object in storage
+pub fn path(url: &str) -> error::Result<object_store::path::Path> {
+    let error = |e: &dyn std::fmt::Display| error::Error::Url(url.to_string(), e.to_string());
+
+    let parsed = url::Url::parse(url).map_err(|e| error(&e))?;
+    let (_, path) = object_store::ObjectStoreScheme::parse(&parsed).map_err(|e| error(&e))?;
+
+    Ok(path)
+}
+
+/// Get object store and path of object, credentials and configuration are read from environment variables (e.g. `AWS_ACCESS_KEY_ID`, `GOOGLE_SERVICE_ACCOUNT`)
+pub fn parse(
+    url: &str,
+) -> error::Result<(
+    std::sync::Arc<dyn object_store::ObjectStore>,
+    object_store::path::Path,
+)> {
+    let url =
+        url::Url::parse(url).map_err(|e| error::Error::Url(url.to_string(), e.to_string()))?;
+
+    let (store, path) = object_store::parse_url_opts(
+        &url,
+        std::env::vars().map(|(k, v)| (k.to_ascii_lowercase(), v)),
+    )?;
+
+    Ok((store.into(), path))
+}
+
+/// Reader on an object, data are download by chunk when required
+pub struct ObjectReader {
+    runtime: tokio::runtime::Runtime,
+    stream: futures::stream::BoxStream<'static, object_store::Result<bytes::Bytes>>,
+    buffer: bytes::Bytes,
+    size: u64,
+}
+
+impl ObjectReader {
+    /// Create a new ObjectReader
+    pub fn new(
+        store: std::sync::Arc<dyn object_store::ObjectStore>,
+        path: &object_store::path::Path,
+    ) -> error::Result<Self> {
+        let runtime = runtime()?;
+        let result = runtime.block_on(store.get(path))?;
+        let size = result.meta.size as u64;
+
+        Ok(Self {
+            runtime,
+            stream: result.into_stream(),
+            buffer: bytes::Bytes::new(),
+            size,
+        })
+    }
+
+    /// Get size of object
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+impl std::io::Read for ObjectReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.buffer.is_empty() {
+            match self.runtime.block_on(self.stream.next()) {
+                Some(Ok(data)) => self.buffer = data,
+                Some(Err(e)) => return Err(std::io::Error::other(e)),
+                None => return Ok(0),
+            }
+        }
+
+        let length = buf.len().min(self.buffer.len());
+        buf[..length].copy_from_slice(&self.buffer[..length]);
+        self.buffer = self.buffer.slice(length..);
+
+        Ok(length)
+    }
+}
+
+/// Read `input` vcf and write parquet in object `path` of `store`, upload is done by part during conversion, return statistics of conversion
+pub fn vcf2parquet<R>(
+    input: &mut R,
+    store: std::sync::Arc<dyn object_store::ObjectStore>,
+    path: &object_store::path::Path,
+    batch_size: usize,
+    compression: parquet::basic::Compression,
+    options: &options::Options,
+    parquet_version: parquet::file::properties::WriterVersion,
+) -> error::Result<stats::Stats>
+where
+    R: std::io::BufRead,
+{
+    let runtime = runtime()?;
+
+    let mut chunk_iterator = crate::vcf2batches(input, batch_size, options)?;
+    let schema_ptr = chunk_iterator.schema();
+    let memory_budget = options.memory_budget();
+
+    // Parquet section
+    let options = crate::writer_properties(batch_size, compression, parquet_version);
+
+    let mut writer = parquet::arrow::AsyncArrowWriter::try_new(
+        parquet::arrow::async_writer::ParquetObjectWriter::new(store, path.clone()),
+        schema_ptr,
+        Some(options),
+    )?;
+
+    for result in chunk_iterator.by_ref() {
+        let group = result?;
+        runtime.block_on(writer.write(&group))?;
+
+        if crate::over_budget(memory_budget, writer.in_progress_size()) {
+            runtime.block_on(writer.flush())?;
+        }
+    }
+    runtime.block_on(writer.close())?;
+
+    Ok(chunk_iterator.stats().clone())
+}
+
+/// Read `input` vcf and write each row group in an object of `store` match with template url, return statistics of conversion
+pub fn vcf2multiparquet<R>(
+    input: &mut R,
+    store: std::sync::Arc<dyn object_store::ObjectStore>,
+    template: &str,
+    batch_size: usize,
+    compression: parquet::basic::Compression,
+    options: &options::Options,
+    parquet_version: parquet::file::properties::WriterVersion,
+) -> error::Result<stats::Stats>
+where
+    R: std::io::BufRead,
+{
+    let runtime = runtime()?;
+
+    let mut chunk_iterator = crate::vcf2batches(input, batch_size, options)?;
+    let schema_ptr = chunk_iterator.schema();
+
+    // Parquet section
+    let options = crate::writer_properties(batch_size, compression, parquet_version);
+
+    for (index, result) in chunk_iterator.by_ref().enumerate() {
+        let group = result?;
+        let output = path(&template.replace("{}", &index.to_string()))?;
+        let mut writer = parquet::arrow::AsyncArrowWriter::try_new(
+            parquet::arrow::async_writer::ParquetObjectWriter::new(store.clone(), output),
+            schema_ptr.clone(),
+            Some(options.clone()),
+        )?;
+
+        runtime.block_on(writer.write(&group))?;
+        runtime.block_on(writer.close())?;
+    }
+
+    Ok(chunk_iterator.stats().clone())
+}
+
+fn runtime() -> error::Result<tokio::runtime::Runtime> {
+    Ok(tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Read as _;
+
+    static VCF_FILE: &[u8] = b"##fileformat=VCFv4.3
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+1\t925952\t.\tG\tA\t.\t.\t.
+1\t925953\t.\tG\tA\t.\t.\t.
+1\t925954\t.\tG\tA\t.\t.\t.
+";
+
+    #[test]
+    fn url() {
+        assert!(is_url("s3://bucket/test.parquet"));
+        assert!(is_url("gs://bucket/test.parquet"));
+        assert!(is_url("az://container/test.parquet"));
+        assert!(!is_url("test.parquet"));
+        assert!(!is_url("-"));
+
+        assert_eq!(
+            path("s3://bucket/dir/test_0.parquet").unwrap().as_ref(),
+            "dir/test_0.parquet"
+        );
+        assert!(path("not a url").is_err());
+    }
+
+    #[test]
+    fn roundtrip() {
+        let store: std::sync::Arc<dyn object_store::ObjectStore> =
+            std::sync::Arc::new(object_store::memory::InMemory::new());
+
+        let mut expected = Vec::new();
+        crate::vcf2parquet_with_options(
+            &mut &VCF_FILE[..],
+            &mut expected,
+            2,
+            parquet::basic::Compression::UNCOMPRESSED,
+            &options::Options::new(),
+            parquet::file::properties::WriterVersion::PARQUET_2_0,
+        )
+        .unwrap();
+
+        vcf2parquet(
+            &mut &VCF_FILE[..],
+            store.clone(),
+            &object_store::path::Path::from("test.parquet"),
+            2,
+            parquet::basic::Compression::UNCOMPRESSED,
+            &options::Options::new(),
+            parquet::file::properties::WriterVersion::PARQUET_2_0,
+        )
+        .unwrap();
+
+        let mut output = Vec::new();
+        ObjectReader::new(
+            store.clone(),
+            &object_store::path::Path::from("test.parquet"),
+        )
+        .unwrap()
+        .read_to_end(&mut output)
+        .unwrap();
+
+        assert_eq!(output, expected);
+
+        vcf2multiparquet(
+            &mut &VCF_FILE[..],
+            store.clone(),
+            "memory:///split/test_{}.parquet",
+            2,
+            parquet::basic::Compression::UNCOMPRESSED,
+            &options::Options::new(),
+            parquet::file::properties::WriterVersion::PARQUET_2_0,
+        )
+        .unwrap();
+
+        for (name, rows) in [("split/test_0.parquet", 2), ("split/test_1.parquet", 1)] {
+            let mut output = Vec::new();
+            ObjectReader::new(store.clone(), &object_store::path::Path::from(name))
+                .unwrap()
+                .read_to_end(&mut output)
+                .unwrap();
+
+            let batches = parquet::arrow::arrow_reader::ParquetRecordBatchReader::try_new(
+                bytes::Bytes::from(output),
+                10,
+            )
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+            assert_eq!(batches[0].num_rows(), rows);
+        }
+    }
+
+    #[test]
+    fn memory_budget() {
+        let store: std::sync::Arc<dyn object_store::ObjectStore> =
+            std::sync::Arc::new(object_store::memory::InMemory::new());
+
+        let stats = vcf2parquet(
+            &mut &VCF_FILE[..],
+            store.clone(),
+            &object_store::path::Path::from("test.parquet"),
+            100,
+            parquet::basic::Compression::UNCOMPRESSED,
+            &options::Options::new().set_memory_budget(Some(1)),
+            parquet::file::properties::WriterVersion::PARQUET_2_0,
+        )
+        .unwrap();
+
+        let mut output = Vec::new();
+        ObjectReader::new(store, &object_store::path::Path::from("test.parquet"))
+            .unwrap()
+            .read_to_end(&mut output)
+            .unwrap();
+
+        // each record exceed budget, so each record is write in its own row group
+        let reader =
+            parquet::file::serialized_reader::SerializedFileReader::new(bytes::Bytes::from(output))
+                .unwrap();
+        assert_eq!(
+            parquet::file::reader::FileReader::metadata(&reader).num_row_groups(),
+            stats.records()
+        );
+    }
+}
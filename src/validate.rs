@@ -0,0 +1,305 @@
+//! Compare a vcf and the parquet generated from it
+
+/* std use */
+use std::hash::Hasher as _;
+
+/* crate use */
+
+/* project use */
+use crate::error;
+use crate::options;
+use crate::schema;
+
+/// Summary of a table content, computed in the same way for vcf and parquet
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Digest {
+    rows: usize,
+    columns: Vec<(String, arrow::datatypes::DataType)>,
+    chromosomes: std::collections::BTreeMap<String, usize>,
+    hashes: std::collections::BTreeMap<String, u64>,
+    spots: std::collections::BTreeMap<usize, Vec<(String, String)>>,
+}
+
+impl Digest {
+    /// Compute digest of record batches, one row every `spot` rows is keep to compare INFO and FORMAT values, 0 disable it
+    pub fn new<I>(batches: I, spot: usize) -> error::Result<Self>
+    where
+        I: Iterator<Item = Result<arrow::record_batch::RecordBatch, arrow::error::ArrowError>>,
+    {
+        let core = schema::required_column()
+            .iter()
+            .map(|f| f.name().to_string())
+            .collect::<Vec<String>>();
+        let options = arrow::util::display::FormatOptions::default().with_null("null");
+
+        let mut digest = Digest::default();
+        let mut hashers = std::collections::BTreeMap::new();
+        for batch in batches {
+            let batch = batch?;
+
+            if digest.columns.is_empty() {
+                digest.columns = batch
+                    .schema()
+                    .fields()
+                    .iter()
+                    .map(|f| (f.name().to_string(), f.data_type().clone()))
+                    .collect();
+            }
+
+            let schema = batch.schema();
+            let formatters = batch
+                .columns()
+                .iter()
+                .map(|c| arrow::util::display::ArrayFormatter::try_new(c.as_ref(), &options))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            for row in 0..batch.num_rows() {
+                let index = digest.rows + row;
+                let keep = spot != 0 && index % spot == 0;
+
+                for (field, formatter) in schema.fields().iter().zip(formatters.iter()) {
+                    let value = formatter.value(row).to_string();
+
+                    if field.name() == "chromosome" {
+                        *digest.chromosomes.entry(value.clone()).or_default() += 1;
+                    }
+
+                    if core.contains(field.name()) {
+                        hashers
+                            .entry(field.name().to_string())
+                            .or_insert_with(rustc_hash::FxHasher::default)
+                            .write(value.as_bytes());
+                    } else if keep {
+                        digest
+                            .spots
+                            .entry(index)
+                            .or_default()
+                            .push((field.name().to_string(), value));
+                    }
+                }
+            }
+
+            digest.rows += batch.num_rows();
+        }
+
+        digest.hashes = hashers.into_iter().map(|(k, h)| (k, h.finish())).collect();
+
+        Ok(digest)
+    }
+
+    /// Get number of rows
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Get number of rows by chromosome
+    pub fn chromosomes(&self) -> &std::collections::BTreeMap<String, usize> {
+        &self.chromosomes
+    }
+
+    /// List differences between two digests, `self` is the expected value
+    pub fn compare(&self, other: &Digest) -> Vec<String> {
+        let mut discrepancies = Vec::new();
+
+        if self.rows != other.rows {
+            discrepancies.push(format!(
+                "number of rows: expected {} found {}",
+                self.rows, other.rows
+            ));
+        }
+
+        if self.columns != other.columns {
+            for (name, ty) in self.columns.iter() {
+                match other.columns.iter().find(|(n, _)| n == name) {
+                    None => discrepancies.push(format!("column {}: missing", name)),
+                    Some((_, t)) if t != ty => discrepancies
+                        .push(format!("column {}: expected type {} found {}", name, ty, t)),
+                    _ => (),
+                }
+            }
+            for (name, _) in other.columns.iter() {
+                if !self.columns.iter().any(|(n, _)| n == name) {
+                    discrepancies.push(format!("column {}: not expected", name));
+                }
+            }
+        }
+
+        for chromosome in self
+            .chromosomes
+            .keys()
+            .chain(other.chromosomes.keys())
+            .collect::<std::collections::BTreeSet<_>>()
+        {
+            let expected = self.chromosomes.get(chromosome).unwrap_or(&0);
+            let found = other.chromosomes.get(chromosome).unwrap_or(&0);
+            if expected != found {
+                discrepancies.push(format!(
+                    "rows of chromosome {}: expected {} found {}",
+                    chromosome, expected, found
+                ));
+            }
+        }
+
+        for (column, hash) in self.hashes.iter() {
+            if other.hashes.get(column) != Some(hash) {
+                discrepancies.push(format!("column {}: content differ", column));
+            }
+        }
+
+        for (index, values) in self.spots.iter() {
+            let found = other.spots.get(index);
+            for (column, value) in values {
+                match found.and_then(|f| f.iter().find(|(c, _)| c == column)) {
+                    Some((_, v)) if v == value => (),
+                    Some((_, v)) => discrepancies.push(format!(
+                        "row {} column {}: expected {} found {}",
+                        index, column, value, v
+                    )),
+                    None => discrepancies.push(format!(
+                        "row {} column {}: expected {} found nothing",
+                        index, column, value
+                    )),
+                }
+            }
+        }
+
+        discrepancies
+    }
+}
+
+/// Result of a validation
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Report {
+    expected: Digest,
+    found: Digest,
+    discrepancies: Vec<String>,
+}
+
+impl Report {
+    /// Get digest of vcf
+    pub fn expected(&self) -> &Digest {
+        &self.expected
+    }
+
+    /// Get digest of parquet
+    pub fn found(&self) -> &Digest {
+        &self.found
+    }
+
+    /// Get list of discrepancies
+    pub fn discrepancies(&self) -> &[String] {
+        &self.discrepancies
+    }
+
+    /// Return true if no discrepancy is found
+    pub fn is_valid(&self) -> bool {
+        self.discrepancies.is_empty()
+    }
+}
+
+/// Read `vcf` and `parquet` side by side and compare their content, `options` must be options used to generate parquet
+pub fn validate<R, P>(
+    vcf: R,
+    parquet: P,
+    batch_size: usize,
+    options: &options::Options,
+    spot: usize,
+) -> error::Result<Report>
+where
+    R: std::io::BufRead,
+    P: parquet::file::reader::ChunkReader + 'static,
+{
+    let expected = Digest::new(crate::vcf2batches(vcf, batch_size, options)?, spot)?;
+
+    let reader = parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(parquet)?
+        .with_batch_size(batch_size)
+        .build()?;
+    let found = Digest::new(reader, spot)?;
+
+    let discrepancies = expected.compare(&found);
+
+    Ok(Report {
+        expected,
+        found,
+        discrepancies,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static VCF_FILE: &[u8] = b"##fileformat=VCFv4.3
+##INFO=<ID=XD,Number=1,Type=Integer,Description=\"depth\">
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+1\t925952\t.\tG\tA\t.\t.\tXD=1
+1\t925953\t.\tG\tA,C\t.\t.\tXD=2
+2\t925954\t.\tG\tA\t.\t.\tXD=3
+";
+
+    fn parquet(vcf: &[u8]) -> std::fs::File {
+        let mut output = tempfile::tempfile().unwrap();
+        crate::vcf2parquet_with_options(
+            &mut &vcf[..],
+            &mut output,
+            2,
+            parquet::basic::Compression::UNCOMPRESSED,
+            &options::Options::new(),
+            parquet::file::properties::WriterVersion::PARQUET_2_0,
+        )
+        .unwrap();
+
+        output
+    }
+
+    #[test]
+    fn valid() {
+        let report = validate(VCF_FILE, parquet(VCF_FILE), 3, &options::Options::new(), 1).unwrap();
+
+        assert!(report.is_valid());
+        assert_eq!(report.expected().rows(), 4);
+        assert_eq!(report.found().chromosomes().get("1"), Some(&3));
+    }
+
+    #[test]
+    fn invalid() {
+        let other = b"##fileformat=VCFv4.3
+##INFO=<ID=XD,Number=1,Type=Integer,Description=\"depth\">
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+1\t925952\t.\tG\tA\t.\t.\tXD=1
+1\t925953\t.\tG\tA,C\t.\t.\tXD=4
+2\t925955\t.\tG\tA\t.\t.\tXD=3
+";
+
+        let report = validate(VCF_FILE, parquet(other), 3, &options::Options::new(), 1).unwrap();
+
+        assert!(!report.is_valid());
+        assert_eq!(
+            report.discrepancies(),
+            &[
+                "column position: content differ".to_string(),
+                "row 1 column info_XD: expected 2 found 4".to_string(),
+                "row 2 column info_XD: expected 2 found 4".to_string(),
+            ]
+        );
+
+        let other = b"##fileformat=VCFv4.3
+##INFO=<ID=XD,Number=1,Type=Integer,Description=\"depth\">
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+1\t925952\t.\tG\tA\t.\t.\tXD=1
+1\t925953\t.\tG\tA,C\t.\t.\tXD=2
+2\t925954\t.\tG\tA\t.\t.\tXD=3
+2\t925955\t.\tG\tA\t.\t.\tXD=3
+";
+
+        let report = validate(VCF_FILE, parquet(other), 3, &options::Options::new(), 0).unwrap();
+
+        assert_eq!(
+            report.discrepancies()[..2],
+            [
+                "number of rows: expected 4 found 5".to_string(),
+                "rows of chromosome 2: expected 1 found 2".to_string(),
+            ]
+        );
+    }
+}
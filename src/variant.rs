@@ -0,0 +1,516 @@
+//! Stable variant identifier, hash, allele normalization, classification and structural variant parsing
+
+/* std use */
+
+/* crate use */
+
+/* project use */
+use crate::error;
+use crate::reference;
+
+/// Build variant identifier `chromosome-position-reference-alternate`, alleles are normalized if `normalize` is true
+pub fn id(
+    chromosome: &str,
+    position: usize,
+    reference: &str,
+    alternate: &str,
+    normalize: bool,
+    fasta: Option<&reference::Reference>,
+) -> error::Result<String> {
+    if !normalize {
+        return Ok(format!(
+            "{}-{}-{}-{}",
+            chromosome, position, reference, alternate
+        ));
+    }
+
+    let (position, reference, alternate) =
+        self::normalize(chromosome, position, reference, alternate, fasta)?;
+
+    Ok(format!(
+        "{}-{}-{}-{}",
+        chromosome, position, reference, alternate
+    ))
+}
+
+/// 64 bits FNV-1a hash of variant identifier, reinterpreted as signed integer
+pub fn hash(id: &str) -> i64 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    id.bytes().fold(OFFSET, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(PRIME)
+    }) as i64
+}
+
+/// Trim shared bases of alleles and, if a reference is available, left align indels
+///
+/// Alleles are write in uppercase, symbolic alleles, breakends and spanning deletions are return unchanged.
+pub fn normalize(
+    chromosome: &str,
+    position: usize,
+    reference: &str,
+    alternate: &str,
+    fasta: Option<&reference::Reference>,
+) -> error::Result<(usize, String, String)> {
+    let mut reference = reference.as_bytes().to_ascii_uppercase();
+    let mut alternate = alternate.as_bytes().to_ascii_uppercase();
+    let mut position = position;
+
+    if !is_sequence(&reference) || !is_sequence(&alternate) || reference == alternate {
+        return Ok((
+            position,
+            String::from_utf8_lossy(&reference).to_string(),
+            String::from_utf8_lossy(&alternate).to_string(),
+        ));
+    }
+
+    // remove shared last base, when an allele become empty extend alleles with previous reference base
+    loop {
+        let extendable = fasta.is_some() && position > 1;
+        if reference.last() != alternate.last()
+            || (!extendable && (reference.len() == 1 || alternate.len() == 1))
+        {
+            break;
+        }
+
+        reference.pop();
+        alternate.pop();
+
+        if reference.is_empty() || alternate.is_empty() {
+            if let Some(fasta) = fasta {
+                position -= 1;
+                let base = fasta.fetch(chromosome, position, position)?;
+                reference.splice(0..0, base.iter().copied());
+                alternate.splice(0..0, base.iter().copied());
+            }
+        }
+    }
+
+    // remove shared first base
+    while reference.len() > 1 && alternate.len() > 1 && reference[0] == alternate[0] {
+        reference.remove(0);
+        alternate.remove(0);
+        position += 1;
+    }
+
+    Ok((
+        position,
+        String::from_utf8_lossy(&reference).to_string(),
+        String::from_utf8_lossy(&alternate).to_string(),
+    ))
+}
+
+/// Type of an alternate allele compared to reference
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Class {
+    /// Single nucleotide variant
+    Snv,
+    /// Multiple nucleotide variant, alleles with same length
+    Mnv,
+    /// Insertion
+    Insertion,
+    /// Deletion
+    Deletion,
+    /// Length and sequence change
+    Complex,
+    /// Symbolic allele, e.g. <DEL>, or spanning deletion *
+    Symbolic,
+    /// Breakend
+    Breakend,
+}
+
+impl Class {
+    /// Get name of class, write in variant_type column
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Class::Snv => "SNV",
+            Class::Mnv => "MNV",
+            Class::Insertion => "insertion",
+            Class::Deletion => "deletion",
+            Class::Complex => "complex",
+            Class::Symbolic => "symbolic",
+            Class::Breakend => "breakend",
+        }
+    }
+
+    /// Check if class is a structural variant, symbolic allele or breakend
+    pub fn is_structural(&self) -> bool {
+        matches!(self, Class::Symbolic | Class::Breakend)
+    }
+}
+
+/// Classification of an alternate allele
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Classification {
+    /// Type of variant
+    pub class: Class,
+    /// Length of alternate minus length of reference, None for structural variant
+    pub length_change: Option<i32>,
+    /// True for transition, false for transversion, None if it's not a SNV of A, C, G or T
+    pub transition: Option<bool>,
+}
+
+/// Classify an alternate allele, shared bases of alleles are trimmed before
+pub fn classify(reference: &str, alternate: &str) -> Classification {
+    let class = if alternate.contains(['[', ']'])
+        || (alternate.len() > 1 && (alternate.starts_with('.') || alternate.ends_with('.')))
+    {
+        Class::Breakend
+    } else if alternate.starts_with('<') || alternate == "*" {
+        Class::Symbolic
+    } else {
+        Class::Snv
+    };
+
+    if class.is_structural() {
+        return Classification {
+            class,
+            length_change: None,
+            transition: None,
+        };
+    }
+
+    // without fasta normalization can't fail
+    let (_, reference, alternate) = normalize("", 1, reference, alternate, None).unwrap_or((
+        1,
+        reference.to_string(),
+        alternate.to_string(),
+    ));
+
+    let class = if reference.len() == alternate.len() {
+        if reference.len() == 1 {
+            Class::Snv
+        } else {
+            Class::Mnv
+        }
+    } else if reference.len() == 1 && alternate.starts_with(&reference) {
+        Class::Insertion
+    } else if alternate.len() == 1 && reference.starts_with(&alternate) {
+        Class::Deletion
+    } else {
+        Class::Complex
+    };
+
+    let transition = match (class, reference.as_bytes(), alternate.as_bytes()) {
+        (Class::Snv, [r], [a]) if b"ACGT".contains(r) && b"ACGT".contains(a) => Some(matches!(
+            (r, a),
+            (b'A', b'G') | (b'G', b'A') | (b'C', b'T') | (b'T', b'C')
+        )),
+        _ => None,
+    };
+
+    Classification {
+        class,
+        length_change: Some(alternate.len() as i32 - reference.len() as i32),
+        transition,
+    }
+}
+
+/// Structural variant description, build from symbolic allele or breakend and INFO fields
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Structural {
+    /// ID of symbolic allele, e.g. DUP:TANDEM, INFO/SVTYPE or BND for breakend
+    pub sv_type: Option<String>,
+    /// INFO/SVLEN of allele
+    pub length: Option<i32>,
+    /// INFO/END or position plus absolute value of SVLEN, None for insertions and breakends
+    pub end: Option<i32>,
+    /// Chromosome of breakend mate
+    pub mate_chromosome: Option<String>,
+    /// Position of breakend mate
+    pub mate_position: Option<i32>,
+    /// Breakend notation, `t[p[`, `t]p]`, `]p]t`, `[p[t`, `.t` or `t.`
+    pub orientation: Option<&'static str>,
+    /// Confidence interval around position, INFO/CIPOS
+    pub cipos: Option<(i32, i32)>,
+    /// Confidence interval around end, INFO/CIEND
+    pub ciend: Option<(i32, i32)>,
+}
+
+/// Parse structural variant of alternate allele `alt_id`, return None if allele isn't symbolic, a breakend or INFO/SVTYPE isn't set
+pub fn structural(
+    position: usize,
+    alternate: &str,
+    alt_id: usize,
+    info: &noodles::vcf::record::Info,
+) -> Option<Structural> {
+    use noodles::vcf::record::info::field::key;
+
+    let symbolic = alternate
+        .strip_prefix('<')
+        .and_then(|a| a.strip_suffix('>'));
+    let breakend = breakend(alternate);
+    let info_type = match info.get(&key::SV_TYPE) {
+        Some(Some(noodles::vcf::record::info::field::Value::String(value))) => Some(value.clone()),
+        _ => None,
+    };
+
+    if symbolic.is_none() && breakend.is_none() && info_type.is_none() {
+        return None;
+    }
+
+    let sv_type = symbolic
+        .map(|s| s.to_string())
+        .or(info_type)
+        .or_else(|| breakend.as_ref().map(|_| "BND".to_string()));
+    let length = integers(info, &key::SV_LENGTHS)
+        .get(alt_id)
+        .copied()
+        .flatten();
+    let end = integers(info, &key::END_POSITION)
+        .first()
+        .copied()
+        .flatten()
+        .or_else(|| {
+            let insertion = sv_type.as_deref().is_some_and(|t| t.starts_with("INS"));
+            match (length, insertion || breakend.is_some()) {
+                (Some(length), false) => Some(position as i32 + length.abs()),
+                _ => None,
+            }
+        });
+    let interval = |k| match integers(info, k)[..] {
+        [Some(start), Some(end)] => Some((start, end)),
+        _ => None,
+    };
+
+    let (mate_chromosome, mate_position, orientation) = match breakend {
+        Some((chromosome, position, orientation)) => (chromosome, position, Some(orientation)),
+        None => (None, None, None),
+    };
+
+    Some(Structural {
+        sv_type,
+        length,
+        end,
+        mate_chromosome,
+        mate_position,
+        orientation,
+        cipos: interval(&key::POSITION_CONFIDENCE_INTERVALS),
+        ciend: interval(&key::END_CONFIDENCE_INTERVALS),
+    })
+}
+
+/// Parse a breakend allele in mate chromosome, mate position and notation
+fn breakend(allele: &str) -> Option<(Option<String>, Option<i32>, &'static str)> {
+    if allele.len() > 1 && allele.starts_with('.') {
+        return Some((None, None, ".t"));
+    }
+    if allele.len() > 1 && allele.ends_with('.') {
+        return Some((None, None, "t."));
+    }
+
+    let first = allele.find(['[', ']'])?;
+    let bracket = allele[first..].chars().next()?;
+    let last = allele.rfind(bracket).filter(|l| *l > first)?;
+
+    let orientation = match (bracket, first == 0) {
+        ('[', false) => "t[p[",
+        (']', false) => "t]p]",
+        (']', true) => "]p]t",
+        _ => "[p[t",
+    };
+
+    let (chromosome, position) = allele[first + 1..last].rsplit_once(':')?;
+    let chromosome = chromosome
+        .strip_prefix('<')
+        .and_then(|c| c.strip_suffix('>'))
+        .unwrap_or(chromosome);
+
+    Some((
+        Some(chromosome.to_string()),
+        position.parse().ok(),
+        orientation,
+    ))
+}
+
+fn integers(
+    info: &noodles::vcf::record::Info,
+    key: &noodles::vcf::record::info::field::Key,
+) -> Vec<Option<i32>> {
+    match info.get(key) {
+        Some(Some(noodles::vcf::record::info::field::Value::Integer(value))) => vec![Some(*value)],
+        Some(Some(noodles::vcf::record::info::field::Value::Array(
+            noodles::vcf::record::info::field::value::Array::Integer(values),
+        ))) => values.clone(),
+        _ => vec![],
+    }
+}
+
+fn is_sequence(allele: &[u8]) -> bool {
+    !allele.is_empty()
+        && allele
+            .iter()
+            .all(|b| matches!(b, b'A' | b'C' | b'G' | b'T' | b'N'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Write as _;
+
+    #[test]
+    fn identifier() {
+        assert_eq!(id("1", 10, "A", "T", false, None).unwrap(), "1-10-A-T");
+        assert_eq!(
+            id("1", 10, "ca", "cta", false, None).unwrap(),
+            "1-10-ca-cta"
+        );
+        assert_eq!(id("1", 10, "ca", "cta", true, None).unwrap(), "1-10-C-CT");
+
+        // reference value of FNV-1a
+        assert_eq!(hash(""), 0xcbf2_9ce4_8422_2325_u64 as i64);
+        assert_eq!(hash("a"), 0xaf63_dc4c_8601_ec8c_u64 as i64);
+        assert_ne!(hash("1-10-A-T"), hash("1-10-A-C"));
+    }
+
+    #[test]
+    fn trim() {
+        let norm = |pos, r: &str, a: &str| normalize("1", pos, r, a, None).unwrap();
+
+        assert_eq!(norm(10, "A", "T"), (10, "A".into(), "T".into()));
+        assert_eq!(norm(10, "ACGT", "ACCT"), (12, "G".into(), "C".into()));
+        assert_eq!(norm(10, "CAA", "CA"), (10, "CA".into(), "C".into()));
+        assert_eq!(norm(10, "GCAC", "GC"), (10, "GCA".into(), "G".into()));
+        assert_eq!(norm(10, "A", "<DEL>"), (10, "A".into(), "<DEL>".into()));
+        assert_eq!(norm(10, "A", "*"), (10, "A".into(), "*".into()));
+    }
+
+    #[test]
+    fn left_align() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        // 1-based position:  123456789
+        file.write_all(b">1\nGGCACACAT\n").unwrap();
+        let fasta = reference::Reference::from_path(file.path()).unwrap();
+
+        // deletion of a CA write at the end of repeat is move at the start
+        assert_eq!(
+            normalize("1", 7, "CAT", "T", Some(&fasta)).unwrap(),
+            (2, "GCA".into(), "G".into())
+        );
+        assert_eq!(
+            normalize("1", 6, "ACA", "A", Some(&fasta)).unwrap(),
+            (2, "GCA".into(), "G".into())
+        );
+        // insertion
+        assert_eq!(
+            normalize("1", 8, "A", "ACA", Some(&fasta)).unwrap(),
+            (2, "G".into(), "GCA".into())
+        );
+        // same representation, same identifier
+        assert_eq!(
+            id("1", 7, "CAT", "T", true, Some(&fasta)).unwrap(),
+            id("1", 4, "ACAC", "AC", true, Some(&fasta)).unwrap()
+        );
+        // snv aren't change
+        assert_eq!(
+            normalize("1", 3, "C", "T", Some(&fasta)).unwrap(),
+            (3, "C".into(), "T".into())
+        );
+        assert!(normalize("2", 3, "AC", "C", Some(&fasta)).is_err());
+    }
+
+    #[test]
+    fn classification() {
+        let class = |r, a| classify(r, a).class;
+
+        assert_eq!(class("A", "G"), Class::Snv);
+        assert_eq!(class("ACGT", "ACCT"), Class::Snv);
+        assert_eq!(class("AC", "GT"), Class::Mnv);
+        assert_eq!(class("A", "ATG"), Class::Insertion);
+        assert_eq!(class("GCAC", "GC"), Class::Deletion);
+        assert_eq!(class("AC", "T"), Class::Complex);
+        assert_eq!(class("A", "<DEL>"), Class::Symbolic);
+        assert_eq!(class("A", "*"), Class::Symbolic);
+        assert_eq!(class("G", "G]17:198982]"), Class::Breakend);
+        assert_eq!(class("G", ".G"), Class::Breakend);
+        assert_eq!(Class::Insertion.as_str(), "insertion");
+
+        assert_eq!(classify("a", "atg").length_change, Some(2));
+        assert_eq!(classify("GCAC", "GC").length_change, Some(-2));
+        assert_eq!(classify("A", "<DUP>").length_change, None);
+
+        assert_eq!(classify("A", "G").transition, Some(true));
+        assert_eq!(classify("c", "t").transition, Some(true));
+        assert_eq!(classify("A", "C").transition, Some(false));
+        assert_eq!(classify("A", "N").transition, None);
+        assert_eq!(classify("A", "AT").transition, None);
+    }
+
+    #[test]
+    fn structural_variant() {
+        use noodles::vcf::record::info::field::{key, value::Array, Value};
+
+        let info: noodles::vcf::record::Info = [
+            (key::SV_TYPE, Some(Value::String("DEL".to_string()))),
+            (
+                key::SV_LENGTHS,
+                Some(Value::Array(Array::Integer(vec![Some(-100), None]))),
+            ),
+            (
+                key::POSITION_CONFIDENCE_INTERVALS,
+                Some(Value::Array(Array::Integer(vec![Some(-10), Some(5)]))),
+            ),
+        ]
+        .into_iter()
+        .collect();
+
+        let sv = structural(1000, "<DEL:ME>", 0, &info).unwrap();
+        assert_eq!(sv.sv_type.as_deref(), Some("DEL:ME"));
+        assert_eq!(sv.length, Some(-100));
+        assert_eq!(sv.end, Some(1100));
+        assert_eq!(sv.cipos, Some((-10, 5)));
+        assert_eq!(sv.ciend, None);
+        assert_eq!(sv.orientation, None);
+
+        // allele is a sequence but INFO/SVTYPE is set
+        let sv = structural(1000, "ACGT", 1, &info).unwrap();
+        assert_eq!(sv.sv_type.as_deref(), Some("DEL"));
+        assert_eq!(sv.length, None);
+        assert_eq!(sv.end, None);
+
+        let info: noodles::vcf::record::Info = [
+            (key::END_POSITION, Some(Value::Integer(2000))),
+            (key::SV_LENGTHS, Some(Value::Integer(50))),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            structural(1000, "<DUP:TANDEM>", 0, &info).unwrap().end,
+            Some(2000)
+        );
+        assert_eq!(
+            structural(1000, "<INS>", 0, &info).unwrap().length,
+            Some(50)
+        );
+        assert!(structural(1000, "A", 0, &info).is_none());
+        assert!(structural(1000, "*", 0, &info).is_none());
+
+        let empty = noodles::vcf::record::Info::default();
+        let sv = structural(10, "N[chr2:12345[", 0, &empty).unwrap();
+        assert_eq!(sv.sv_type.as_deref(), Some("BND"));
+        assert_eq!(sv.mate_chromosome.as_deref(), Some("chr2"));
+        assert_eq!(sv.mate_position, Some(12345));
+        assert_eq!(sv.orientation, Some("t[p["));
+        assert_eq!(sv.end, None);
+
+        let sv = structural(10, "]<ctg1>:5]AC", 0, &empty).unwrap();
+        assert_eq!(sv.mate_chromosome.as_deref(), Some("ctg1"));
+        assert_eq!(sv.orientation, Some("]p]t"));
+        assert_eq!(
+            structural(10, "[13:7[G", 0, &empty).unwrap().orientation,
+            Some("[p[t")
+        );
+        assert_eq!(
+            structural(10, "G]17:198982]", 0, &empty)
+                .unwrap()
+                .orientation,
+            Some("t]p]")
+        );
+
+        let sv = structural(10, "G.", 0, &empty).unwrap();
+        assert_eq!(sv.orientation, Some("t."));
+        assert_eq!(sv.mate_chromosome, None);
+    }
+}
@@ -20,15 +20,19 @@ fn help() -> Result<(), assert_cmd::cargo::CargoError> {
 Usage: vcf2parquet.exe [OPTIONS] --input <INPUT> <COMMAND>
 
 Commands:
-  convert  Convert a vcf in a parquet
-  split    Convert a vcf in multiple parquet file each file contains `batch_size` record
-  help     Print this message or the help of the given subcommand(s)
+  convert   Convert a vcf in a parquet
+  split     Convert a vcf in multiple parquet file each file contains `batch_size` record
+  validate  Compare a vcf and the parquet generated from it, use same options as conversion
+  inspect   Print columns produce from a vcf, or row groups, columns size and metadata of a parquet
+  help      Print this message or the help of the given subcommand(s)
 
 Options:
   -i, --input <INPUT>
-          Input path
+          Input path or object storage url (s3://, gs://, az://), - read stdin
   -b, --batch-size <BATCH_SIZE>
           Batch size (default 100,000)
+  -m, --memory-budget <MEMORY_BUDGET>
+          Memory budget of column builders in MiB, batch size is reduce to fit and row group is write when budget is exceed (default no limit)
   -c, --compression <COMPRESSION>
           Compression method (default snappy) [possible values: uncompressed, snappy, gzip, lzo, brotli, lz4, zstd]
   -r, --read-buffer <READ_BUFFER>
@@ -37,6 +41,60 @@ Options:
           All information fields are optional
       --parquet-version <PARQUET_VERSION>
           Select version of parquet version default v2 [possible values: v1, v2]
+  -D, --dictionary <DICTIONARY>
+          Columns stored as dictionary of string, e.g. chromosome,info_GENE
+      --columns <COLUMNS>
+          Columns keep in output, INFO and FORMAT fields of other columns are not decoded, e.g. position,info_DP
+      --samples <SAMPLES>
+          Samples keep in output, FORMAT fields of other samples are not decoded
+      --variant-id
+          Add a variant_id column chromosome-position-reference-alternate
+      --variant-hash
+          Add a variant_hash column, 64 bits hash of variant_id
+      --normalize
+          Trim alleles before computation of variant_id and variant_hash
+      --reference <REFERENCE>
+          Fasta reference used to left align indels, imply --normalize
+      --classify
+          Add variant_type, length_change, transition, sv_type and sv_length columns
+      --sv
+          Parse symbolic alleles and breakends in sv_type, sv_length, sv_end, mate_chromosome, mate_position, orientation and CIPOS/CIEND columns
+      --context <CONTEXT>
+          Add a context column with N reference bases on each side of REF, e.g. 1 for trinucleotide context, require --reference
+      --check-reference
+          Add a reference_match column, false if REF differ from reference, require --reference
+      --fill-reference
+          Replace REF made only of N by reference bases, require --reference
+      --genotype-stats
+          Add ac, an, af, n_het, n_hom_alt, n_missing, call_rate and hwe_pvalue columns computed from genotypes of kept samples
+  -T, --type-hint <TYPE_HINTS>
+          Arrow type of numeric fields, e.g. INFO/DP=int16,FORMAT/PL=int16,INFO/AF=float64
+      --info-float64
+          Store INFO float fields in 64 bits
+      --compact-integer
+          Choose narrowest integer type of INFO and FORMAT integer fields, input is read twice (not available with stdin)
+      --schema-override <SCHEMA_OVERRIDE>
+          Toml or json file that override INFO and FORMAT header definition
+      --undeclared <UNDECLARED>
+          Policy for INFO and FORMAT keys not declared in header: error, warn or extra (default warn)
+      --lenient
+          Value that can't be convert are replace by null and unparsable records are skip, instead of stop conversion
+      --info-name <INFO_NAME>
+          Template of INFO column names, {key} is replace by key (default info_{key})
+      --format-name <FORMAT_NAME>
+          Template of FORMAT column names, {sample} and {key} are replace by sample name and key (default format_{sample}_{key})
+      --sanitize-names
+          Replace characters other than A-Z, a-z, 0-9 and _ in column names by _
+      --lowercase-names
+          Write column names in lowercase
+      --stats-json <STATS_JSON>
+          Write conversion statistics in a json file
+      --sample-qc <SAMPLE_QC>
+          Write a parquet with one row by sample: called, missing, het and hom-alt genotypes, Ti/Tv, mean DP and GQ and het rate by chromosome
+  -q, --quiet
+          Silence all output, by default warnings, progress and summary of conversion are reported
+  -v, --verbosity...
+          Verbose mode (-v, -vv, -vvv, etc)
   -h, --help
           Print help (see more with \'--help\')
   -V, --version
@@ -48,15 +106,19 @@ Options:
 Usage: vcf2parquet [OPTIONS] --input <INPUT> <COMMAND>
 
 Commands:
-  convert  Convert a vcf in a parquet
-  split    Convert a vcf in multiple parquet file each file contains `batch_size` record
-  help     Print this message or the help of the given subcommand(s)
+  convert   Convert a vcf in a parquet
+  split     Convert a vcf in multiple parquet file each file contains `batch_size` record
+  validate  Compare a vcf and the parquet generated from it, use same options as conversion
+  inspect   Print columns produce from a vcf, or row groups, columns size and metadata of a parquet
+  help      Print this message or the help of the given subcommand(s)
 
 Options:
   -i, --input <INPUT>
-          Input path
+          Input path or object storage url (s3://, gs://, az://), - read stdin
   -b, --batch-size <BATCH_SIZE>
           Batch size (default 100,000)
+  -m, --memory-budget <MEMORY_BUDGET>
+          Memory budget of column builders in MiB, batch size is reduce to fit and row group is write when budget is exceed (default no limit)
   -c, --compression <COMPRESSION>
           Compression method (default snappy) [possible values: uncompressed, snappy, gzip, lzo, brotli, lz4, zstd]
   -r, --read-buffer <READ_BUFFER>
@@ -65,6 +127,60 @@ Options:
           All information fields are optional
       --parquet-version <PARQUET_VERSION>
           Select version of parquet version default v2 [possible values: v1, v2]
+  -D, --dictionary <DICTIONARY>
+          Columns stored as dictionary of string, e.g. chromosome,info_GENE
+      --columns <COLUMNS>
+          Columns keep in output, INFO and FORMAT fields of other columns are not decoded, e.g. position,info_DP
+      --samples <SAMPLES>
+          Samples keep in output, FORMAT fields of other samples are not decoded
+      --variant-id
+          Add a variant_id column chromosome-position-reference-alternate
+      --variant-hash
+          Add a variant_hash column, 64 bits hash of variant_id
+      --normalize
+          Trim alleles before computation of variant_id and variant_hash
+      --reference <REFERENCE>
+          Fasta reference used to left align indels, imply --normalize
+      --classify
+          Add variant_type, length_change, transition, sv_type and sv_length columns
+      --sv
+          Parse symbolic alleles and breakends in sv_type, sv_length, sv_end, mate_chromosome, mate_position, orientation and CIPOS/CIEND columns
+      --context <CONTEXT>
+          Add a context column with N reference bases on each side of REF, e.g. 1 for trinucleotide context, require --reference
+      --check-reference
+          Add a reference_match column, false if REF differ from reference, require --reference
+      --fill-reference
+          Replace REF made only of N by reference bases, require --reference
+      --genotype-stats
+          Add ac, an, af, n_het, n_hom_alt, n_missing, call_rate and hwe_pvalue columns computed from genotypes of kept samples
+  -T, --type-hint <TYPE_HINTS>
+          Arrow type of numeric fields, e.g. INFO/DP=int16,FORMAT/PL=int16,INFO/AF=float64
+      --info-float64
+          Store INFO float fields in 64 bits
+      --compact-integer
+          Choose narrowest integer type of INFO and FORMAT integer fields, input is read twice (not available with stdin)
+      --schema-override <SCHEMA_OVERRIDE>
+          Toml or json file that override INFO and FORMAT header definition
+      --undeclared <UNDECLARED>
+          Policy for INFO and FORMAT keys not declared in header: error, warn or extra (default warn)
+      --lenient
+          Value that can't be convert are replace by null and unparsable records are skip, instead of stop conversion
+      --info-name <INFO_NAME>
+          Template of INFO column names, {key} is replace by key (default info_{key})
+      --format-name <FORMAT_NAME>
+          Template of FORMAT column names, {sample} and {key} are replace by sample name and key (default format_{sample}_{key})
+      --sanitize-names
+          Replace characters other than A-Z, a-z, 0-9 and _ in column names by _
+      --lowercase-names
+          Write column names in lowercase
+      --stats-json <STATS_JSON>
+          Write conversion statistics in a json file
+      --sample-qc <SAMPLE_QC>
+          Write a parquet with one row by sample: called, missing, het and hom-alt genotypes, Ti/Tv, mean DP and GQ and het rate by chromosome
+  -q, --quiet
+          Silence all output, by default warnings, progress and summary of conversion are reported
+  -v, --verbosity...
+          Verbose mode (-v, -vv, -vvv, etc)
   -h, --help
           Print help (see more with \'--help\')
   -V, --version
@@ -194,3 +310,885 @@ fn split() -> Result<(), assert_cmd::cargo::CargoError> {
 
     Ok(())
 }
+
+#[test]
+fn convert_stdin_stdout() -> Result<(), assert_cmd::cargo::CargoError> {
+    let mut cmd = assert_cmd::Command::cargo_bin("vcf2parquet")?;
+
+    let mut input = Vec::new();
+    std::fs::File::open("tests/data/test.vcf")
+        .unwrap()
+        .read_to_end(&mut input)
+        .unwrap();
+
+    cmd.args(["-I", "-i", "-", "convert", "-o", "-"])
+        .write_stdin(input);
+
+    let mut truth = Vec::new();
+    std::fs::File::open("tests/data/test.parquet")
+        .unwrap()
+        .read_to_end(&mut truth)
+        .unwrap();
+
+    let assert = cmd.assert();
+
+    assert.success().stdout(truth);
+
+    Ok(())
+}
+
+#[test]
+fn compact_integer() -> Result<(), assert_cmd::cargo::CargoError> {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let output_path = temp_dir.path().join("output.parquet");
+
+    let mut cmd = assert_cmd::Command::cargo_bin("vcf2parquet")?;
+    cmd.args([
+        "-i",
+        "tests/data/cohort.vcf",
+        "--compact-integer",
+        "convert",
+        "-o",
+        output_path.as_os_str().to_str().unwrap(),
+    ]);
+    cmd.assert().success();
+
+    let schema = parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(
+        std::fs::File::open(&output_path).unwrap(),
+    )
+    .unwrap()
+    .schema()
+    .clone();
+    assert_eq!(
+        schema.field_with_name("format_s1_DP").unwrap().data_type(),
+        &arrow::datatypes::DataType::Int8
+    );
+
+    // input can't be read twice
+    let mut cmd = assert_cmd::Command::cargo_bin("vcf2parquet")?;
+    cmd.args(["-i", "-", "--compact-integer", "convert", "-o", "-"])
+        .write_stdin(std::fs::read("tests/data/cohort.vcf").unwrap());
+    cmd.assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn info_float64() -> Result<(), assert_cmd::cargo::CargoError> {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let output_path = temp_dir.path().join("output.parquet");
+
+    let mut cmd = assert_cmd::Command::cargo_bin("vcf2parquet")?;
+    cmd.args([
+        "-I",
+        "-i",
+        "tests/data/precision.vcf",
+        "--info-float64",
+        "convert",
+        "-o",
+        output_path.as_os_str().to_str().unwrap(),
+    ]);
+    cmd.assert().success();
+
+    let batch = parquet::arrow::arrow_reader::ParquetRecordBatchReader::try_new(
+        std::fs::File::open(&output_path).unwrap(),
+        1024,
+    )
+    .unwrap()
+    .next()
+    .unwrap()
+    .unwrap();
+
+    let af = batch
+        .column_by_name("info_AF")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<arrow::array::Float64Array>()
+        .unwrap();
+    assert_eq!(af.value(0), 0.123456789012);
+    assert_eq!(af.value(2), 0.987654321);
+
+    let pv = batch
+        .column_by_name("info_PV")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<arrow::array::Float64Array>()
+        .unwrap();
+    assert_eq!(pv.value(0), 1.23456789e-12);
+
+    Ok(())
+}
+
+#[cfg(feature = "object_store")]
+#[test]
+fn convert_object_store() -> Result<(), assert_cmd::cargo::CargoError> {
+    let mut cmd = assert_cmd::Command::cargo_bin("vcf2parquet")?;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    let input = std::fs::canonicalize("tests/data/test.vcf").unwrap();
+    let parquet_path = temp_path.join("tests.parquet");
+
+    cmd.args([
+        "-I",
+        "-i",
+        &format!("file://{}", input.display()),
+        "convert",
+        "-o",
+        &format!("file://{}", parquet_path.display()),
+    ]);
+
+    let assert = cmd.assert();
+
+    assert.success();
+
+    let mut output = Vec::new();
+    std::fs::File::open(parquet_path)
+        .unwrap()
+        .read_to_end(&mut output)
+        .unwrap();
+
+    let mut truth = Vec::new();
+    std::fs::File::open("tests/data/test.parquet")
+        .unwrap()
+        .read_to_end(&mut truth)
+        .unwrap();
+
+    assert_eq!(output, truth);
+
+    Ok(())
+}
+
+#[test]
+fn stats_json() -> Result<(), assert_cmd::cargo::CargoError> {
+    let mut cmd = assert_cmd::Command::cargo_bin("vcf2parquet")?;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let temp_path = temp_dir.path();
+    let parquet_path = temp_path.join("tests.parquet");
+    let stats_path = temp_path.join("stats.json");
+
+    cmd.args([
+        "-I",
+        "--stats-json",
+        stats_path.as_os_str().to_str().unwrap(),
+        "-i",
+        "tests/data/test.vcf",
+        "convert",
+        "-o",
+        parquet_path.as_os_str().to_str().unwrap(),
+    ]);
+
+    // summary is reported by default
+    let output = cmd.assert().success().get_output().stderr.clone();
+    assert!(String::from_utf8(output)
+        .unwrap()
+        .contains("10 records read in"));
+
+    let stats: serde_json::Value =
+        serde_json::from_reader(std::fs::File::open(stats_path).unwrap()).unwrap();
+
+    assert_eq!(stats["records"], 10);
+    assert_eq!(stats["rows"], 11);
+    assert_eq!(stats["no_alt"], 0);
+    assert!(stats["peak_memory"].as_u64().unwrap() > 0);
+    assert_eq!(
+        stats["bytes"],
+        std::fs::metadata("tests/data/test.vcf").unwrap().len()
+    );
+
+    let mut cmd = assert_cmd::Command::cargo_bin("vcf2parquet")?;
+    cmd.args([
+        "-q",
+        "-I",
+        "-i",
+        "tests/data/test.vcf",
+        "convert",
+        "-o",
+        parquet_path.as_os_str().to_str().unwrap(),
+    ]);
+    let output = cmd.assert().success().get_output().stderr.clone();
+    assert!(output.is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn validate() -> Result<(), assert_cmd::cargo::CargoError> {
+    let mut cmd = assert_cmd::Command::cargo_bin("vcf2parquet")?;
+
+    cmd.args([
+        "-I",
+        "-i",
+        "tests/data/test.vcf",
+        "validate",
+        "-p",
+        "tests/data/test.parquet",
+        "-s",
+        "1",
+    ]);
+
+    let assert = cmd.assert();
+
+    assert
+        .success()
+        .stdout(&b"11 rows expected, 11 rows found\n"[..]);
+
+    let mut cmd = assert_cmd::Command::cargo_bin("vcf2parquet")?;
+
+    cmd.args([
+        "-I",
+        "-i",
+        "tests/data/test.vcf",
+        "validate",
+        "-p",
+        "tests/data/test_v1.parquet",
+    ]);
+
+    let assert = cmd.assert();
+
+    assert.success();
+
+    Ok(())
+}
+
+#[test]
+fn inspect() -> Result<(), assert_cmd::cargo::CargoError> {
+    let mut cmd = assert_cmd::Command::cargo_bin("vcf2parquet")?;
+
+    cmd.args(["-I", "-i", "tests/data/test.vcf", "inspect"]);
+
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.starts_with("name\ttype\tnullable\tsource\nchromosome\tUtf8\tfalse\tCHROM\n"));
+    assert!(output.contains("\ninfo_info_Integer_1\tInt32\ttrue\tINFO=<ID=info_Integer_1,Number=1,Type=Integer,Description=\"generated vcf info field\">\n"));
+
+    let mut cmd = assert_cmd::Command::cargo_bin("vcf2parquet")?;
+
+    cmd.args(["-i", "tests/data/test.parquet", "inspect"]);
+
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.starts_with("11 rows in 1 row groups\n"));
+    assert!(output.contains("\nchromosome\tSNAPPY\t"));
+
+    Ok(())
+}
+
+#[test]
+fn inspect_export() -> Result<(), assert_cmd::cargo::CargoError> {
+    let mut cmd = assert_cmd::Command::cargo_bin("vcf2parquet")?;
+
+    cmd.args([
+        "-I",
+        "-i",
+        "tests/data/test.vcf",
+        "inspect",
+        "-e",
+        "duckdb",
+        "-t",
+        "variant",
+    ]);
+
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.starts_with(
+        "CREATE TABLE \"variant\" (\n    \"chromosome\" VARCHAR NOT NULL,\n    \"position\" INTEGER NOT NULL,\n"
+    ));
+    assert!(output.contains("\n    \"info_info_Integer_2\" INTEGER[],\n"));
+
+    let mut cmd = assert_cmd::Command::cargo_bin("vcf2parquet")?;
+
+    cmd.args(["-i", "tests/data/test.parquet", "inspect", "-e", "bigquery"]);
+
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.starts_with("CREATE TABLE `vcf` (\n    `chromosome` STRING NOT NULL,\n"));
+
+    Ok(())
+}
+
+#[test]
+fn naming() -> Result<(), assert_cmd::cargo::CargoError> {
+    let mut cmd = assert_cmd::Command::cargo_bin("vcf2parquet")?;
+
+    cmd.args([
+        "-I",
+        "-i",
+        "tests/data/test.vcf",
+        "--info-name",
+        "i_{key}",
+        "--sanitize-names",
+        "--lowercase-names",
+        "inspect",
+    ]);
+
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains("\ni_info_integer_1\tInt32\ttrue\t"));
+    assert!(output.contains("\ni_info_integer__\t"));
+
+    // keys that differ only by case are convert with default naming
+    let vcf = b"##fileformat=VCFv4.3
+##INFO=<ID=DP,Number=1,Type=Integer,Description=\"depth\">
+##INFO=<ID=dp,Number=1,Type=Integer,Description=\"other depth\">
+#CHROM\tPOS\tID\tREF\tALT\tQUAL\tFILTER\tINFO
+1\t1\t.\tA\tC\t.\t.\tDP=1;dp=2
+";
+    let mut cmd = assert_cmd::Command::cargo_bin("vcf2parquet")?;
+    cmd.args(["-I", "-i", "-", "convert", "-o", "-"])
+        .write_stdin(&vcf[..]);
+    cmd.assert().success();
+
+    let mut cmd = assert_cmd::Command::cargo_bin("vcf2parquet")?;
+    cmd.args(["-I", "-i", "-", "--sanitize-names", "convert", "-o", "-"])
+        .write_stdin(&vcf[..]);
+    cmd.assert().failure();
+
+    Ok(())
+}
+
+#[test]
+fn selection() -> Result<(), assert_cmd::cargo::CargoError> {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let full_path = temp_dir.path().join("full.parquet");
+    let selected_path = temp_dir.path().join("selected.parquet");
+
+    let mut cmd = assert_cmd::Command::cargo_bin("vcf2parquet")?;
+    cmd.args([
+        "-I",
+        "-i",
+        "tests/data/test.vcf",
+        "convert",
+        "-o",
+        full_path.as_os_str().to_str().unwrap(),
+    ]);
+    cmd.assert().success();
+
+    let columns = [
+        "position",
+        "info_info_Float_A",
+        "format_sample_1_GT",
+        "format_sample_1_format_Integer_R",
+    ];
+    let mut cmd = assert_cmd::Command::cargo_bin("vcf2parquet")?;
+    cmd.args([
+        "-I",
+        "-i",
+        "tests/data/test.vcf",
+        "--columns",
+        &columns.join(","),
+        "convert",
+        "-o",
+        selected_path.as_os_str().to_str().unwrap(),
+    ]);
+    cmd.assert().success();
+
+    let read = |path: &std::path::Path| {
+        parquet::arrow::arrow_reader::ParquetRecordBatchReader::try_new(
+            std::fs::File::open(path).unwrap(),
+            1024,
+        )
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+    };
+    let full = read(&full_path);
+    let selected = read(&selected_path);
+
+    assert_eq!(selected.num_columns(), columns.len());
+    for column in columns {
+        assert_eq!(
+            selected.column_by_name(column).unwrap(),
+            full.column_by_name(column).unwrap()
+        );
+    }
+
+    Ok(())
+}
+
+#[test]
+fn variant_key() -> Result<(), assert_cmd::cargo::CargoError> {
+    use arrow::array::Array as _;
+
+    let mut cmd = assert_cmd::Command::cargo_bin("vcf2parquet")?;
+
+    cmd.args([
+        "-I",
+        "-i",
+        "tests/data/test.vcf",
+        "--variant-id",
+        "--variant-hash",
+        "--normalize",
+        "inspect",
+    ]);
+
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains("\nvariant_id\tUtf8\tfalse\tCHROM-POS-REF-ALT\n"));
+    assert!(output.contains("\nvariant_hash\tInt64\tfalse\tCHROM-POS-REF-ALT\n"));
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let output_path = temp_dir.path().join("output.parquet");
+
+    let mut cmd = assert_cmd::Command::cargo_bin("vcf2parquet")?;
+    cmd.args([
+        "-I",
+        "-i",
+        "tests/data/test.vcf",
+        "--variant-id",
+        "--variant-hash",
+        "convert",
+        "-o",
+        output_path.as_os_str().to_str().unwrap(),
+    ]);
+    cmd.assert().success();
+
+    let batch = parquet::arrow::arrow_reader::ParquetRecordBatchReader::try_new(
+        std::fs::File::open(&output_path).unwrap(),
+        1024,
+    )
+    .unwrap()
+    .next()
+    .unwrap()
+    .unwrap();
+
+    let ids = batch
+        .column_by_name("variant_id")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<arrow::array::StringArray>()
+        .unwrap();
+    let hashes = batch
+        .column_by_name("variant_hash")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<arrow::array::Int64Array>()
+        .unwrap();
+
+    assert_eq!(ids.len(), batch.num_rows());
+    assert!(ids.value(0).starts_with("YAR028W-"));
+    assert_ne!(hashes.value(0), hashes.value(1));
+
+    Ok(())
+}
+
+#[test]
+fn classify() -> Result<(), assert_cmd::cargo::CargoError> {
+    use arrow::array::Array as _;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let output_path = temp_dir.path().join("output.parquet");
+
+    let mut cmd = assert_cmd::Command::cargo_bin("vcf2parquet")?;
+    cmd.args([
+        "-I",
+        "-i",
+        "tests/data/test.vcf",
+        "--classify",
+        "--columns",
+        "variant_type,length_change,transition,sv_type",
+        "convert",
+        "-o",
+        output_path.as_os_str().to_str().unwrap(),
+    ]);
+    cmd.assert().success();
+
+    let batch = parquet::arrow::arrow_reader::ParquetRecordBatchReader::try_new(
+        std::fs::File::open(&output_path).unwrap(),
+        1024,
+    )
+    .unwrap()
+    .next()
+    .unwrap()
+    .unwrap();
+
+    let types = batch
+        .column_by_name("variant_type")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<arrow::array::StringArray>()
+        .unwrap();
+    let lengths = batch
+        .column_by_name("length_change")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<arrow::array::Int32Array>()
+        .unwrap();
+    let transitions = batch
+        .column_by_name("transition")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<arrow::array::BooleanArray>()
+        .unwrap();
+
+    // a>ATg
+    assert_eq!(types.value(0), "insertion");
+    assert_eq!(lengths.value(0), 2);
+    assert!(transitions.is_null(0));
+    // c>a
+    assert_eq!(types.value(5), "SNV");
+    assert!(!transitions.value(5));
+    // g>a
+    assert_eq!(types.value(7), "SNV");
+    assert!(transitions.value(7));
+
+    assert_eq!(
+        batch.column_by_name("sv_type").unwrap().null_count(),
+        batch.num_rows()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn structural_variant() -> Result<(), assert_cmd::cargo::CargoError> {
+    use arrow::array::Array as _;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let output_path = temp_dir.path().join("output.parquet");
+
+    let mut cmd = assert_cmd::Command::cargo_bin("vcf2parquet")?;
+    cmd.args([
+        "-I",
+        "-i",
+        "tests/data/sv.vcf",
+        "--sv",
+        "--columns",
+        "sv_type,sv_length,sv_end,mate_chromosome,mate_position,orientation,cipos_start,ciend_end",
+        "convert",
+        "-o",
+        output_path.as_os_str().to_str().unwrap(),
+    ]);
+    cmd.assert().success();
+
+    let batch = parquet::arrow::arrow_reader::ParquetRecordBatchReader::try_new(
+        std::fs::File::open(&output_path).unwrap(),
+        1024,
+    )
+    .unwrap()
+    .next()
+    .unwrap()
+    .unwrap();
+
+    let strings = |name: &str| {
+        batch
+            .column_by_name(name)
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow::array::StringArray>()
+            .unwrap()
+            .iter()
+            .map(|v| v.map(|s| s.to_string()))
+            .collect::<Vec<Option<String>>>()
+    };
+    let integers = |name: &str| {
+        batch
+            .column_by_name(name)
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow::array::Int32Array>()
+            .unwrap()
+            .iter()
+            .collect::<Vec<Option<i32>>>()
+    };
+
+    assert_eq!(
+        strings("sv_type"),
+        vec![
+            Some("DEL".to_string()),
+            Some("DUP:TANDEM".to_string()),
+            Some("INS".to_string()),
+            Some("BND".to_string()),
+            Some("BND".to_string()),
+            None
+        ]
+    );
+    assert_eq!(
+        integers("sv_end"),
+        vec![Some(1500), Some(5300), None, None, None, None]
+    );
+    assert_eq!(
+        strings("mate_chromosome"),
+        vec![
+            None,
+            None,
+            None,
+            Some("chr2".to_string()),
+            Some("chr1".to_string()),
+            None
+        ]
+    );
+    assert_eq!(
+        integers("mate_position"),
+        vec![None, None, None, Some(12345), Some(9000), None]
+    );
+    assert_eq!(
+        strings("orientation"),
+        vec![
+            None,
+            None,
+            None,
+            Some("t[p[".to_string()),
+            Some("]p]t".to_string()),
+            None
+        ]
+    );
+    assert_eq!(
+        integers("cipos_start"),
+        vec![Some(-10), None, None, None, None, None]
+    );
+    assert_eq!(
+        integers("ciend_end"),
+        vec![Some(5), None, None, None, None, None]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn reference_context() -> Result<(), assert_cmd::cargo::CargoError> {
+    use arrow::array::Array as _;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let output_path = temp_dir.path().join("output.parquet");
+
+    let mut cmd = assert_cmd::Command::cargo_bin("vcf2parquet")?;
+    cmd.args([
+        "-i",
+        "tests/data/reference.vcf",
+        "--context",
+        "1",
+        "inspect",
+    ]);
+    cmd.assert().failure();
+
+    let mut cmd = assert_cmd::Command::cargo_bin("vcf2parquet")?;
+    cmd.args([
+        "-v",
+        "-i",
+        "tests/data/reference.vcf",
+        "--reference",
+        "tests/data/reference.fa",
+        "--context",
+        "1",
+        "--check-reference",
+        "--fill-reference",
+        "convert",
+        "-o",
+        output_path.as_os_str().to_str().unwrap(),
+    ]);
+    let output = cmd.assert().success().get_output().stderr.clone();
+    assert!(String::from_utf8(output)
+        .unwrap()
+        .contains("1 records have a REF that doesn't match reference"));
+
+    let batch = parquet::arrow::arrow_reader::ParquetRecordBatchReader::try_new(
+        std::fs::File::open(&output_path).unwrap(),
+        1024,
+    )
+    .unwrap()
+    .next()
+    .unwrap()
+    .unwrap();
+
+    let strings = |name: &str| {
+        batch
+            .column_by_name(name)
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow::array::StringArray>()
+            .unwrap()
+            .iter()
+            .map(|v| v.map(|s| s.to_string()))
+            .collect::<Vec<Option<String>>>()
+    };
+
+    assert_eq!(
+        strings("context"),
+        vec![
+            Some("TAC".to_string()),
+            Some("CGGC".to_string()),
+            Some("CCT".to_string()),
+            Some("GGT".to_string()),
+            None
+        ]
+    );
+    assert_eq!(
+        strings("reference"),
+        vec![
+            Some("A".to_string()),
+            Some("GG".to_string()),
+            Some("A".to_string()),
+            Some("G".to_string()),
+            Some("A".to_string())
+        ]
+    );
+
+    let matches = batch
+        .column_by_name("reference_match")
+        .unwrap()
+        .as_any()
+        .downcast_ref::<arrow::array::BooleanArray>()
+        .unwrap()
+        .iter()
+        .collect::<Vec<Option<bool>>>();
+    assert_eq!(
+        matches,
+        vec![Some(true), Some(true), Some(false), Some(true), None]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn genotype_stats() -> Result<(), assert_cmd::cargo::CargoError> {
+    use arrow::array::Array as _;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let output_path = temp_dir.path().join("output.parquet");
+
+    let read = |samples: Option<&str>| {
+        let mut cmd = assert_cmd::Command::cargo_bin("vcf2parquet").unwrap();
+        cmd.args(["-i", "tests/data/cohort.vcf", "--genotype-stats"]);
+        cmd.args([
+            "--columns",
+            "ac,an,af,n_het,n_hom_alt,n_missing,call_rate,hwe_pvalue",
+        ]);
+        if let Some(samples) = samples {
+            cmd.args(["--samples", samples]);
+        }
+        cmd.args(["convert", "-o", output_path.as_os_str().to_str().unwrap()]);
+        cmd.assert().success();
+
+        parquet::arrow::arrow_reader::ParquetRecordBatchReader::try_new(
+            std::fs::File::open(&output_path).unwrap(),
+            1024,
+        )
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+    };
+    let integers = |batch: &arrow::array::RecordBatch, name: &str| {
+        batch
+            .column_by_name(name)
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow::array::Int32Array>()
+            .unwrap()
+            .values()
+            .to_vec()
+    };
+    let floats = |batch: &arrow::array::RecordBatch, name: &str| {
+        batch
+            .column_by_name(name)
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow::array::Float64Array>()
+            .unwrap()
+            .iter()
+            .collect::<Vec<Option<f64>>>()
+    };
+
+    // second record is split in two rows
+    let batch = read(None);
+    assert_eq!(integers(&batch, "ac"), vec![3, 2, 3, 0]);
+    assert_eq!(integers(&batch, "an"), vec![6, 7, 7, 8]);
+    assert_eq!(integers(&batch, "n_het"), vec![1, 2, 1, 0]);
+    assert_eq!(integers(&batch, "n_hom_alt"), vec![1, 0, 1, 0]);
+    assert_eq!(integers(&batch, "n_missing"), vec![1, 1, 1, 0]);
+    assert_eq!(
+        floats(&batch, "af"),
+        vec![Some(0.5), Some(2.0 / 7.0), Some(3.0 / 7.0), Some(0.0)]
+    );
+    assert_eq!(
+        floats(&batch, "call_rate"),
+        vec![Some(0.75), Some(0.75), Some(0.75), Some(1.0)]
+    );
+    assert_eq!(floats(&batch, "hwe_pvalue")[3], Some(1.0));
+
+    // statistics use only kept samples
+    let batch = read(Some("s1,s2"));
+    assert_eq!(batch.num_columns(), 8);
+    assert_eq!(integers(&batch, "ac"), vec![3, 2, 1, 0]);
+    assert_eq!(integers(&batch, "an"), vec![4, 4, 4, 4]);
+    assert_eq!(integers(&batch, "n_missing"), vec![0, 0, 0, 0]);
+
+    Ok(())
+}
+
+#[test]
+fn sample_qc() -> Result<(), assert_cmd::cargo::CargoError> {
+    use arrow::array::Array as _;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let output_path = temp_dir.path().join("output.parquet");
+    let qc_path = temp_dir.path().join("qc.parquet");
+
+    let mut cmd = assert_cmd::Command::cargo_bin("vcf2parquet")?;
+    cmd.args([
+        "-i",
+        "tests/data/cohort.vcf",
+        "--sample-qc",
+        qc_path.as_os_str().to_str().unwrap(),
+        "--columns",
+        "position",
+        "convert",
+        "-o",
+        output_path.as_os_str().to_str().unwrap(),
+    ]);
+    cmd.assert().success();
+
+    let batch = parquet::arrow::arrow_reader::ParquetRecordBatchReader::try_new(
+        std::fs::File::open(&qc_path).unwrap(),
+        1024,
+    )
+    .unwrap()
+    .next()
+    .unwrap()
+    .unwrap();
+
+    assert_eq!(batch.num_rows(), 4);
+
+    let integers = |name: &str| {
+        batch
+            .column_by_name(name)
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow::array::Int64Array>()
+            .unwrap()
+            .values()
+            .to_vec()
+    };
+    let floats = |name: &str| {
+        batch
+            .column_by_name(name)
+            .unwrap()
+            .as_any()
+            .downcast_ref::<arrow::array::Float64Array>()
+            .unwrap()
+            .iter()
+            .collect::<Vec<Option<f64>>>()
+    };
+
+    assert_eq!(integers("called"), vec![3, 3, 3, 1]);
+    assert_eq!(integers("missing"), vec![0, 0, 0, 2]);
+    assert_eq!(integers("het"), vec![2, 1, 0, 0]);
+    assert_eq!(integers("hom_alt"), vec![0, 1, 1, 0]);
+    assert_eq!(
+        floats("mean_dp"),
+        vec![Some(10.0), Some(12.0), Some(8.0), Some(1.5)]
+    );
+    assert_eq!(floats("mean_gq"), vec![None, None, None, None]);
+    assert_eq!(floats("ti_tv")[0], Some(2.0));
+
+    Ok(())
+}